@@ -27,6 +27,11 @@ struct Opts {
     /// The compiler mode to use, defaults to JIT.
     #[clap(short, long, default_value = "jit")]
     mode: String,
+    /// Emit information about the target file instead of compiling it. The
+    /// only supported kind is `types`, which prints the types of the
+    /// top-level variables and functions.
+    #[clap(long)]
+    emit: Option<String>,
 }
 
 fn main() {
@@ -51,6 +56,16 @@ fn main() {
         error!("Input file does not exist: {:?}", input);
         return;
     }
+    // emit the requested information, if any
+    if let Some(emit) = opts.emit {
+        match emit.to_ascii_lowercase().as_str() {
+            "types" => {
+                fluxc_main::emit_types(input).map_err(|e| error!("Error analysing: {}", e)).ok();
+            }
+            _ => error!("Unrecognized emit kind '{}'", emit),
+        }
+        return;
+    }
     // fetch compiler mode
     match opts.mode.to_ascii_lowercase().as_str() {
         "jit" => {
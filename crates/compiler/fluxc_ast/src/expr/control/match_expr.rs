@@ -7,6 +7,9 @@ pub struct Match {
     pub expr: Box<Node<Expr>>,
     /// The list of cases being tested.
     pub branches: Vec<Node<MatchBranch>>,
+    /// The value to return if no pattern is matched, given by a trailing
+    /// `else` branch.
+    pub else_value: Option<Box<Node<Expr>>>,
 }
 /// A match branch case.
 #[derive(Debug, Clone, PartialEq)]
@@ -4,13 +4,15 @@ pub(crate) mod block_expr;
 pub(crate) mod control;
 pub(crate) mod literal;
 pub(crate) mod operation;
+pub(crate) mod struct_expr;
 
 pub use block_expr::*;
 pub use control::*;
 pub use literal::*;
 pub use operation::*;
+pub use struct_expr::*;
 
-use crate::{FuncCall, Node};
+use crate::{Closure, FuncCall, MethodCall, Node};
 
 /// The enumeration of possible expression types.
 ///
@@ -36,6 +38,8 @@ pub enum Expr {
         Node<BinaryExpr>,
     ),
     UnaryExpr(Node<UnaryExpr>),
+    /// A cast expression (e.g. `x as float`).
+    Cast(Node<Cast>),
     /// A block of code (e.g. `{ /* ... */ }`).
     Block(
         /// The inner block type.
@@ -43,6 +47,8 @@ pub enum Expr {
     ),
     /// A function call expression.
     FuncCall(Node<FuncCall>),
+    /// A method call expression.
+    MethodCall(Node<MethodCall>),
     /// A conditional expression.
     Conditional(Node<Conditional>),
     /// An Unconditional loop expression.
@@ -51,6 +57,12 @@ pub enum Expr {
     While(Node<While>),
     /// A match expression.
     Match(Node<Match>),
+    /// A struct literal expression.
+    StructExpr(Node<StructExpr>),
+    /// A field access expression.
+    FieldAccess(Node<FieldAccess>),
+    /// An anonymous function expression.
+    Closure(Node<Closure>),
 }
 
 /// The identifier type.
@@ -39,6 +39,9 @@ pub enum BinaryOp {
     LogicalAnd,
     /// The logical OR operator, `||`.
     LogicalOr,
+    /// The coalescing operator, `??`, which evaluates to its left hand side
+    /// unless it is unit, and to its right hand side otherwise.
+    Coalesce,
     /// The bitwise left shift operator, `<<`.
     Shl,
     /// The bitwise right shift operator, `>>`.
@@ -116,6 +119,7 @@ impl FromStr for BinaryOp {
             "^=" => Ok(BitwiseXorEq),
             "<<=" => Ok(ShlEq),
             ">>=" => Ok(ShrEq),
+            "??" => Ok(Coalesce),
             _ => Err("invalid binary operator".into()),
         }
     }
@@ -135,7 +139,8 @@ impl BinaryOp {
             BinaryOp::Eq | BinaryOp::Ne => 12,
             BinaryOp::LogicalAnd => 13,
             BinaryOp::LogicalOr => 14,
-            _ => 15,
+            BinaryOp::Coalesce => 15,
+            _ => 16,
         }
     }
 
@@ -143,4 +148,24 @@ impl BinaryOp {
     pub const fn associativity(&self) -> Associativity {
         Associativity::Ltr
     }
+
+    /// Returns `true` if this operator assigns to its left hand side.
+    pub const fn is_assignment(&self) -> bool {
+        matches!(
+            self,
+            BinaryOp::Assign
+                | BinaryOp::PlusEq
+                | BinaryOp::MinusEq
+                | BinaryOp::MulEq
+                | BinaryOp::DivEq
+                | BinaryOp::ModEq
+                | BinaryOp::BitwiseAndEq
+                | BinaryOp::BitwiseOrEq
+                | BinaryOp::BitwiseXorEq
+                | BinaryOp::LogicalAndEq
+                | BinaryOp::LogicalOrEq
+                | BinaryOp::ShlEq
+                | BinaryOp::ShrEq
+        )
+    }
 }
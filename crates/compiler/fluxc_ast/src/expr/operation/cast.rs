@@ -0,0 +1,13 @@
+use crate::{Expr, Node, TypeExpr};
+
+/// A cast expression, e.g. `x as float`.
+///
+/// Casts convert a value to another type, such as an `int` to a `float`, or
+/// extract a member of a union, such as the `int` of an `int | string`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cast {
+    /// The expression whose value is cast.
+    pub expr: Box<Node<Expr>>,
+    /// The type the value is cast to.
+    pub ty: Node<TypeExpr>,
+}
@@ -1,7 +1,9 @@
 pub(crate) mod binary_expr;
+pub(crate) mod cast;
 pub(crate) mod unary_expr;
 
 pub use binary_expr::*;
+pub use cast::*;
 pub use unary_expr::*;
 
 /// Enum representing operator associativity.
@@ -26,6 +26,8 @@ pub enum UnaryOp {
     Index(u64),
     /// The address-of operator, `&`.
     Reference,
+    /// The mutable address-of operator, `&mut`.
+    MutReference,
     /// The bitwise not operator, `~`.
     BitwiseNot,
     /// The logical not operator, `!`.
@@ -34,6 +36,10 @@ pub enum UnaryOp {
     Dereference,
     /// The negation operator, `-`.
     Negation,
+    /// The suffix error propagation operator, `?`, which returns the `Error`
+    /// held by its operand from the enclosing function, and otherwise
+    /// evaluates to the value of its operand.
+    Propagate,
 }
 
 impl FromStr for UnaryOp {
@@ -56,9 +62,11 @@ impl FromStr for UnaryOp {
             "++" => Ok(Increment),
             "--" => Ok(Decrement),
             "&" => Ok(Reference),
+            "&mut" => Ok(MutReference),
             "~" => Ok(BitwiseNot),
             "!" => Ok(LogicalNot),
             "*" => Ok(Dereference),
+            "?" => Ok(Propagate),
             _ => Err("invalid unary operator".into()),
         }
     }
@@ -69,7 +77,7 @@ impl UnaryOp {
     pub const fn precedence(&self) -> usize {
         use UnaryOp::*;
         match self {
-            Increment | Decrement | Index(_) => 1,
+            Increment | Decrement | Index(_) | Propagate => 1,
             _ => 2,
         }
     }
@@ -79,8 +87,14 @@ impl UnaryOp {
     pub const fn associativity(&self) -> Associativity {
         use UnaryOp::*;
         match self {
-            Increment | Decrement | Index(_) => Associativity::Ltr,
+            Increment | Decrement | Index(_) | Propagate => Associativity::Ltr,
             _ => Associativity::Rtl,
         }
     }
+
+    /// Returns `true` if this operator may mutate its operand.
+    pub const fn is_mutation(&self) -> bool {
+        use UnaryOp::*;
+        matches!(self, Increment | Decrement | MutReference)
+    }
 }
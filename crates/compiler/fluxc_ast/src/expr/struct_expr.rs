@@ -0,0 +1,32 @@
+use crate::{Expr, Ident, Node};
+
+/// A struct literal expression, e.g. `Point { x: 1, y: 2 }`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructExpr {
+    /// The identifier of the struct being constructed.
+    pub ident: Node<Ident>,
+    /// The field initializers of this struct literal.
+    pub fields: Vec<Node<StructExprField>>,
+}
+
+/// A field initializer in a struct literal.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructExprField {
+    /// The identifier of the field being initialized.
+    pub ident: Node<Ident>,
+    /// The value of the field.
+    pub value: Node<Expr>,
+}
+
+/// A field access expression, e.g. `p.x`, or an optional field access, e.g.
+/// `p?.x`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldAccess {
+    /// The expression whose field is being accessed.
+    pub expr: Box<Node<Expr>>,
+    /// The identifier of the accessed field.
+    pub field: Node<Ident>,
+    /// Whether the access is optional, in which case it evaluates to unit if
+    /// the expression is unit, rather than accessing its field.
+    pub optional: bool,
+}
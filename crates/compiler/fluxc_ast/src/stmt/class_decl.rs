@@ -0,0 +1,44 @@
+//! Contains the class declaration AST data structures.
+
+use crate::{Block, FuncParam, GenericParam, Ident, Mutability, Node, StructField, TypeExpr};
+
+/// A declaration of a class.
+///
+/// Classes are record types that carry methods alongside their fields. A class
+/// declaration looks something like:
+/// ```flx
+/// class Counter {
+///     count: int
+///
+///     increment mut self -> {
+///         self.count += 1
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassDecl {
+    /// The identifier of the declared class.
+    pub ident: Node<Ident>,
+    /// The type parameters of this class.
+    pub generics: Vec<Node<GenericParam>>,
+    /// The fields of this class, in declaration order.
+    pub fields: Vec<Node<StructField>>,
+    /// The methods declared on this class.
+    pub methods: Vec<Node<MethodDecl>>,
+}
+
+/// A method declared on a class.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MethodDecl {
+    /// The identifier representing the method.
+    pub ident: Node<Ident>,
+    /// The `self` receiver of this method and its mutability. Methods without
+    /// a receiver are static methods of the class.
+    pub receiver: Option<Node<Mutability>>,
+    /// The arguments this method requires, excluding the receiver.
+    pub params: Vec<Node<FuncParam>>,
+    /// The body of the method.
+    pub body: Node<Block>,
+    /// The return type of the method.
+    pub ret_ty: Node<TypeExpr>,
+}
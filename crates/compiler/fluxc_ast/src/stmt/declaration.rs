@@ -47,5 +47,18 @@ pub enum Mutability {
 #[derive(Debug, PartialEq, Clone)]
 pub struct TypeDeclaration {
     pub ident: Node<Ident>,
+    /// The type parameters of this type.
+    pub generics: Vec<Node<GenericParam>>,
     pub value: Node<TypeExpr>,
 }
+
+/// A type parameter of a generic declaration, such as `T` in
+/// `type Pair<T> = (T, T)`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct GenericParam {
+    /// The identifier of this type parameter.
+    pub ident: Node<Ident>,
+    /// The type that the arguments of this type parameter must extend, given
+    /// with `T extends Bound`.
+    pub bound: Option<Node<TypeExpr>>,
+}
@@ -4,7 +4,7 @@
 //! - Local function declarations
 //! - External function declarations
 
-use crate::{Block, Expr, Ident, Node, TypeExpr};
+use crate::{Block, Expr, GenericParam, Ident, Mutability, Node, TypeExpr};
 
 /// An enumeration of function linkage types.
 pub enum Linkage {
@@ -13,15 +13,39 @@ pub enum Linkage {
     External,
 }
 
-/// A function call.
+/// A function call, e.g. `add 1, 2`.
 #[derive(Debug, Clone, PartialEq)]
 pub struct FuncCall {
-    /// The identifier of the function
-    pub ident: Node<Ident>,
+    /// The expression evaluating to the function being called, which is
+    /// usually the identifier of a function or of a variable holding one.
+    pub callee: Box<Node<Expr>>,
     /// Arguments being passed to the function.
     pub args: Vec<Node<Expr>>,
 }
 
+/// An anonymous function, e.g. `(x: int) -> int { x + n }`, which may refer
+/// to the variables of the scopes enclosing it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Closure {
+    /// The arguments this closure requires.
+    pub params: Vec<Node<FuncParam>>,
+    /// The return type of the closure, which is inferred if omitted.
+    pub ret_ty: Node<TypeExpr>,
+    /// The body of the closure.
+    pub body: Node<Block>,
+}
+
+/// A method call, e.g. `counter.add 1`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MethodCall {
+    /// The expression the method is being called on.
+    pub receiver: Box<Node<Expr>>,
+    /// The identifier of the method.
+    pub method: Node<Ident>,
+    /// Arguments being passed to the method.
+    pub args: Vec<Node<Expr>>,
+}
+
 /// An argument to a function call.
 #[derive(Debug, PartialEq, Clone)]
 pub struct FuncParam {
@@ -29,6 +53,9 @@ pub struct FuncParam {
     pub ident: Node<Ident>,
     /// The identifier representing the type of this argument.
     pub ty: Node<TypeExpr>,
+    /// The mutability of this argument. Arguments are immutable unless their
+    /// type is prefixed with `mut`.
+    pub mutability: Mutability,
 }
 
 /// An enum of function declaration types.
@@ -37,16 +64,23 @@ pub enum FuncDecl {
     Local {
         /// The identifier representing the function.
         ident: Node<Ident>,
+        /// The type parameters of the function.
+        generics: Vec<Node<GenericParam>>,
         /// The arguments this function requires.
         params: Vec<Node<FuncParam>>,
         /// The body of the function.
         body: Node<Block>,
         /// The identifier representing the return type of the function.
         ret_ty: Node<TypeExpr>,
+        /// Whether this function is declared with `const`, allowing it to be
+        /// called in the initializers of constants.
+        constant: bool,
     },
     Export {
         /// The identifier representing the function.
         ident: Node<Ident>,
+        /// The type parameters of the function.
+        generics: Vec<Node<GenericParam>>,
         /// The arguments this function requires.
         params: Vec<Node<FuncParam>>,
         /// The body of the function.
@@ -65,12 +99,21 @@ pub enum FuncDecl {
 }
 
 impl FuncDecl {
+    /// Return the type parameters of this function. External functions cannot
+    /// be generic.
+    pub fn generics(&self) -> &[Node<GenericParam>] {
+        match self {
+            FuncDecl::Local { generics, .. } | FuncDecl::Export { generics, .. } => generics,
+            FuncDecl::External { .. } => &[],
+        }
+    }
+
     /// Return the linkage of this function.
     pub fn linkage(&self) -> Linkage {
         match self {
-            FuncDecl::Local { ident: _, params: _, body: _, ret_ty: _ } => Linkage::Local,
-            FuncDecl::Export { ident: _, params: _, body: _, ret_ty: _ } => Linkage::Export,
-            FuncDecl::External { ident: _, params: _, ret_ty: _ } => Linkage::External,
+            FuncDecl::Local { .. } => Linkage::Local,
+            FuncDecl::Export { .. } => Linkage::Export,
+            FuncDecl::External { .. } => Linkage::External,
         }
     }
 }
@@ -0,0 +1,84 @@
+//! Contains the interface declaration AST data structures.
+
+use crate::{Block, FuncParam, Ident, MethodDecl, Mutability, Node, Primitive, TypeExpr};
+
+/// A declaration of an interface.
+///
+/// Interfaces declare a set of methods that implementing types must provide.
+/// Methods may provide a default implementation, which is inherited by
+/// implementing types that do not override it. Abstract methods must declare
+/// their return type.
+/// ```flx
+/// interface Comparable {
+///     compare self, other: Self -> int
+///
+///     less_than self, other: Self -> bool {
+///         return self.compare other < 0
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct InterfaceDecl {
+    /// The identifier of the declared interface.
+    pub ident: Node<Ident>,
+    /// The methods declared by this interface.
+    pub methods: Vec<Node<InterfaceMethod>>,
+}
+
+/// A method declared by an interface.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InterfaceMethod {
+    /// The identifier representing the method.
+    pub ident: Node<Ident>,
+    /// The `self` receiver of this method and its mutability.
+    pub receiver: Option<Node<Mutability>>,
+    /// The arguments this method requires, excluding the receiver.
+    pub params: Vec<Node<FuncParam>>,
+    /// The return type of the method.
+    pub ret_ty: Node<TypeExpr>,
+    /// The default implementation of this method, if it has one.
+    pub body: Option<Node<Block>>,
+}
+
+impl InterfaceMethod {
+    /// Return the default implementation of this method as a method
+    /// declaration of the implementing type `ty`, with `Self` in its signature
+    /// replaced by the type, or `None` if this method is abstract.
+    pub fn default_impl(&self, ty: &str) -> Option<MethodDecl> {
+        let replace_self = |node: &Node<TypeExpr>| {
+            let ty = node.value.replace(&|inner| match inner {
+                TypeExpr::Primitive(Primitive::Ref(name)) if name == "Self" => {
+                    Some(TypeExpr::Primitive(Primitive::Ref(ty.to_string())))
+                }
+                _ => None,
+            });
+            Node::new(ty, node.span.clone())
+        };
+        self.body.as_ref().map(|body| MethodDecl {
+            ident: self.ident.clone(),
+            receiver: self.receiver.clone(),
+            params: self
+                .params
+                .iter()
+                .map(|param| {
+                    let ty = replace_self(&param.value.ty);
+                    Node::new(FuncParam { ty, ..param.value.clone() }, param.span.clone())
+                })
+                .collect(),
+            body: body.clone(),
+            ret_ty: replace_self(&self.ret_ty),
+        })
+    }
+}
+
+/// An implementation of an interface for a type, e.g.
+/// `impl Comparable for Point { ... }`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImplDecl {
+    /// The identifier of the interface being implemented.
+    pub interface: Node<Ident>,
+    /// The identifier of the implementing type.
+    pub ty: Node<Ident>,
+    /// The methods implemented for the type.
+    pub methods: Vec<Node<MethodDecl>>,
+}
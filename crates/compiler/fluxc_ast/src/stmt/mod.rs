@@ -1,12 +1,18 @@
 //! Contains the statement AST data structures.
 
+pub(crate) mod class_decl;
 pub(crate) mod declaration;
 pub(crate) mod func_decl;
+pub(crate) mod interface_decl;
 pub(crate) mod module;
+pub(crate) mod struct_decl;
 
+pub use class_decl::*;
 pub use declaration::*;
 pub use func_decl::*;
+pub use interface_decl::*;
 pub use module::*;
+pub use struct_decl::*;
 
 use crate::{Expr, Node};
 
@@ -19,6 +25,14 @@ pub enum Stmt {
     TypeDeclaration(Node<TypeDeclaration>),
     /// A function declaration.
     FuncDecl(Node<FuncDecl>),
+    /// A struct declaration.
+    StructDecl(Node<StructDecl>),
+    /// A class declaration.
+    ClassDecl(Node<ClassDecl>),
+    /// An interface declaration.
+    InterfaceDecl(Node<InterfaceDecl>),
+    /// An implementation of an interface.
+    ImplDecl(Node<ImplDecl>),
     /// A function return statement.
     Return(Node<Expr>),
     /// A loop break statement.
@@ -0,0 +1,69 @@
+//! Contains the struct declaration AST data structures.
+
+use crate::{GenericParam, Ident, Node, Primitive, TypeExpr};
+
+/// A declaration of a struct type.
+///
+/// Structs are named record types with a fixed set of typed fields. A struct
+/// declaration looks something like:
+/// ```flx
+/// struct Point { x: int, y: int }
+/// ```
+/// Structs declared `extern` lay out their fields in declaration order, as C
+/// would, so that they can be passed to external functions:
+/// ```flx
+/// extern struct Pixel { r: u8, g: u8, b: u8 }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructDecl {
+    /// The identifier of the declared struct.
+    pub ident: Node<Ident>,
+    /// The type parameters of this struct.
+    pub generics: Vec<Node<GenericParam>>,
+    /// The fields of this struct, in declaration order.
+    pub fields: Vec<Node<StructField>>,
+    /// Whether this struct is declared `extern`, using the C layout.
+    pub external: bool,
+}
+
+impl StructDecl {
+    /// The name of the standard error type.
+    pub const ERROR: &'static str = "Error";
+
+    /// The declaration of the standard error type, which every module may
+    /// refer to without declaring it:
+    /// ```flx
+    /// struct Error { message: string }
+    /// ```
+    /// Functions that can fail return it alongside the values they otherwise
+    /// return, such as `int | Error`. It is not declared in any source, so its
+    /// nodes have empty spans.
+    pub fn error() -> StructDecl {
+        StructDecl {
+            ident: Node::new(Self::ERROR.to_string(), 0..0),
+            generics: vec![],
+            fields: vec![Node::new(
+                StructField {
+                    ident: Node::new("message".to_string(), 0..0),
+                    ty: Node::new(TypeExpr::Primitive(Primitive::String), 0..0),
+                },
+                0..0,
+            )],
+            external: false,
+        }
+    }
+
+    /// Return the type of values of the standard error type.
+    pub fn error_type() -> TypeExpr {
+        TypeExpr::Primitive(Primitive::Ref(Self::ERROR.to_string()))
+    }
+}
+
+/// A field in a struct declaration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructField {
+    /// The identifier of this field.
+    pub ident: Node<Ident>,
+    /// The type of this field.
+    pub ty: Node<TypeExpr>,
+}
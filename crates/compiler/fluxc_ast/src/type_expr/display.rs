@@ -0,0 +1,150 @@
+//! Implements the display of type expressions, as they would be written in
+//! source code. Types are simplified before being displayed, so that
+//! equivalent types are always displayed identically.
+
+use std::fmt::{self, Display};
+
+use super::{Operation, Primitive, Simplify, TypeExpr};
+
+/// Write a simplified primitive type.
+fn write_primitive(f: &mut fmt::Formatter<'_>, primitive: &Primitive) -> fmt::Result {
+    match primitive {
+        Primitive::Int => write!(f, "int"),
+        Primitive::IntLiteral(int) => write!(f, "{int}"),
+        Primitive::Float => write!(f, "float"),
+        Primitive::FloatLiteral(float) => write!(f, "{float:?}"),
+        Primitive::Numeric(numeric) => write!(f, "{}", numeric.name()),
+        Primitive::String => write!(f, "string"),
+        Primitive::StringLiteral(str) => write!(f, "{str:?}"),
+        Primitive::Char => write!(f, "char"),
+        Primitive::CharLiteral(c) => write!(f, "{c:?}"),
+        Primitive::Tuple(types) => {
+            write!(f, "(")?;
+            for (i, ty) in types.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                write_type(f, ty)?;
+            }
+            if types.len() == 1 {
+                write!(f, ",")?;
+            }
+            write!(f, ")")
+        }
+        Primitive::Bool => write!(f, "bool"),
+        Primitive::True => write!(f, "true"),
+        Primitive::False => write!(f, "false"),
+        Primitive::Unit => write!(f, "unit"),
+        Primitive::Any => write!(f, "any"),
+        Primitive::Never => write!(f, "never"),
+        Primitive::Ref(name) => write!(f, "{name}"),
+    }
+}
+
+/// The precedence of a type expression. Operands with a lower precedence than
+/// the operation they are a member of are wrapped in parentheses.
+fn precedence(ty: &TypeExpr) -> u8 {
+    match ty {
+        TypeExpr::Operation(Operation::Conditional(_)) | TypeExpr::Function(..) => 0,
+        TypeExpr::Operation(Operation::Union(_)) => 1,
+        TypeExpr::Operation(Operation::Intersection(_)) => 2,
+        TypeExpr::Operation(Operation::KeyOf(_)) => 3,
+        _ => 4,
+    }
+}
+
+/// Write a member of a type operation of the given precedence, wrapping it in
+/// parentheses if it binds more loosely than the operation.
+fn write_operand(f: &mut fmt::Formatter<'_>, ty: &TypeExpr, precedence_of: u8) -> fmt::Result {
+    if precedence(ty) < precedence_of {
+        write!(f, "(")?;
+        write_type(f, ty)?;
+        write!(f, ")")
+    } else {
+        write_type(f, ty)
+    }
+}
+
+/// Write a simplified type expression.
+fn write_type(f: &mut fmt::Formatter<'_>, ty: &TypeExpr) -> fmt::Result {
+    match ty {
+        TypeExpr::Primitive(primitive) => write_primitive(f, primitive),
+        TypeExpr::Operation(Operation::Union(union)) => {
+            write_operand(f, &union.lhs, 1)?;
+            write!(f, " | ")?;
+            write_operand(f, &union.rhs, 1)
+        }
+        TypeExpr::Operation(Operation::Intersection(intersection)) => {
+            write_operand(f, &intersection.lhs, 2)?;
+            write!(f, " & ")?;
+            write_operand(f, &intersection.rhs, 2)
+        }
+        TypeExpr::Operation(Operation::Array(inner, size)) => {
+            write_operand(f, inner, 4)?;
+            match size {
+                Some(size) => write!(f, "[{size}]"),
+                None => write!(f, "[]"),
+            }
+        }
+        TypeExpr::Operation(Operation::Conditional(conditional)) => {
+            write_operand(f, &conditional.check, 1)?;
+            write!(f, " extends ")?;
+            write_operand(f, &conditional.extends, 1)?;
+            write!(f, " ? ")?;
+            write_type(f, &conditional.then)?;
+            write!(f, " : ")?;
+            write_type(f, &conditional.otherwise)
+        }
+        TypeExpr::Operation(Operation::KeyOf(inner)) => {
+            write!(f, "keyof ")?;
+            write_operand(f, inner, 3)
+        }
+        TypeExpr::Infer => write!(f, "_"),
+        // circular references are written as the alias they refer to, never
+        // its expansion, so that recursive types are written finitely
+        TypeExpr::Circular(inner) => match inner.as_ref() {
+            TypeExpr::Primitive(Primitive::Ref(name)) => write!(f, "{name}"),
+            reference => write_type(f, reference),
+        },
+        TypeExpr::Generic(name, args) => {
+            write!(f, "{name}<")?;
+            for (i, ty) in args.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                write_type(f, ty)?;
+            }
+            write!(f, ">")
+        }
+        TypeExpr::Capture(name) => write!(f, "infer {name}"),
+        TypeExpr::Function(params, ret) => {
+            write!(f, "(")?;
+            for (i, ty) in params.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                write_type(f, ty)?;
+            }
+            write!(f, ") -> ")?;
+            write_type(f, ret)
+        }
+    }
+}
+
+impl Display for TypeExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_type(f, &self.simplify())
+    }
+}
+
+impl Display for Primitive {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_type(f, &self.simplify())
+    }
+}
+
+impl Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_type(f, &self.simplify())
+    }
+}
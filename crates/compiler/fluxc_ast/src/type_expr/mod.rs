@@ -1,11 +1,17 @@
+mod display;
+mod numeric;
 mod operator;
+mod order;
 mod primitive;
 
-use std::fmt::Debug;
+use std::{collections::HashMap, fmt::Debug};
 
+pub use numeric::*;
 pub use operator::*;
 pub use primitive::*;
 
+pub(crate) use order::compare;
+
 /// The root-level type expression enumeration
 #[derive(Debug, Clone)]
 pub enum TypeExpr {
@@ -17,6 +23,69 @@ pub enum TypeExpr {
     Infer,
     /// A circular type reference.
     Circular(Box<TypeExpr>),
+    /// A generic type applied to type arguments, such as `Pair<int, string>`.
+    Generic(String, Vec<TypeExpr>),
+    /// A type variable introduced by `infer Name` in the `extends` clause of a
+    /// conditional type, bound to the part of the checked type it matches.
+    Capture(String),
+    /// The type of a function, taking arguments of the given types and
+    /// returning a value of the final type, such as `(int, string) -> bool`.
+    Function(Vec<TypeExpr>, Box<TypeExpr>),
+}
+
+impl TypeExpr {
+    /// Replace every reference to a type parameter in this type with the type
+    /// argument it is mapped to. References to other types are left as-is.
+    pub fn substitute(&self, args: &HashMap<String, TypeExpr>) -> TypeExpr {
+        self.replace(&|ty| match ty {
+            TypeExpr::Primitive(Primitive::Ref(name)) => args.get(name).cloned(),
+            _ => None,
+        })
+    }
+
+    /// Rebuild this type, replacing every type for which `f` returns a
+    /// replacement. Types that are not replaced have their members replaced
+    /// in turn.
+    pub fn replace(&self, f: &impl Fn(&TypeExpr) -> Option<TypeExpr>) -> TypeExpr {
+        if let Some(ty) = f(self) {
+            return ty;
+        }
+        let replace_all = |types: &[TypeExpr]| types.iter().map(|ty| ty.replace(f)).collect();
+        match self {
+            TypeExpr::Primitive(Primitive::Tuple(types)) => {
+                TypeExpr::Primitive(Primitive::Tuple(replace_all(types)))
+            }
+            TypeExpr::Operation(Operation::Union(union)) => TypeExpr::Operation(Operation::Union(
+                Union::of(union.lhs.replace(f), union.rhs.replace(f)),
+            )),
+            TypeExpr::Operation(Operation::Intersection(intersection)) => {
+                TypeExpr::Operation(Operation::Intersection(Intersection::of(
+                    intersection.lhs.replace(f),
+                    intersection.rhs.replace(f),
+                )))
+            }
+            TypeExpr::Operation(Operation::Array(inner, size)) => {
+                TypeExpr::Operation(Operation::Array(inner.replace(f).into(), *size))
+            }
+            TypeExpr::Operation(Operation::Conditional(conditional)) => {
+                TypeExpr::Operation(Operation::Conditional(ConditionalType::of(
+                    conditional.check.replace(f),
+                    conditional.extends.replace(f),
+                    conditional.then.replace(f),
+                    conditional.otherwise.replace(f),
+                )))
+            }
+            TypeExpr::Operation(Operation::KeyOf(inner)) => {
+                TypeExpr::Operation(Operation::KeyOf(inner.replace(f).into()))
+            }
+            TypeExpr::Circular(inner) => TypeExpr::Circular(inner.replace(f).into()),
+            TypeExpr::Generic(name, types) => TypeExpr::Generic(name.clone(), replace_all(types)),
+            TypeExpr::Function(params, ret) => {
+                TypeExpr::Function(replace_all(params), ret.replace(f).into())
+            }
+            ty => ty.clone(),
+        }
+    }
 }
 
 /// Trait implemented by structures that have or represent a Flux type.
@@ -35,12 +104,11 @@ impl<T: Typed> Typed for Box<T> {
     }
 }
 
+/// Type expressions are equal if their simplified forms are identical. Since
+/// unions and intersections simplify to a canonical order, equality does not
+/// depend on the order or grouping of their members.
 impl PartialEq for TypeExpr {
     fn eq(&self, other: &Self) -> bool {
-        match (self.simplify(), other.simplify()) {
-            (TypeExpr::Primitive(a), TypeExpr::Primitive(b)) => a == b,
-            (TypeExpr::Operation(a), TypeExpr::Operation(b)) => a == b,
-            _ => false,
-        }
+        compare(&self.simplify(), &other.simplify()).is_eq()
     }
 }
@@ -0,0 +1,115 @@
+//! Contains the sized numeric type definitions.
+
+/// An enumeration of the sized numeric types. Unlike `int` and `float`, which
+/// are always 64 bits wide, these are held in exactly as many bits as their
+/// name suggests, and are used where the layout of values matters, such as
+/// when calling C libraries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Numeric {
+    /// An 8-bit signed integer.
+    I8,
+    /// A 16-bit signed integer.
+    I16,
+    /// A 32-bit signed integer.
+    I32,
+    /// A 64-bit signed integer.
+    I64,
+    /// An 8-bit unsigned integer.
+    U8,
+    /// A 16-bit unsigned integer.
+    U16,
+    /// A 32-bit unsigned integer.
+    U32,
+    /// A 64-bit unsigned integer.
+    U64,
+    /// An unsigned integer as wide as a pointer.
+    Usize,
+    /// A 32-bit floating-point number.
+    F32,
+}
+
+impl Numeric {
+    /// Every sized numeric type.
+    pub const ALL: [Numeric; 10] = [
+        Numeric::I8,
+        Numeric::I16,
+        Numeric::I32,
+        Numeric::I64,
+        Numeric::U8,
+        Numeric::U16,
+        Numeric::U32,
+        Numeric::U64,
+        Numeric::Usize,
+        Numeric::F32,
+    ];
+
+    /// Return the name of this type, as it is written in source code.
+    pub fn name(self) -> &'static str {
+        match self {
+            Numeric::I8 => "i8",
+            Numeric::I16 => "i16",
+            Numeric::I32 => "i32",
+            Numeric::I64 => "i64",
+            Numeric::U8 => "u8",
+            Numeric::U16 => "u16",
+            Numeric::U32 => "u32",
+            Numeric::U64 => "u64",
+            Numeric::Usize => "usize",
+            Numeric::F32 => "f32",
+        }
+    }
+
+    /// Find the sized numeric type with the given name.
+    pub fn from_name(name: &str) -> Option<Numeric> {
+        Numeric::ALL.into_iter().find(|numeric| numeric.name() == name)
+    }
+
+    /// Return the number of bytes values of this type are held in on a target
+    /// whose pointers are `pointer_size` bytes wide.
+    pub fn size(self, pointer_size: usize) -> usize {
+        match self {
+            Numeric::I8 | Numeric::U8 => 1,
+            Numeric::I16 | Numeric::U16 => 2,
+            Numeric::I32 | Numeric::U32 | Numeric::F32 => 4,
+            Numeric::I64 | Numeric::U64 => 8,
+            Numeric::Usize => pointer_size,
+        }
+    }
+
+    /// Return the number of bits values of this type are held in on the host,
+    /// which the JIT compiles code for.
+    fn bits(self) -> u32 {
+        self.size(std::mem::size_of::<usize>()) as u32 * 8
+    }
+
+    /// Returns true if this is a floating-point type.
+    pub fn is_float(self) -> bool {
+        self == Numeric::F32
+    }
+
+    /// Returns true if this is a signed integer type.
+    pub fn is_signed(self) -> bool {
+        matches!(self, Numeric::I8 | Numeric::I16 | Numeric::I32 | Numeric::I64)
+    }
+
+    /// Return the smallest and largest values of this type, or `None` if this
+    /// is a floating-point type.
+    pub fn range(self) -> Option<(i128, i128)> {
+        let bits = self.bits();
+        match self {
+            Numeric::F32 => None,
+            _ if self.is_signed() => Some((-(1 << (bits - 1)), (1 << (bits - 1)) - 1)),
+            _ => Some((0, (1 << bits) - 1)),
+        }
+    }
+
+    /// Wrap the given integer into the range of this integer type, keeping
+    /// its lowest bits, as a conversion between integer types does.
+    pub fn wrap(self, value: i64) -> i64 {
+        let shift = 64 - self.bits();
+        match self.is_signed() {
+            true => (value << shift) >> shift,
+            false => (((value as u64) << shift) >> shift) as i64,
+        }
+    }
+}
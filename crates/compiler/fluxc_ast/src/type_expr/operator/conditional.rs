@@ -0,0 +1,222 @@
+use std::{cell::RefCell, collections::HashMap};
+
+use crate::{
+    type_expr::compare, Extends, Operation, Primitive, Simplify, Ternary, TypeExpr, Union,
+};
+
+/// Represents a conditional type, `check extends extends ? then : otherwise`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConditionalType {
+    /// The type being checked.
+    pub check: Box<TypeExpr>,
+    /// The type the checked type is compared against, which may capture parts
+    /// of the checked type using `infer`.
+    pub extends: Box<TypeExpr>,
+    /// The type evaluated to if the checked type extends `extends`.
+    pub then: Box<TypeExpr>,
+    /// The type evaluated to otherwise.
+    pub otherwise: Box<TypeExpr>,
+}
+
+impl ConditionalType {
+    /// Creates a new conditional type.
+    pub fn of(check: TypeExpr, extends: TypeExpr, then: TypeExpr, otherwise: TypeExpr) -> Self {
+        Self {
+            check: Box::new(check),
+            extends: Box::new(extends),
+            then: Box::new(then),
+            otherwise: Box::new(otherwise),
+        }
+    }
+
+    /// Returns the names of the types captured by `infer` in the `extends`
+    /// clause of this conditional.
+    pub fn captures(&self) -> Vec<String> {
+        let captures = RefCell::new(vec![]);
+        self.extends.replace(&|ty| {
+            if let TypeExpr::Capture(name) = ty {
+                captures.borrow_mut().push(name.clone());
+            }
+            None
+        });
+        captures.into_inner()
+    }
+}
+
+/// Bind the types captured by `infer` in the given pattern to the parts of
+/// `ty` they match.
+fn capture(pattern: &TypeExpr, ty: &TypeExpr, bindings: &mut HashMap<String, TypeExpr>) {
+    match (pattern, ty) {
+        (TypeExpr::Capture(name), ty) => {
+            bindings.entry(name.clone()).or_insert_with(|| ty.clone());
+        }
+        (TypeExpr::Primitive(Primitive::Tuple(a)), TypeExpr::Primitive(Primitive::Tuple(b)))
+            if a.len() == b.len() =>
+        {
+            a.iter().zip(b).for_each(|(a, b)| capture(a, b, bindings))
+        }
+        (
+            TypeExpr::Operation(Operation::Array(a, _)),
+            TypeExpr::Operation(Operation::Array(b, _)),
+        )
+        | (TypeExpr::Operation(Operation::KeyOf(a)), TypeExpr::Operation(Operation::KeyOf(b))) => {
+            capture(a, b, bindings)
+        }
+        (TypeExpr::Generic(a, params), TypeExpr::Generic(b, args))
+            if a == b && params.len() == args.len() =>
+        {
+            params.iter().zip(args).for_each(|(a, b)| capture(a, b, bindings))
+        }
+        (TypeExpr::Function(a, x), TypeExpr::Function(b, y)) if a.len() == b.len() => {
+            a.iter().zip(b).for_each(|(a, b)| capture(a, b, bindings));
+            capture(x, y, bindings)
+        }
+        _ => (),
+    }
+}
+
+/// Evaluate the simplified conditional type for the given member of its
+/// checked type. Within the branches, the checked type refers to the member.
+fn evaluate(conditional: &ConditionalType, check: &TypeExpr) -> TypeExpr {
+    let narrow = |ty: &TypeExpr| {
+        ty.replace(&|ty| compare(ty, &conditional.check).is_eq().then(|| check.clone()))
+    };
+    let conditional = ConditionalType::of(
+        check.clone(),
+        conditional.extends.as_ref().clone(),
+        narrow(&conditional.then),
+        narrow(&conditional.otherwise),
+    );
+    let mut bindings = HashMap::new();
+    capture(&conditional.extends, check, &mut bindings);
+    // captures that match nothing in the checked type are unconstrained
+    for name in conditional.captures() {
+        bindings.entry(name).or_insert(TypeExpr::Primitive(Primitive::Any));
+    }
+    let extends = conditional.extends.replace(&|ty| match ty {
+        TypeExpr::Capture(name) => bindings.get(name).cloned(),
+        _ => None,
+    });
+    match check.extends(&extends) {
+        Ternary::True => conditional.then.substitute(&bindings).simplify(),
+        Ternary::False => conditional.otherwise.simplify(),
+        Ternary::Unknown => Operation::Conditional(conditional).into(),
+    }
+}
+
+/// Simplification of conditional types evaluates them to one of their
+/// branches, distributing over the members of the checked type:
+/// - (A | B) extends T ? X : Y = (A extends T ? X : Y) | (B extends T ? X : Y)
+/// - never extends T ? X : Y = never
+/// - A extends T ? X : Y = X, where A extends T
+/// - A extends T ? X : Y = Y, where A does not extend T
+///
+/// `bool` is distributed over as `true | false`, and within the branches, the
+/// checked type refers to the member being checked. The types captured by `infer`
+/// are substituted into the `then` branch. Conditionals that cannot be decided
+/// without further context, such as those checking a type reference, are left
+/// as they are.
+impl Simplify for ConditionalType {
+    fn simplify(&self) -> TypeExpr {
+        let conditional = ConditionalType::of(
+            self.check.simplify(),
+            self.extends.simplify(),
+            self.then.simplify(),
+            self.otherwise.simplify(),
+        );
+        let members = match conditional.check.as_ref() {
+            TypeExpr::Operation(Operation::Union(union)) => union.members(),
+            TypeExpr::Primitive(Primitive::Bool) => {
+                vec![Primitive::True.into(), Primitive::False.into()]
+            }
+            // never is the empty union
+            TypeExpr::Primitive(Primitive::Never) => vec![],
+            ty => return evaluate(&conditional, ty),
+        };
+        Union::from_members(members.iter().map(|ty| evaluate(&conditional, ty)).collect())
+            .simplify()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{ConditionalType, Operation, Primitive, Simplify, TypeExpr, Union};
+
+    /// Create the conditional type `check extends extends ? 1 : 2`.
+    fn conditional(check: TypeExpr, extends: TypeExpr) -> TypeExpr {
+        Operation::Conditional(ConditionalType::of(
+            check,
+            extends,
+            TypeExpr::Primitive(Primitive::IntLiteral(1)),
+            TypeExpr::Primitive(Primitive::IntLiteral(2)),
+        ))
+        .into()
+    }
+
+    #[test]
+    fn evaluate_conditional() {
+        // int extends int ? 1 : 2 = 1
+        assert_eq!(
+            conditional(Primitive::Int.into(), Primitive::Int.into()).simplify(),
+            TypeExpr::Primitive(Primitive::IntLiteral(1))
+        );
+        // string extends int ? 1 : 2 = 2
+        assert_eq!(
+            conditional(Primitive::String.into(), Primitive::Int.into()).simplify(),
+            TypeExpr::Primitive(Primitive::IntLiteral(2))
+        );
+        // T extends int ? 1 : 2 is left as-is
+        let unknown = conditional(Primitive::Ref("T".into()).into(), Primitive::Int.into());
+        assert_eq!(unknown.simplify().to_string(), "T extends int ? 1 : 2");
+    }
+
+    #[test]
+    fn distribute_conditional() {
+        // int | string extends int ? 1 : 2 = 1 | 2
+        let check = Union::of(Primitive::Int.into(), Primitive::String.into()).into();
+        assert_eq!(conditional(check, Primitive::Int.into()).to_string(), "1 | 2");
+        // bool extends true ? 1 : 2 = 1 | 2
+        assert_eq!(
+            conditional(Primitive::Bool.into(), Primitive::True.into()).to_string(),
+            "1 | 2"
+        );
+        // never extends int ? 1 : 2 = never
+        assert_eq!(
+            conditional(Primitive::Never.into(), Primitive::Int.into()).simplify(),
+            TypeExpr::Primitive(Primitive::Never)
+        );
+    }
+
+    #[test]
+    fn infer_captures() {
+        // (int, string) extends (infer A, infer B) ? (B, A) : never = (string, int)
+        let swap: TypeExpr = Operation::Conditional(ConditionalType::of(
+            Primitive::Tuple(vec![Primitive::Int.into(), Primitive::String.into()]).into(),
+            Primitive::Tuple(vec![TypeExpr::Capture("A".into()), TypeExpr::Capture("B".into())])
+                .into(),
+            Primitive::Tuple(vec![
+                Primitive::Ref("B".into()).into(),
+                Primitive::Ref("A".into()).into(),
+            ])
+            .into(),
+            Primitive::Never.into(),
+        ))
+        .into();
+        assert_eq!(swap.to_string(), "(string, int)");
+        // Box<1 | 2> extends Box<infer T> ? T : never = 1 | 2
+        let unbox: TypeExpr = Operation::Conditional(ConditionalType::of(
+            TypeExpr::Generic(
+                "Box".into(),
+                vec![Union::of(Primitive::IntLiteral(1).into(), Primitive::IntLiteral(2).into())
+                    .into()],
+            ),
+            TypeExpr::Generic("Box".into(), vec![TypeExpr::Capture("T".into())]),
+            Primitive::Ref("T".into()).into(),
+            Primitive::Never.into(),
+        ))
+        .into();
+        assert_eq!(unbox.to_string(), "1 | 2");
+    }
+}
@@ -0,0 +1,108 @@
+use crate::{Extends, Operation, Primitive, Simplify, TypeExpr, Union};
+
+/// Represents the difference of two types: the values of one type that are not
+/// values of the other.
+#[derive(Debug, Clone)]
+pub struct Difference {
+    pub lhs: Box<TypeExpr>,
+    pub rhs: Box<TypeExpr>,
+}
+
+impl Difference {
+    /// Creates a new difference of two types.
+    pub fn of(lhs: TypeExpr, rhs: TypeExpr) -> Self {
+        Self { lhs: Box::new(lhs), rhs: Box::new(rhs) }
+    }
+}
+
+/// Collect the members of a simplified type, treating `bool` as `true | false`
+/// and `never` as the empty union.
+fn flatten(ty: &TypeExpr, members: &mut Vec<TypeExpr>) {
+    match ty {
+        TypeExpr::Operation(Operation::Union(union)) => {
+            union.members().iter().for_each(|ty| flatten(ty, members))
+        }
+        TypeExpr::Primitive(Primitive::Bool) => {
+            members.extend([Primitive::True.into(), Primitive::False.into()])
+        }
+        TypeExpr::Primitive(Primitive::Never) => (),
+        ty => members.push(ty.clone()),
+    }
+}
+
+/// Trait for type difference.
+pub trait Exclude<B> {
+    /// Find the values of this type that are not values of `b`.
+    fn exclude(&self, b: &B) -> TypeExpr;
+}
+
+impl Exclude<TypeExpr> for TypeExpr {
+    fn exclude(&self, b: &TypeExpr) -> TypeExpr {
+        Difference::of(self.clone(), b.clone()).simplify()
+    }
+}
+
+impl From<Difference> for TypeExpr {
+    fn from(d: Difference) -> Self {
+        d.simplify()
+    }
+}
+
+/// Simplification of differences removes the members of the left-hand type
+/// that extend the right-hand type, relying on a few logical rules:
+/// - (A | B) - T = (A - T) | (B - T)
+/// - A - B = never, where A extends B (e.g. `1 - int = never`)
+/// - A - B = A, otherwise
+///
+/// As a result, `T - never = T`, `T - any = never` and `never - T = never`.
+/// `bool` is treated as `true | false`, so that `bool - true = false`. Types
+/// that cannot be represented without a value, such as `int - 1`, and members
+/// whose relation to the right-hand type cannot be decided without further
+/// context, are kept as they are.
+impl Simplify for Difference {
+    fn simplify(&self) -> TypeExpr {
+        let mut members = vec![];
+        flatten(&self.lhs.simplify(), &mut members);
+        let rhs = self.rhs.simplify();
+        members.retain(|ty| !ty.extends(&rhs).is_true());
+        Union::from_members(members).simplify()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{Exclude, Primitive, TypeExpr};
+
+    #[test]
+    fn primitive_difference() {
+        // string - string = never
+        assert_eq!(
+            TypeExpr::Primitive(Primitive::String).exclude(&TypeExpr::Primitive(Primitive::String)),
+            TypeExpr::Primitive(Primitive::Never)
+        );
+        // string - never = string
+        assert_eq!(
+            TypeExpr::Primitive(Primitive::String).exclude(&TypeExpr::Primitive(Primitive::Never)),
+            TypeExpr::Primitive(Primitive::String)
+        );
+        // string - any = never
+        assert_eq!(
+            TypeExpr::Primitive(Primitive::String).exclude(&TypeExpr::Primitive(Primitive::Any)),
+            TypeExpr::Primitive(Primitive::Never)
+        );
+        // "hello" - string = never
+        assert_eq!(
+            TypeExpr::Primitive(Primitive::StringLiteral("hello".to_string()))
+                .exclude(&TypeExpr::Primitive(Primitive::String)),
+            TypeExpr::Primitive(Primitive::Never)
+        );
+        // string - "hello" = string
+        assert_eq!(
+            TypeExpr::Primitive(Primitive::String)
+                .exclude(&TypeExpr::Primitive(Primitive::StringLiteral("hello".to_string()))),
+            TypeExpr::Primitive(Primitive::String)
+        );
+    }
+}
@@ -1,109 +1,166 @@
-use crate::{Operation, Primitive, Type, Union, Simplify};
+use crate::{Intersection, Operation, Primitive, Simplify, TypeExpr, Union};
 
-/// Trait for type extension.
-pub trait Extends<B> {
-    /// Returns `true` if this type is a subtype of the given parent type.
-    fn extends(&self, b: &B) -> Primitive;
+/// The result of a subtyping check. Checks involving types that cannot be
+/// resolved without further context, such as type references or types that
+/// are yet to be inferred, are `Unknown`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ternary {
+    /// The relation always holds.
+    True,
+    /// The relation never holds.
+    False,
+    /// The relation cannot be decided from the types alone.
+    Unknown,
 }
 
-impl Extends<Primitive> for Primitive {
-    fn extends(&self, b: &Primitive) -> Primitive {
-        match (self, b) {
-            // primitives
-            (Primitive::IntLiteral(_), Primitive::Int)
-            | (Primitive::Int, Primitive::IntLiteral(_)) => Primitive::True,
-            (Primitive::StringLiteral(_), Primitive::String)
-            | (Primitive::String, Primitive::StringLiteral(_)) => Primitive::True,
-            (Primitive::True, Primitive::Bool) | (Primitive::Bool, Primitive::True) => {
-                Primitive::True
-            }
-            (Primitive::False, Primitive::Bool) | (Primitive::Bool, Primitive::False) => {
-                Primitive::True
-            }
-            // special cases
-            // any
-            (_, Primitive::Any) => Primitive::True,
-            // never
-            (Primitive::Never, Primitive::Never) => Primitive::True,
-            (_, Primitive::Never) | (Primitive::Never, _) => Primitive::False,
-            // two literal and non-literal primitives
-            // A extends B :- A = B
-            _ => (self == b).into(),
+impl Ternary {
+    /// Returns `true` if the relation is known to hold.
+    pub fn is_true(self) -> bool {
+        self == Ternary::True
+    }
+
+    /// Returns `true` if the relation is known not to hold.
+    pub fn is_false(self) -> bool {
+        self == Ternary::False
+    }
+
+    /// Three-valued logical conjunction.
+    pub fn and(self, other: Ternary) -> Ternary {
+        match (self, other) {
+            (Ternary::False, _) | (_, Ternary::False) => Ternary::False,
+            (Ternary::True, Ternary::True) => Ternary::True,
+            _ => Ternary::Unknown,
         }
     }
-}
 
-impl Extends<Type> for Type {
-    fn extends(&self, parent: &Type) -> Primitive {
-        match (self, parent) {
-            (Type::Primitive(a), Type::Primitive(b)) => a.extends(b),
-            // primitive and an operation
-            (Type::Primitive(a), Type::Operation(b)) | (Type::Operation(b), Type::Primitive(a)) => {
-                b.extends(a)
-            }
-            // two opereations
-            (Type::Operation(a), Type::Operation(b)) => a.extends(b),
-			
+    /// Three-valued logical disjunction.
+    pub fn or(self, other: Ternary) -> Ternary {
+        match (self, other) {
+            (Ternary::True, _) | (_, Ternary::True) => Ternary::True,
+            (Ternary::False, Ternary::False) => Ternary::False,
+            _ => Ternary::Unknown,
         }
     }
 }
 
-impl Extends<Primitive> for Type {
-    fn extends(&self, b: &Primitive) -> Primitive {
-        match self {
-            Type::Primitive(a) => a.extends(b),
-            Type::Operation(a) => a.extends(b),
+impl From<bool> for Ternary {
+    fn from(b: bool) -> Self {
+        if b {
+            Ternary::True
+        } else {
+            Ternary::False
         }
     }
 }
 
-impl Extends<Primitive> for Operation {
-    fn extends(&self, b: &Primitive) -> Primitive {
-        match self {
-            Operation::Union(Union { lhs, rhs }) => {
-                if lhs.extends(b) == Primitive::True {
-                    Primitive::True
-                } else if rhs.extends(b) == Primitive::True {
-                    Primitive::True
-                } else {
-                    Primitive::False
+/// Trait for type extension.
+pub trait Extends<B> {
+    /// Returns whether this type is a subtype of the given parent type, i.e.
+    /// whether every value of this type is also a value of the parent.
+    fn extends(&self, b: &B) -> Ternary;
+}
+
+impl Extends<Primitive> for Primitive {
+    fn extends(&self, b: &Primitive) -> Ternary {
+        match (self, b) {
+            // every type extends any, and never extends every type
+            (_, Primitive::Any) | (Primitive::Never, _) => Ternary::True,
+            (_, Primitive::Never) | (Primitive::Any, _) => Ternary::False,
+            // literals extend the primitive they are a member of
+            (Primitive::IntLiteral(_), Primitive::Int)
+            | (Primitive::FloatLiteral(_), Primitive::Float)
+            | (Primitive::StringLiteral(_), Primitive::String)
+            | (Primitive::CharLiteral(_), Primitive::Char)
+            | (Primitive::True | Primitive::False, Primitive::Bool) => Ternary::True,
+            // bool is exactly true | false
+            (Primitive::Bool, Primitive::True | Primitive::False) => Ternary::False,
+            // tuples are covariant in their elements
+            (Primitive::Tuple(a), Primitive::Tuple(b)) => {
+                if a.len() != b.len() {
+                    return Ternary::False;
                 }
+                a.iter().zip(b).fold(Ternary::True, |acc, (a, b)| acc.and(a.extends(b)))
             }
-            _ => Primitive::False,
+            // references can only be compared by name without further context
+            (Primitive::Ref(a), Primitive::Ref(b)) if a == b => Ternary::True,
+            (Primitive::Ref(_), _) | (_, Primitive::Ref(_)) => Ternary::Unknown,
+            // any other pair of primitives must be identical
+            // A extends B :- A = B
+            _ => (self == b).into(),
         }
     }
 }
 
-impl Extends<Operation> for Type {
-	fn extends(&self, b: &Operation) -> Primitive {
-		match (self, b) {
-			(Type::Primitive(primitive), op) => primitive.extends(op),
-			(Type::Operation(lhs), rhs) => lhs.extends(rhs),
-		}
-	}
+impl Extends<TypeExpr> for TypeExpr {
+    fn extends(&self, parent: &TypeExpr) -> Ternary {
+        extends(&self.simplify(), &parent.simplify())
+    }
 }
 
-impl Extends<Operation> for Operation {
-    fn extends(&self, b: &Operation) -> Primitive {
-        match (self, b) {
-            (Operation::Union(a), Operation::Union(b)) => a.extends(b),
-            _ => Primitive::False,
-        }
+impl Extends<Primitive> for TypeExpr {
+    fn extends(&self, b: &Primitive) -> Ternary {
+        self.extends(&TypeExpr::Primitive(b.clone()))
     }
 }
 
-impl Extends<Operation> for Primitive {
-	fn extends(&self, b: &Operation) -> Primitive {
-		match (self, b.simplify()) {
-			(lhs, Type::Primitive(rhs)) => lhs.extends(&rhs),
-			(_, Type::Operation(_)) => todo!("primitive extends unsimplifiable operation"),
-		} 
-	}
-}
+/// Check whether the simplified type `a` extends the simplified type `b`.
+fn extends(a: &TypeExpr, b: &TypeExpr) -> Ternary {
+    use Operation::{Array, Conditional, Intersection as And, KeyOf, Union as Or};
+    use TypeExpr::{
+        Capture, Circular, Function, Generic, Infer, Operation as Op, Primitive as Prim,
+    };
 
-impl Extends<Union> for Union {
-    fn extends(&self, b: &Union) -> Primitive {
-        todo!()
+    match (a, b) {
+        // circular references are checked against the type they refer to
+        (Circular(a), b) => extends(a, b),
+        (a, Circular(b)) => extends(a, b),
+        // these hold regardless of the structure of the other type
+        (_, Prim(Primitive::Any)) | (Prim(Primitive::Never), _) => Ternary::True,
+        (Infer, _) | (_, Infer) => Ternary::Unknown,
+        // conditionals and keys left unevaluated by simplification depend on
+        // types that are not yet known
+        (Op(Conditional(_) | KeyOf(_)) | Capture(_), _)
+        | (_, Op(Conditional(_) | KeyOf(_)) | Capture(_)) => Ternary::Unknown,
+        // A | B extends T :- A extends T, B extends T
+        (Op(Or(Union { lhs, rhs })), b) => extends(lhs, b).and(extends(rhs, b)),
+        // T extends A & B :- T extends A, T extends B
+        (a, Op(And(Intersection { lhs, rhs }))) => extends(a, lhs).and(extends(a, rhs)),
+        // bool is treated as true | false when checked against a union
+        (Prim(Primitive::Bool), b @ Op(Or(_))) => {
+            extends(&Primitive::True.into(), b).and(extends(&Primitive::False.into(), b))
+        }
+        // T extends A | B :- T extends A; T extends B
+        (a, Op(Or(Union { lhs, rhs }))) => extends(a, lhs).or(extends(a, rhs)),
+        // A & B extends T :- A extends T; B extends T
+        (Op(And(Intersection { lhs, rhs })), b) => match extends(lhs, b).or(extends(rhs, b)) {
+            // the intersection may still be narrower than both of its members
+            Ternary::False => Ternary::Unknown,
+            result => result,
+        },
+        // arrays are covariant in their element type, and sized arrays extend
+        // unsized arrays of the same element type
+        (Op(Array(a, n)), Op(Array(b, m))) => {
+            let size: Ternary = (m.is_none() || n == m).into();
+            size.and(extends(a, b))
+        }
+        (Prim(a), Prim(b)) => a.extends(b),
+        // functions are contravariant in their parameters and covariant in
+        // their return type
+        (Function(a, x), Function(b, y)) => {
+            if a.len() != b.len() {
+                return Ternary::False;
+            }
+            let params = a.iter().zip(b).fold(Ternary::True, |acc, (a, b)| acc.and(extends(b, a)));
+            params.and(extends(x, y))
+        }
+        // generic types with the same arguments are identical, but otherwise
+        // depend on their declarations
+        (Generic(a, args), Generic(b, params)) if a == b && args == params => Ternary::True,
+        // references may alias array types
+        (Prim(Primitive::Ref(_)) | Generic(..), _) | (_, Prim(Primitive::Ref(_)) | Generic(..)) => {
+            Ternary::Unknown
+        }
+        _ => Ternary::False,
     }
 }
 
@@ -111,95 +168,72 @@ impl Extends<Union> for Union {
 mod tests {
     use pretty_assertions::assert_eq;
 
-    use crate::{Extends, Operation, Primitive, Type, Union};
+    use crate::{Extends, Primitive, Ternary, TypeExpr};
 
     #[test]
     fn primitive_extends_primitive() {
-        use crate::{Primitive, Type};
         // int extends int
-        assert_eq!(
-            Type::Primitive(Primitive::Int).extends(&Type::Primitive(Primitive::Int)),
-            Primitive::True
-        );
+        assert_eq!(Primitive::Int.extends(&Primitive::Int), Ternary::True);
         // 1 extends int
-        assert_eq!(
-            Type::Primitive(Primitive::IntLiteral(1)).extends(&Type::Primitive(Primitive::Int)),
-            Primitive::True
-        );
+        assert_eq!(Primitive::IntLiteral(1).extends(&Primitive::Int), Ternary::True);
+        // int extends 1
+        assert_eq!(Primitive::Int.extends(&Primitive::IntLiteral(1)), Ternary::False);
         // 1 extends 0
-        assert_eq!(
-            Type::Primitive(Primitive::IntLiteral(1))
-                .extends(&Type::Primitive(Primitive::IntLiteral(0))),
-            Primitive::False
-        );
+        assert_eq!(Primitive::IntLiteral(1).extends(&Primitive::IntLiteral(0)), Ternary::False);
         // true extends bool
-        assert_eq!(
-            Type::Primitive(Primitive::True).extends(&Type::Primitive(Primitive::Bool)),
-            Primitive::True
-        );
+        assert_eq!(Primitive::True.extends(&Primitive::Bool), Ternary::True);
         // false extends bool
-        assert_eq!(
-            Type::Primitive(Primitive::False).extends(&Type::Primitive(Primitive::Bool)),
-            Primitive::True
-        );
-        // string extends string
-        assert_eq!(
-            Type::Primitive(Primitive::String).extends(&Type::Primitive(Primitive::String)),
-            Primitive::True
-        );
+        assert_eq!(Primitive::False.extends(&Primitive::Bool), Ternary::True);
+        // bool extends true
+        assert_eq!(Primitive::Bool.extends(&Primitive::True), Ternary::False);
         // "foo" extends string
         assert_eq!(
-            Type::Primitive(Primitive::StringLiteral("foo".to_string()))
-                .extends(&Type::Primitive(Primitive::String)),
-            Primitive::True
+            Primitive::StringLiteral("foo".to_string()).extends(&Primitive::String),
+            Ternary::True
         );
         // "foo" extends "bar"
         assert_eq!(
-            Type::Primitive(Primitive::StringLiteral("foo".to_string())).extends(&Type::Primitive(
-                Primitive::StringLiteral("bar".to_string())
-            )),
-            Primitive::False
+            Primitive::StringLiteral("foo".to_string())
+                .extends(&Primitive::StringLiteral("bar".to_string())),
+            Ternary::False
         );
+        // 'a' extends char
+        assert_eq!(Primitive::CharLiteral('a').extends(&Primitive::Char), Ternary::True);
+        // int extends float
+        assert_eq!(Primitive::Int.extends(&Primitive::Float), Ternary::False);
+    }
 
+    #[test]
+    fn special_primitives() {
         // any extends any
-        assert_eq!(
-            Type::Primitive(Primitive::Any).extends(&Type::Primitive(Primitive::Any)),
-            Primitive::True
-        );
+        assert_eq!(Primitive::Any.extends(&Primitive::Any), Ternary::True);
         // never extends never
-        assert_eq!(
-            Type::Primitive(Primitive::Never).extends(&Type::Primitive(Primitive::Never)),
-            Primitive::True
-        );
+        assert_eq!(Primitive::Never.extends(&Primitive::Never), Ternary::True);
         // never extends any
-        assert_eq!(
-            Type::Primitive(Primitive::Never).extends(&Type::Primitive(Primitive::Any)),
-            Primitive::True
-        );
+        assert_eq!(Primitive::Never.extends(&Primitive::Any), Ternary::True);
         // any extends never
-        assert_eq!(
-            Type::Primitive(Primitive::Any).extends(&Type::Primitive(Primitive::Never)),
-            Primitive::False
-        );
+        assert_eq!(Primitive::Any.extends(&Primitive::Never), Ternary::False);
+        // any extends int
+        assert_eq!(Primitive::Any.extends(&Primitive::Int), Ternary::False);
+        // never extends 1
+        assert_eq!(Primitive::Never.extends(&Primitive::IntLiteral(1)), Ternary::True);
     }
 
     #[test]
-    fn primitive_extends_union() {
-        // int extends int | string
+    fn unresolved_types() {
+        // Foo extends Foo
         assert_eq!(
-            Type::Primitive(Primitive::Int).extends(&Type::Operation(Operation::Union(Union::of(
-                Type::Primitive(Primitive::Int).into(),
-                Type::Primitive(Primitive::String).into()
-            )))),
-            Primitive::True
+            Primitive::Ref("Foo".to_string()).extends(&Primitive::Ref("Foo".to_string())),
+            Ternary::True
         );
-        // int extends string | bool
+        // Foo extends Bar
         assert_eq!(
-            Type::Primitive(Primitive::Int).extends(&Type::Operation(Operation::Union(Union::of(
-                Type::Primitive(Primitive::String).into(),
-                Type::Primitive(Primitive::Bool).into()
-            )))),
-            Primitive::False
+            Primitive::Ref("Foo".to_string()).extends(&Primitive::Ref("Bar".to_string())),
+            Ternary::Unknown
         );
+        // Foo extends any
+        assert_eq!(Primitive::Ref("Foo".to_string()).extends(&Primitive::Any), Ternary::True);
+        // _ extends int
+        assert_eq!(TypeExpr::Infer.extends(&Primitive::Int), Ternary::Unknown);
     }
 }
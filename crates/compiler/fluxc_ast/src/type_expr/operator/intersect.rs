@@ -1,15 +1,62 @@
-use crate::{Operation, Primitive, Simplify, TypeExpr, Unify, Union};
+use crate::{type_expr::compare, Operation, Primitive, Simplify, TypeExpr, Union};
 
-#[derive(Debug, Clone, PartialEq)]
+use super::union::absorb;
+
+/// Represents the intersection of two types.
+#[derive(Debug, Clone)]
 pub struct Intersection {
     pub lhs: Box<TypeExpr>,
     pub rhs: Box<TypeExpr>,
 }
 
 impl Intersection {
+    /// Creates a new intersection of two types.
     pub fn of(lhs: TypeExpr, rhs: TypeExpr) -> Self {
         Self { lhs: Box::new(lhs), rhs: Box::new(rhs) }
     }
+
+    /// Creates the intersection of the given types. The result is not
+    /// simplified. The intersection of no types is `any`.
+    pub fn from_members(members: Vec<TypeExpr>) -> TypeExpr {
+        members
+            .into_iter()
+            .rev()
+            .reduce(|rhs, lhs| {
+                TypeExpr::Operation(Operation::Intersection(Intersection::of(lhs, rhs)))
+            })
+            .unwrap_or(TypeExpr::Primitive(Primitive::Any))
+    }
+
+    /// Returns the members of the simplified form of this intersection, in
+    /// their canonical order.
+    pub fn members(&self) -> Vec<TypeExpr> {
+        let mut members = vec![];
+        flatten(&self.simplify(), &mut members);
+        members
+    }
+}
+
+/// Collect the members of a simplified intersection.
+fn flatten(ty: &TypeExpr, members: &mut Vec<TypeExpr>) {
+    match ty {
+        TypeExpr::Operation(Operation::Intersection(Intersection { lhs, rhs })) => {
+            flatten(lhs, members);
+            flatten(rhs, members);
+        }
+        ty => members.push(ty.clone()),
+    }
+}
+
+/// Returns `true` if the given type is a primitive whose values can be fully
+/// determined without further context. Two such primitives that do not extend
+/// one another have no values in common.
+fn is_concrete(ty: &TypeExpr) -> bool {
+    match ty {
+        TypeExpr::Primitive(Primitive::Tuple(types)) => types.iter().all(is_concrete),
+        TypeExpr::Primitive(Primitive::Ref(_) | Primitive::Any | Primitive::Never) => false,
+        TypeExpr::Primitive(_) => true,
+        _ => false,
+    }
 }
 
 /// A trait for type intersection.
@@ -30,95 +77,67 @@ impl From<Intersection> for TypeExpr {
     }
 }
 
+impl PartialEq for Intersection {
+    fn eq(&self, other: &Self) -> bool {
+        compare(&self.simplify(), &other.simplify()).is_eq()
+    }
+}
+
 /// Implement Simplify trait for Intersection. Simplification of intersections
-/// relies on a few logical rules:
+/// flattens the intersection into a set of members, sorted into a canonical
+/// order, relying on a few logical rules:
 /// - T & T = T
 /// - T & any = T
 /// - T & never = never
 /// - T & (A | B) = (T & A) | (T & B)
-/// - (A | B) & (C | D) = (A & C) | (A & D) | (B & C) | (B & D)
-/// These are applied in order in an attempt to simplify the tree.
+/// - A & B = A, where A extends B (e.g. `1 & int = 1`)
+/// - A & B = never, where A and B are disjoint primitives
 impl Simplify for Intersection {
     fn simplify(&self) -> TypeExpr {
-        let lhs = self.lhs.simplify();
-        let rhs = self.rhs.simplify();
-        // T & T = T
-        if lhs == rhs {
-            return lhs;
-        }
-        // T & any = T
-        if lhs == TypeExpr::Primitive(Primitive::Any) {
-            return rhs;
-        }
-        if rhs == TypeExpr::Primitive(Primitive::Any) {
-            return lhs;
-        }
+        let mut members = vec![];
+        flatten(&self.lhs.simplify(), &mut members);
+        flatten(&self.rhs.simplify(), &mut members);
         // T & never = never
-        if lhs == TypeExpr::Primitive(Primitive::Never) {
+        if members.iter().any(|ty| matches!(ty, TypeExpr::Primitive(Primitive::Never))) {
             return TypeExpr::Primitive(Primitive::Never);
         }
-        if rhs == TypeExpr::Primitive(Primitive::Never) {
-            return TypeExpr::Primitive(Primitive::Never);
-        }
-        if lhs == rhs {
-            return lhs;
+        // T & any = T
+        members.retain(|ty| !matches!(ty, TypeExpr::Primitive(Primitive::Any)));
+        // T & (A | B) = (T & A) | (T & B)
+        if let Some(i) =
+            members.iter().position(|ty| matches!(ty, TypeExpr::Operation(Operation::Union(_))))
+        {
+            let union = match members.remove(i) {
+                TypeExpr::Operation(Operation::Union(union)) => union,
+                _ => unreachable!(),
+            };
+            let rest = Intersection::from_members(members);
+            return Union::from_members(
+                union
+                    .members()
+                    .into_iter()
+                    .map(|ty| {
+                        TypeExpr::Operation(Operation::Intersection(Intersection::of(
+                            ty,
+                            rest.clone(),
+                        )))
+                    })
+                    .collect(),
+            )
+            .simplify();
         }
-        // more complex intersection
-        match (&lhs, &rhs) {
-            // (A | B) & (C | D) = (A & C) | (A & D) | (B & C) | (B & D)
-            (
-                TypeExpr::Operation(Operation::Union(Union { lhs: a, rhs: b })),
-                TypeExpr::Operation(Operation::Union(Union { lhs: c, rhs: d })),
-            ) => {
-                return a
-                    .intersect(c)
-                    .unify(&a.intersect(d))
-                    .unify(&b.intersect(c))
-                    .unify(&b.intersect(d))
-                    .simplify();
-            }
-            // T & (A | B) = (T & A) | (T & B)
-            (t, TypeExpr::Operation(Operation::Union(Union { lhs, rhs }))) => {
-                return lhs.intersect(t).unify(&rhs.intersect(t).simplify());
-            }
-            // A & B where A and B's are primitives
-            (TypeExpr::Primitive(a), TypeExpr::Primitive(b)) => {
-                if a == b {
-                    return a.into();
-                }
-                // intersect primitive int literals
-                let int = match (&a, &b) {
-                    (Primitive::IntLiteral(_), Primitive::Int) => a.into(),
-                    (Primitive::Int, Primitive::IntLiteral(_)) => b.into(),
-                    _ => Primitive::Never.into(),
-                };
-                if int != Primitive::Never.into() {
-                    return int;
-                }
-                // intersect primitive float literals
-                let float = match (&a, &b) {
-                    (Primitive::FloatLiteral(_), Primitive::Float) => a.into(),
-                    (Primitive::Float, Primitive::FloatLiteral(_)) => b.into(),
-                    _ => Primitive::Never.into(),
-                };
-                if float != Primitive::Never.into() {
-                    return float;
-                }
-                // intersect primitive string literals
-                let string = match (&a, &b) {
-                    (Primitive::StringLiteral(_), Primitive::String) => a.into(),
-                    (Primitive::String, Primitive::StringLiteral(_)) => b.into(),
-                    _ => Primitive::Never.into(),
-                };
-                if string != Primitive::Never.into() {
-                    return string;
-                }
-            }
-            _ => (),
+        // T & T = T
+        members.sort_by(compare);
+        members.dedup_by(|a, b| compare(a, b).is_eq());
+        let members = absorb(members, true);
+        // A & B = never
+        if members.iter().filter(|ty| is_concrete(ty)).count() > 1 {
+            return TypeExpr::Primitive(Primitive::Never);
         }
-        TypeExpr::Operation(Operation::Intersection(Intersection::of(lhs, rhs)))
+        Intersection::from_members(members)
     }
 }
+
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
@@ -0,0 +1,73 @@
+use crate::{Intersection, Operation, Primitive, Simplify, TypeExpr, Union};
+
+/// The union of the indices of a tuple or array of the given length.
+fn indices(len: usize) -> TypeExpr {
+    Union::from_members(
+        (0..len as i64).map(|i| TypeExpr::Primitive(Primitive::IntLiteral(i))).collect(),
+    )
+}
+
+/// Evaluate `keyof` for the given simplified type, whose keys are known from
+/// its structure:
+/// - keyof (A, B) = 0 | 1
+/// - keyof T[n] = 0 | 1 | ... | n - 1
+/// - keyof T[] = int
+/// - keyof (A | B) = keyof A & keyof B
+/// - keyof (A & B) = keyof A | keyof B
+/// - keyof T = never, for any other primitive
+///
+/// The keys of type references, such as structs, depend on their declarations
+/// and are left as they are.
+pub(crate) fn keys(ty: &TypeExpr) -> TypeExpr {
+    match ty {
+        TypeExpr::Primitive(Primitive::Tuple(types)) => indices(types.len()),
+        TypeExpr::Operation(Operation::Array(_, Some(len))) => indices(*len),
+        TypeExpr::Operation(Operation::Array(_, None)) => TypeExpr::Primitive(Primitive::Int),
+        TypeExpr::Operation(Operation::Union(union)) => {
+            Intersection::from_members(union.members().iter().map(keys).collect()).simplify()
+        }
+        TypeExpr::Operation(Operation::Intersection(intersection)) => {
+            Union::from_members(intersection.members().iter().map(keys).collect()).simplify()
+        }
+        TypeExpr::Primitive(Primitive::Ref(_) | Primitive::Any | Primitive::Never) => {
+            TypeExpr::Operation(Operation::KeyOf(ty.clone().into()))
+        }
+        TypeExpr::Primitive(_) => TypeExpr::Primitive(Primitive::Never),
+        ty => TypeExpr::Operation(Operation::KeyOf(ty.clone().into())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{Operation, Primitive, TypeExpr, Union};
+
+    /// Create the type `keyof ty`.
+    fn keyof(ty: TypeExpr) -> TypeExpr {
+        TypeExpr::Operation(Operation::KeyOf(ty.into()))
+    }
+
+    #[test]
+    fn keyof_tuples() {
+        // keyof (int, string) = 0 | 1
+        let tuple = Primitive::Tuple(vec![Primitive::Int.into(), Primitive::String.into()]);
+        assert_eq!(keyof(tuple.into()).to_string(), "0 | 1");
+        // keyof int[3] = 0 | 1 | 2
+        let array = Operation::Array(Box::new(Primitive::Int.into()), Some(3));
+        assert_eq!(keyof(array.into()).to_string(), "0 | 1 | 2");
+        // keyof int = never
+        assert_eq!(keyof(Primitive::Int.into()).to_string(), "never");
+    }
+
+    #[test]
+    fn keyof_operations() {
+        // keyof ((int, int) | (int,)) = 0
+        let pair = Primitive::Tuple(vec![Primitive::Int.into(), Primitive::Int.into()]);
+        let single = Primitive::Tuple(vec![Primitive::Int.into()]);
+        let union = Union::of(pair.into(), single.into()).into();
+        assert_eq!(keyof(union).to_string(), "0");
+        // keyof Point is left as-is
+        assert_eq!(keyof(Primitive::Ref("Point".into()).into()).to_string(), "keyof Point");
+    }
+}
@@ -1,10 +1,15 @@
-// mod extends;
+mod conditional;
+mod difference;
+mod extends;
 mod intersect;
+mod keyof;
 mod simplify;
 mod union;
 
 // export all types
-// pub use extends::*;
+pub use conditional::*;
+pub use difference::*;
+pub use extends::*;
 pub use intersect::*;
 pub use simplify::*;
 pub use union::*;
@@ -21,6 +26,10 @@ pub enum Operation {
     Union(Union),
     /// An array type.
     Array(Box<TypeExpr>, Option<usize>),
+    /// A conditional type, such as `T extends int ? 1 : 2`.
+    Conditional(ConditionalType),
+    /// The union of the keys of a type, such as `keyof Point`.
+    KeyOf(Box<TypeExpr>),
 }
 
 impl Into<TypeExpr> for Operation {
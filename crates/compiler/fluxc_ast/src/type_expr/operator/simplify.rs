@@ -1,3 +1,4 @@
+use super::keyof::keys;
 use crate::{Operation, Primitive, TypeExpr};
 
 // Trait for simplifying a type tree.
@@ -11,7 +12,11 @@ impl Simplify for Operation {
         match self {
             Operation::Intersection(intersection) => intersection.simplify(),
             Operation::Union(union) => union.simplify(),
-            _ => TypeExpr::Operation(self.clone()),
+            Operation::Array(inner, size) => {
+                TypeExpr::Operation(Operation::Array(inner.simplify().into(), *size))
+            }
+            Operation::Conditional(conditional) => conditional.simplify(),
+            Operation::KeyOf(inner) => keys(&inner.simplify()),
         }
     }
 }
@@ -34,6 +39,13 @@ impl Simplify for TypeExpr {
             TypeExpr::Operation(op) => op.simplify(),
             TypeExpr::Primitive(primitive) => primitive.simplify(),
             TypeExpr::Circular(inner) => TypeExpr::Circular(inner.simplify().into()),
+            TypeExpr::Generic(name, args) => {
+                TypeExpr::Generic(name.clone(), args.iter().map(|ty| ty.simplify()).collect())
+            }
+            TypeExpr::Function(params, ret) => TypeExpr::Function(
+                params.iter().map(|ty| ty.simplify()).collect(),
+                ret.simplify().into(),
+            ),
             t => t.clone(),
         }
     }
@@ -1,7 +1,7 @@
-use crate::{Operation, Primitive, Simplify, TypeExpr};
+use crate::{type_expr::compare, Extends, Operation, Primitive, Simplify, TypeExpr};
 
 /// Represents the union of two types.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
 pub struct Union {
     pub lhs: Box<TypeExpr>,
     pub rhs: Box<TypeExpr>,
@@ -12,6 +12,60 @@ impl Union {
     pub fn of(lhs: TypeExpr, rhs: TypeExpr) -> Self {
         Self { lhs: Box::new(lhs), rhs: Box::new(rhs) }
     }
+
+    /// Creates the union of the given types. The result is not simplified.
+    /// The union of no types is `never`.
+    pub fn from_members(members: Vec<TypeExpr>) -> TypeExpr {
+        members
+            .into_iter()
+            .rev()
+            .reduce(|rhs, lhs| TypeExpr::Operation(Operation::Union(Union::of(lhs, rhs))))
+            .unwrap_or(TypeExpr::Primitive(Primitive::Never))
+    }
+
+    /// Returns the members of the simplified form of this union, in their
+    /// canonical order.
+    pub fn members(&self) -> Vec<TypeExpr> {
+        let mut members = vec![];
+        flatten(&self.simplify(), &mut members);
+        members
+    }
+}
+
+/// Collect the members of a simplified union.
+fn flatten(ty: &TypeExpr, members: &mut Vec<TypeExpr>) {
+    match ty {
+        TypeExpr::Operation(Operation::Union(Union { lhs, rhs })) => {
+            flatten(lhs, members);
+            flatten(rhs, members);
+        }
+        ty => members.push(ty.clone()),
+    }
+}
+
+/// Removes every member that extends another member of a sorted and
+/// deduplicated set of types, keeping the widest of the members. If
+/// `narrowest` is set, the narrowest of the members are kept instead. Of two
+/// mutually extending members, the first is kept.
+pub(crate) fn absorb(members: Vec<TypeExpr>, narrowest: bool) -> Vec<TypeExpr> {
+    let extends = |a: &TypeExpr, b: &TypeExpr| {
+        if narrowest {
+            b.extends(a).is_true()
+        } else {
+            a.extends(b).is_true()
+        }
+    };
+    members
+        .iter()
+        .enumerate()
+        .filter(|(i, a)| {
+            !members
+                .iter()
+                .enumerate()
+                .any(|(j, b)| i != &j && extends(a, b) && (!extends(b, a) || j < *i))
+        })
+        .map(|(_, a)| a.clone())
+        .collect()
 }
 
 /// Trait for type union.
@@ -32,41 +86,40 @@ impl Into<TypeExpr> for Union {
     }
 }
 
+impl PartialEq for Union {
+    fn eq(&self, other: &Self) -> bool {
+        compare(&self.simplify(), &other.simplify()).is_eq()
+    }
+}
+
+/// Simplification of unions flattens the union into a set of members, sorted
+/// into a canonical order, relying on a few logical rules:
+/// - T | T = T
+/// - T | any = any
+/// - T | never = T
+/// - true | false = bool
+/// - A | B = B, where A extends B (e.g. `1 | int = int`)
 impl Simplify for Union {
     fn simplify(&self) -> TypeExpr {
-        let lhs = self.lhs.simplify();
-        let rhs = self.rhs.simplify();
-        // T | T = T
-        if lhs == rhs {
-            return lhs;
+        let mut members = vec![];
+        flatten(&self.lhs.simplify(), &mut members);
+        flatten(&self.rhs.simplify(), &mut members);
+        // T | any = any
+        if members.iter().any(|ty| matches!(ty, TypeExpr::Primitive(Primitive::Any))) {
+            return TypeExpr::Primitive(Primitive::Any);
         }
-        match (&lhs, &rhs) {
-            // T | any = any
-            (TypeExpr::Primitive(Primitive::Any), _) | (_, TypeExpr::Primitive(Primitive::Any)) => {
-                return TypeExpr::Primitive(Primitive::Never)
-            }
-            // T | never = T
-            (TypeExpr::Primitive(Primitive::Never), _)
-            | (_, TypeExpr::Primitive(Primitive::Never)) => return lhs,
-            // (A | B) | A = A | B
-            (TypeExpr::Operation(Operation::Union(Union { lhs: a, rhs: b })), c)
-            | (c, TypeExpr::Operation(Operation::Union(Union { lhs: a, rhs: b }))) => {
-                if a.as_ref() == c {
-                    return TypeExpr::Operation(Operation::Union(Union {
-                        lhs: a.clone(),
-                        rhs: c.clone().into(),
-                    }));
-                } else if b.as_ref() == c {
-                    return TypeExpr::Operation(Operation::Union(Union {
-                        lhs: c.clone().into(),
-                        rhs: b.clone(),
-                    }));
-                }
-            }
-            _ => {}
-        };
-        // (A | B) | A = T
-        TypeExpr::Operation(Operation::Union(Union::of(lhs.into(), rhs.into())))
+        // T | never = T
+        members.retain(|ty| !matches!(ty, TypeExpr::Primitive(Primitive::Never)));
+        // true | false = bool
+        let is =
+            |p: Primitive| members.iter().any(|ty| matches!(ty, TypeExpr::Primitive(q) if *q == p));
+        if is(Primitive::True) && is(Primitive::False) {
+            members.push(TypeExpr::Primitive(Primitive::Bool));
+        }
+        // T | T = T
+        members.sort_by(compare);
+        members.dedup_by(|a, b| compare(a, b).is_eq());
+        Union::from_members(absorb(members, false))
     }
 }
 
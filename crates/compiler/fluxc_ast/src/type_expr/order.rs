@@ -0,0 +1,93 @@
+//! Defines the canonical ordering of type expressions.
+
+use std::cmp::Ordering;
+
+use super::{Operation, Primitive, TypeExpr};
+
+/// The position of a primitive in the canonical ordering. Literal types are
+/// ordered directly after the primitive they are a member of.
+fn primitive_rank(primitive: &Primitive) -> u8 {
+    match primitive {
+        Primitive::Never => 0,
+        Primitive::Unit => 1,
+        Primitive::Bool => 2,
+        Primitive::True => 3,
+        Primitive::False => 4,
+        Primitive::Int => 5,
+        Primitive::IntLiteral(_) => 6,
+        Primitive::Float => 7,
+        Primitive::FloatLiteral(_) => 8,
+        Primitive::Numeric(_) => 9,
+        Primitive::Char => 10,
+        Primitive::CharLiteral(_) => 11,
+        Primitive::String => 12,
+        Primitive::StringLiteral(_) => 13,
+        Primitive::Tuple(_) => 14,
+        Primitive::Ref(_) => 15,
+        Primitive::Any => 16,
+    }
+}
+
+/// The position of a type expression in the canonical ordering.
+fn rank(ty: &TypeExpr) -> u8 {
+    match ty {
+        TypeExpr::Primitive(primitive) => primitive_rank(primitive),
+        TypeExpr::Operation(Operation::Array(..)) => 17,
+        TypeExpr::Operation(Operation::Intersection(_)) => 18,
+        TypeExpr::Operation(Operation::Union(_)) => 19,
+        TypeExpr::Operation(Operation::KeyOf(_)) => 20,
+        TypeExpr::Operation(Operation::Conditional(_)) => 21,
+        TypeExpr::Generic(..) => 22,
+        TypeExpr::Function(..) => 23,
+        TypeExpr::Circular(_) => 24,
+        TypeExpr::Capture(_) => 25,
+        TypeExpr::Infer => 26,
+    }
+}
+
+/// Compare two sequences of type expressions lexicographically.
+fn compare_all(a: &[TypeExpr], b: &[TypeExpr]) -> Ordering {
+    a.iter().zip(b).map(|(a, b)| compare(a, b)).find(|o| o.is_ne()).unwrap_or(a.len().cmp(&b.len()))
+}
+
+/// Compare two type expressions structurally. This ordering is total, and is
+/// used to sort the members of unions and intersections into their canonical
+/// order. Operands should be simplified before being compared.
+pub fn compare(a: &TypeExpr, b: &TypeExpr) -> Ordering {
+    use Primitive::*;
+    rank(a).cmp(&rank(b)).then_with(|| match (a, b) {
+        (TypeExpr::Primitive(a), TypeExpr::Primitive(b)) => match (a, b) {
+            (IntLiteral(a), IntLiteral(b)) => a.cmp(b),
+            (FloatLiteral(a), FloatLiteral(b)) => a.total_cmp(b),
+            (CharLiteral(a), CharLiteral(b)) => a.cmp(b),
+            (Numeric(a), Numeric(b)) => a.cmp(b),
+            (StringLiteral(a), StringLiteral(b)) | (Ref(a), Ref(b)) => a.cmp(b),
+            (Tuple(a), Tuple(b)) => compare_all(a, b),
+            _ => Ordering::Equal,
+        },
+        (TypeExpr::Operation(a), TypeExpr::Operation(b)) => match (a, b) {
+            (Operation::Array(a, n), Operation::Array(b, m)) => compare(a, b).then(n.cmp(m)),
+            (Operation::Intersection(a), Operation::Intersection(b)) => {
+                compare(&a.lhs, &b.lhs).then_with(|| compare(&a.rhs, &b.rhs))
+            }
+            (Operation::Union(a), Operation::Union(b)) => {
+                compare(&a.lhs, &b.lhs).then_with(|| compare(&a.rhs, &b.rhs))
+            }
+            (Operation::KeyOf(a), Operation::KeyOf(b)) => compare(a, b),
+            (Operation::Conditional(a), Operation::Conditional(b)) => compare(&a.check, &b.check)
+                .then_with(|| compare(&a.extends, &b.extends))
+                .then_with(|| compare(&a.then, &b.then))
+                .then_with(|| compare(&a.otherwise, &b.otherwise)),
+            _ => Ordering::Equal,
+        },
+        (TypeExpr::Generic(a, args), TypeExpr::Generic(b, params)) => {
+            a.cmp(b).then_with(|| compare_all(args, params))
+        }
+        (TypeExpr::Function(a, x), TypeExpr::Function(b, y)) => {
+            compare_all(a, b).then_with(|| compare(x, y))
+        }
+        (TypeExpr::Circular(a), TypeExpr::Circular(b)) => compare(a, b),
+        (TypeExpr::Capture(a), TypeExpr::Capture(b)) => a.cmp(b),
+        _ => Ordering::Equal,
+    })
+}
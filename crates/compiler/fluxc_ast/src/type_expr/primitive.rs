@@ -1,6 +1,6 @@
 //! Contains the primitive type definitions.
 
-use super::TypeExpr;
+use super::{Numeric, TypeExpr};
 
 /// An enumeration of primitive types. This enum represents all primitive types,
 /// including the `never` type.
@@ -16,6 +16,8 @@ pub enum Primitive {
     Float,
     /// The primitive float literal type.
     FloatLiteral(f64),
+    /// A sized numeric type, such as `u8` or `f32`.
+    Numeric(Numeric),
     /// The primitive string type. This represents the infinite union of all
     /// strings.
     String,
@@ -76,3 +78,9 @@ impl Into<TypeExpr> for &Primitive {
         TypeExpr::Primitive(self.clone())
     }
 }
+
+impl From<Numeric> for Primitive {
+    fn from(numeric: Numeric) -> Self {
+        Primitive::Numeric(numeric)
+    }
+}
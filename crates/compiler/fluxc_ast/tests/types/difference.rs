@@ -0,0 +1,62 @@
+//! Integration tests for the difference operator.
+
+use fluxc_ast::{Difference, Exclude, Intersect, Primitive, Simplify, TypeExpr, Union};
+use pretty_assertions::assert_eq;
+
+#[test]
+fn test_union_difference() {
+    // (int | string) - int = string
+    assert_eq!(
+        Difference::of(
+            Union::of(Primitive::Int.into(), Primitive::String.into()).into(),
+            Primitive::Int.into()
+        )
+        .simplify(),
+        TypeExpr::Primitive(Primitive::String)
+    );
+    // (1 | 2 | string) - (1 | string) = 2
+    let ty: TypeExpr = Union::of(
+        Union::of(Primitive::IntLiteral(1).into(), Primitive::IntLiteral(2).into()).into(),
+        Primitive::String.into(),
+    )
+    .into();
+    let excluded = Union::of(Primitive::IntLiteral(1).into(), Primitive::String.into()).into();
+    assert_eq!(ty.exclude(&excluded), TypeExpr::Primitive(Primitive::IntLiteral(2)));
+    // (int | string) - float = int | string
+    let ty: TypeExpr = Union::of(Primitive::Int.into(), Primitive::String.into()).into();
+    assert_eq!(ty.exclude(&Primitive::Float.into()), ty);
+}
+
+#[test]
+fn test_bool_difference() {
+    // bool - true = false
+    assert_eq!(
+        TypeExpr::Primitive(Primitive::Bool).exclude(&Primitive::True.into()),
+        TypeExpr::Primitive(Primitive::False)
+    );
+    // (bool | ()) - (true | false) = ()
+    let ty: TypeExpr = Union::of(Primitive::Bool.into(), Primitive::Unit.into()).into();
+    let excluded = Union::of(Primitive::True.into(), Primitive::False.into()).into();
+    assert_eq!(ty.exclude(&excluded), TypeExpr::Primitive(Primitive::Unit));
+}
+
+#[test]
+fn test_difference_identities() {
+    let ty: TypeExpr = Union::of(Primitive::IntLiteral(1).into(), Primitive::String.into()).into();
+    let other: TypeExpr = Union::of(Primitive::Int.into(), Primitive::Float.into()).into();
+    // (T - U) & U = never
+    assert_eq!(ty.exclude(&other).intersect(&other), TypeExpr::Primitive(Primitive::Never));
+    // (T - U) | (T & U) = T
+    let parts = Union::of(ty.exclude(&other), ty.intersect(&other)).simplify();
+    assert_eq!(parts, ty.simplify());
+    // never - T = never
+    assert_eq!(
+        TypeExpr::Primitive(Primitive::Never).exclude(&ty),
+        TypeExpr::Primitive(Primitive::Never)
+    );
+    // Point - int = Point, as references are not decided without context
+    assert_eq!(
+        TypeExpr::Primitive(Primitive::Ref("Point".into())).exclude(&Primitive::Int.into()),
+        TypeExpr::Primitive(Primitive::Ref("Point".into()))
+    );
+}
@@ -0,0 +1,125 @@
+//! Integration tests for the display of type expressions.
+
+use fluxc_ast::{ConditionalType, Intersection, Operation, Primitive, TypeExpr, Union};
+use pretty_assertions::assert_eq;
+
+#[test]
+fn display_primitives() {
+    assert_eq!(TypeExpr::Primitive(Primitive::Int).to_string(), "int");
+    assert_eq!(TypeExpr::Primitive(Primitive::IntLiteral(-3)).to_string(), "-3");
+    assert_eq!(TypeExpr::Primitive(Primitive::FloatLiteral(1.0)).to_string(), "1.0");
+    assert_eq!(TypeExpr::Primitive(Primitive::StringLiteral("a".to_string())).to_string(), "\"a\"");
+    assert_eq!(TypeExpr::Primitive(Primitive::CharLiteral('a')).to_string(), "'a'");
+    assert_eq!(
+        TypeExpr::Primitive(Primitive::Tuple(vec![Primitive::Int.into(), Primitive::Bool.into()]))
+            .to_string(),
+        "(int, bool)"
+    );
+    assert_eq!(
+        TypeExpr::Operation(Operation::Array(Box::new(Primitive::Int.into()), Some(4))).to_string(),
+        "int[4]"
+    );
+}
+
+#[test]
+fn display_operations() {
+    // members are displayed in their canonical order
+    assert_eq!(
+        TypeExpr::Operation(Operation::Union(Union::of(
+            Union::of(Primitive::String.into(), Primitive::IntLiteral(1).into()).into(),
+            Primitive::Int.into()
+        )))
+        .to_string(),
+        "int | string"
+    );
+    assert_eq!(
+        TypeExpr::Operation(Operation::Union(Union::of(
+            Primitive::Ref("Foo".to_string()).into(),
+            Intersection::of(
+                Primitive::Ref("Baz".to_string()).into(),
+                Primitive::Ref("Bar".to_string()).into()
+            )
+            .into()
+        )))
+        .to_string(),
+        "Foo | Bar & Baz"
+    );
+    assert_eq!(
+        TypeExpr::Operation(Operation::Array(
+            Box::new(Union::of(Primitive::Int.into(), Primitive::String.into()).into()),
+            None
+        ))
+        .to_string(),
+        "(int | string)[]"
+    );
+}
+
+#[test]
+fn display_conditionals() {
+    let conditional = |check: TypeExpr| -> TypeExpr {
+        Operation::Conditional(ConditionalType::of(
+            check,
+            Primitive::Tuple(vec![TypeExpr::Capture("T".to_string())]).into(),
+            Primitive::Ref("T".to_string()).into(),
+            Primitive::Never.into(),
+        ))
+        .into()
+    };
+    let unresolved = conditional(Primitive::Ref("Foo".to_string()).into());
+    assert_eq!(unresolved.to_string(), "Foo extends (infer T,) ? T : never");
+    // conditionals are parenthesized inside other operations
+    assert_eq!(
+        TypeExpr::Operation(Operation::Union(Union::of(unresolved, Primitive::Int.into())))
+            .to_string(),
+        "int | (Foo extends (infer T,) ? T : never)"
+    );
+    assert_eq!(
+        TypeExpr::Operation(Operation::Array(
+            Box::new(Operation::KeyOf(Box::new(Primitive::Ref("Foo".to_string()).into())).into()),
+            None
+        ))
+        .to_string(),
+        "(keyof Foo)[]"
+    );
+}
+
+#[test]
+fn display_functions() {
+    let func = TypeExpr::Function(
+        vec![Primitive::Int.into(), Primitive::String.into()],
+        Box::new(Primitive::Bool.into()),
+    );
+    assert_eq!(func.to_string(), "(int, string) -> bool");
+    assert_eq!(
+        TypeExpr::Function(vec![], Box::new(func.clone())).to_string(),
+        "() -> (int, string) -> bool"
+    );
+    // functions are parenthesized inside other operations
+    assert_eq!(
+        TypeExpr::Operation(Operation::Union(Union::of(func, Primitive::Unit.into()))).to_string(),
+        "unit | ((int, string) -> bool)"
+    );
+}
+
+#[test]
+fn display_circular() {
+    // circular references are displayed as the alias they refer to, rather
+    // than expanded again
+    let list = TypeExpr::Circular(Box::new(Primitive::Ref("List".to_string()).into()));
+    assert_eq!(
+        TypeExpr::Operation(Operation::Union(Union::of(
+            Primitive::Unit.into(),
+            Primitive::Tuple(vec![Primitive::Int.into(), list]).into()
+        )))
+        .to_string(),
+        "unit | (int, List)"
+    );
+    let list = TypeExpr::Circular(Box::new(TypeExpr::Generic(
+        "List".to_string(),
+        vec![Primitive::Int.into()],
+    )));
+    assert_eq!(
+        TypeExpr::Operation(Operation::Array(Box::new(list), None)).to_string(),
+        "List<int>[]"
+    );
+}
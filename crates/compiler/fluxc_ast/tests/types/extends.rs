@@ -0,0 +1,150 @@
+//! Integration tests for the extends operator.
+
+use fluxc_ast::{Extends, Intersection, Operation, Primitive, Ternary, TypeExpr, Union};
+use pretty_assertions::assert_eq;
+
+#[test]
+fn extends_union() {
+    // int extends int | string
+    assert_eq!(
+        TypeExpr::Primitive(Primitive::Int).extends(&TypeExpr::Operation(Operation::Union(
+            Union::of(Primitive::Int.into(), Primitive::String.into())
+        ))),
+        Ternary::True
+    );
+    // int extends string | bool
+    assert_eq!(
+        TypeExpr::Primitive(Primitive::Int).extends(&TypeExpr::Operation(Operation::Union(
+            Union::of(Primitive::String.into(), Primitive::Bool.into())
+        ))),
+        Ternary::False
+    );
+    // 1 | 2 extends int
+    assert_eq!(
+        TypeExpr::Operation(Operation::Union(Union::of(
+            Primitive::IntLiteral(1).into(),
+            Primitive::IntLiteral(2).into()
+        )))
+        .extends(&Primitive::Int),
+        Ternary::True
+    );
+    // 1 | "a" extends int
+    assert_eq!(
+        TypeExpr::Operation(Operation::Union(Union::of(
+            Primitive::IntLiteral(1).into(),
+            Primitive::StringLiteral("a".to_string()).into()
+        )))
+        .extends(&Primitive::Int),
+        Ternary::False
+    );
+    // bool extends true | false
+    assert_eq!(
+        TypeExpr::Primitive(Primitive::Bool).extends(&TypeExpr::Operation(Operation::Union(
+            Union::of(Primitive::True.into(), Primitive::False.into())
+        ))),
+        Ternary::True
+    );
+    // int | string extends string | int
+    assert_eq!(
+        TypeExpr::Operation(Operation::Union(Union::of(
+            Primitive::Int.into(),
+            Primitive::String.into()
+        )))
+        .extends(&TypeExpr::Operation(Operation::Union(Union::of(
+            Primitive::String.into(),
+            Primitive::Int.into()
+        )))),
+        Ternary::True
+    );
+}
+
+#[test]
+fn extends_intersection() {
+    // Foo & Bar extends Foo
+    assert_eq!(
+        TypeExpr::Operation(Operation::Intersection(Intersection::of(
+            Primitive::Ref("Foo".to_string()).into(),
+            Primitive::Ref("Bar".to_string()).into()
+        )))
+        .extends(&Primitive::Ref("Foo".to_string())),
+        Ternary::True
+    );
+    // Foo extends Foo & Bar
+    assert_eq!(
+        TypeExpr::Primitive(Primitive::Ref("Foo".to_string())).extends(&TypeExpr::Operation(
+            Operation::Intersection(Intersection::of(
+                Primitive::Ref("Foo".to_string()).into(),
+                Primitive::Ref("Bar".to_string()).into()
+            ))
+        )),
+        Ternary::Unknown
+    );
+}
+
+#[test]
+fn extends_structures() {
+    // (1, "a") extends (int, string)
+    assert_eq!(
+        Primitive::Tuple(vec![
+            Primitive::IntLiteral(1).into(),
+            Primitive::StringLiteral("a".to_string()).into()
+        ])
+        .extends(&Primitive::Tuple(vec![Primitive::Int.into(), Primitive::String.into()])),
+        Ternary::True
+    );
+    // (int, string) extends (int,)
+    assert_eq!(
+        Primitive::Tuple(vec![Primitive::Int.into(), Primitive::String.into()])
+            .extends(&Primitive::Tuple(vec![Primitive::Int.into()])),
+        Ternary::False
+    );
+    // 1[4] extends int[]
+    assert_eq!(
+        TypeExpr::Operation(Operation::Array(Box::new(Primitive::IntLiteral(1).into()), Some(4)))
+            .extends(&TypeExpr::Operation(Operation::Array(Box::new(Primitive::Int.into()), None))),
+        Ternary::True
+    );
+    // int[] extends int[4]
+    assert_eq!(
+        TypeExpr::Operation(Operation::Array(Box::new(Primitive::Int.into()), None)).extends(
+            &TypeExpr::Operation(Operation::Array(Box::new(Primitive::Int.into()), Some(4)))
+        ),
+        Ternary::False
+    );
+    // circular int extends int
+    assert_eq!(
+        TypeExpr::Circular(Box::new(Primitive::Int.into())).extends(&Primitive::Int),
+        Ternary::True
+    );
+}
+
+#[test]
+fn extends_function() {
+    let func = |params: Vec<TypeExpr>, ret: TypeExpr| TypeExpr::Function(params, Box::new(ret));
+    // (int) -> 1 extends (int) -> int
+    assert_eq!(
+        func(vec![Primitive::Int.into()], Primitive::IntLiteral(1).into())
+            .extends(&func(vec![Primitive::Int.into()], Primitive::Int.into())),
+        Ternary::True
+    );
+    // (int) -> unit extends (1) -> unit, as parameters are contravariant
+    assert_eq!(
+        func(vec![Primitive::Int.into()], Primitive::Unit.into())
+            .extends(&func(vec![Primitive::IntLiteral(1).into()], Primitive::Unit.into())),
+        Ternary::True
+    );
+    // (1) -> unit extends (int) -> unit
+    assert_eq!(
+        func(vec![Primitive::IntLiteral(1).into()], Primitive::Unit.into())
+            .extends(&func(vec![Primitive::Int.into()], Primitive::Unit.into())),
+        Ternary::False
+    );
+    // (int, int) -> unit extends (int) -> unit
+    assert_eq!(
+        func(vec![Primitive::Int.into(), Primitive::Int.into()], Primitive::Unit.into())
+            .extends(&func(vec![Primitive::Int.into()], Primitive::Unit.into())),
+        Ternary::False
+    );
+    // () -> int extends int
+    assert_eq!(func(vec![], Primitive::Int.into()).extends(&Primitive::Int), Ternary::False);
+}
@@ -1,3 +1,6 @@
 //! Integration tests relating to type expressions.
+mod difference;
+mod display;
+mod extends;
 mod intersect;
 mod simplify;
@@ -98,3 +98,74 @@ fn simplify_intersection() {
         TypeExpr::Primitive(Primitive::String)
     );
 }
+
+#[test]
+fn canonical_union() {
+    // (int | string) | float = float | (string | int)
+    assert_eq!(
+        TypeExpr::Operation(Operation::Union(Union::of(
+            Union::of(Primitive::Int.into(), Primitive::String.into()).into(),
+            Primitive::Float.into()
+        ))),
+        TypeExpr::Operation(Operation::Union(Union::of(
+            Primitive::Float.into(),
+            Union::of(Primitive::String.into(), Primitive::Int.into()).into()
+        )))
+    );
+    // 1 | int = int
+    assert_eq!(
+        Union::of(Primitive::IntLiteral(1).into(), Primitive::Int.into()).simplify(),
+        TypeExpr::Primitive(Primitive::Int)
+    );
+    // true | false = bool
+    assert_eq!(
+        Union::of(Primitive::True.into(), Primitive::False.into()).simplify(),
+        TypeExpr::Primitive(Primitive::Bool)
+    );
+    // "a" | 2 | 1 | "a" has members 1, 2, "a"
+    assert_eq!(
+        Union::of(
+            Union::of(
+                Primitive::StringLiteral("a".to_string()).into(),
+                Primitive::IntLiteral(2).into()
+            )
+            .into(),
+            Union::of(
+                Primitive::IntLiteral(1).into(),
+                Primitive::StringLiteral("a".to_string()).into()
+            )
+            .into()
+        )
+        .members(),
+        vec![
+            TypeExpr::Primitive(Primitive::IntLiteral(1)),
+            TypeExpr::Primitive(Primitive::IntLiteral(2)),
+            TypeExpr::Primitive(Primitive::StringLiteral("a".to_string())),
+        ]
+    );
+}
+
+#[test]
+fn canonical_intersection() {
+    // Foo & Bar = Bar & Foo
+    assert_eq!(
+        TypeExpr::Operation(Operation::Intersection(Intersection::of(
+            Primitive::Ref("Foo".to_string()).into(),
+            Primitive::Ref("Bar".to_string()).into()
+        ))),
+        TypeExpr::Operation(Operation::Intersection(Intersection::of(
+            Primitive::Ref("Bar".to_string()).into(),
+            Primitive::Ref("Foo".to_string()).into()
+        )))
+    );
+    // 1 & int = 1
+    assert_eq!(
+        Intersection::of(Primitive::IntLiteral(1).into(), Primitive::Int.into()).simplify(),
+        TypeExpr::Primitive(Primitive::IntLiteral(1))
+    );
+    // int & string = never
+    assert_eq!(
+        Intersection::of(Primitive::Int.into(), Primitive::String.into()).simplify(),
+        TypeExpr::Primitive(Primitive::Never)
+    );
+}
@@ -1,10 +1,24 @@
-use fluxc_ast::Block;
-use fluxc_errors::CompilerError;
+use cranelift::prelude::Value;
+use fluxc_hir::{Block, Stmt};
 
 use crate::{Translate, TranslationContext};
 
+/// Blocks evaluate to the value of their trailing expression, or to unit if
+/// they have none.
 impl Translate for Block {
-    fn translate<'a>(&self, _ctx: &mut TranslationContext<'a>) -> Result<(), CompilerError> {
-        todo!()
+    type Output = Value;
+
+    fn translate(&self, ctx: &mut TranslationContext<'_>) -> Value {
+        let Some((last, stmts)) = self.stmts.split_last() else { return ctx.unit() };
+        for stmt in stmts {
+            stmt.translate(ctx);
+        }
+        match last {
+            Stmt::Expr(expr) => expr.translate(ctx),
+            stmt => {
+                stmt.translate(ctx);
+                ctx.unit()
+            }
+        }
     }
 }
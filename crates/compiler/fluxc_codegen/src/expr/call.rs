@@ -0,0 +1,116 @@
+use cranelift::{
+    codegen::ir::Inst,
+    prelude::{AbiParam, InstBuilder, StackSlotData, StackSlotKind, Value},
+};
+use cranelift_module::{FuncOrDataId, Module};
+use fluxc_ast::TypeExpr;
+use fluxc_hir::{Call, Dispatch, Expr, ExprKind, MethodCall};
+
+use crate::{mangle_instance, mangle_method, Translate, TranslationContext};
+
+impl TranslationContext<'_> {
+    /// Emit the function call `call`. Calls to declared functions, instances
+    /// of generic functions and methods without a receiver are direct, while
+    /// calls to any other value are made through the closure it evaluates to.
+    pub fn call(&mut self, call: &Call) -> Value {
+        let name = match &call.callee.kind {
+            ExprKind::Function(name) if call.type_args.is_empty() => name.clone(),
+            ExprKind::Function(name) => mangle_instance(name, &call.type_args),
+            ExprKind::Method(ty, method) => mangle_method(&ty.to_string(), method),
+            _ => {
+                let callee = call.callee.translate(self);
+                return self.call_value(callee, &call.callee.ty, &call.args);
+            }
+        };
+        let params = self.params(&name);
+        let args = self.call_args(&params, &call.args);
+        self.call_direct(&name, &args)
+    }
+
+    /// Emit the method call `call`, which returns a value of the type `ty`.
+    /// The receiver is passed by reference ahead of the arguments. Calls on
+    /// interface values are dispatched through their vtable.
+    pub fn method_call(&mut self, call: &MethodCall, ty: &TypeExpr) -> Value {
+        let receiver = call.receiver.translate(self);
+        match &call.dispatch {
+            Dispatch::Static(ty) => {
+                let name = mangle_method(ty, &call.method);
+                let params = self.params(&name);
+                let receiver = self.receiver(receiver, &call.receiver.ty);
+                let mut args = vec![receiver];
+                args.extend(self.call_args(&params[1..], &call.args));
+                self.call_direct(&name, &args)
+            }
+            Dispatch::Dynamic(interface) => {
+                let params = self.params(&mangle_method(interface, &call.method));
+                let args = self.call_args(&params[1..], &call.args);
+                let pointer_type = self.pointer_type();
+                let mut signature = self.module.make_signature();
+                signature.params.push(AbiParam::new(pointer_type));
+                for param in &params[1..] {
+                    signature.params.extend(self.abi_type(param).map(AbiParam::new));
+                }
+                signature.returns.extend(self.abi_type(ty).map(AbiParam::new));
+                let fat = self.types.resolve(&call.receiver.ty).expect("unresolved interface").id;
+                let call = self.call_dyn(fat, receiver, &call.method, signature, &args);
+                self.call_result(call)
+            }
+        }
+    }
+
+    /// Return the argument types of the function declared as `name`.
+    fn params(&self, name: &str) -> Vec<TypeExpr> {
+        self.arg_types.get(name).cloned().unwrap_or_else(|| panic!("`{name}` was not declared"))
+    }
+
+    /// Emit the arguments passed to parameters of the types `params`, coerced
+    /// to those types. Zero-sized values are not passed, so are left out.
+    pub fn call_args(&mut self, params: &[TypeExpr], args: &[Expr]) -> Vec<Value> {
+        let mut values = vec![];
+        for (arg, param) in args.iter().zip(params) {
+            let value = arg.translate(self);
+            if self.abi_type(param).is_some() {
+                values.push(self.coerce(value, &arg.ty, param));
+            }
+        }
+        values
+    }
+
+    /// Emit the address of `value`, a value of the type `ty` that a method is
+    /// called on. Receivers are passed by reference, so scalars are spilled
+    /// to the stack.
+    fn receiver(&mut self, value: Value, ty: &TypeExpr) -> Value {
+        if self.is_aggregate(ty) {
+            return value;
+        }
+        let size = self.types.resolve(ty).and_then(|entry| entry.size);
+        let size = size.expect("cannot call a method on an unsized value");
+        let slot = self
+            .builder
+            .create_sized_stack_slot(StackSlotData::new(StackSlotKind::ExplicitSlot, size as u32));
+        let pointer_type = self.pointer_type();
+        let addr = self.builder.ins().stack_addr(pointer_type, slot, 0);
+        self.store_value(addr, ty, value);
+        addr
+    }
+
+    /// Emit a direct call to the function declared as `name`, passing it
+    /// `args`. Returns the value it returns.
+    fn call_direct(&mut self, name: &str, args: &[Value]) -> Value {
+        let Some(FuncOrDataId::Func(id)) = self.module.get_name(name) else {
+            panic!("`{name}` was not declared");
+        };
+        let func = self.module.declare_func_in_func(id, self.builder.func);
+        let call = self.builder.ins().call(func, args);
+        self.call_result(call)
+    }
+
+    /// Return the value returned by the given call, or a placeholder if it
+    /// returns a zero-sized value, which is not returned.
+    pub fn call_result(&mut self, call: Inst) -> Value {
+        match self.builder.inst_results(call).first() {
+            Some(value) => *value,
+            None => self.unit(),
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use std::cmp::Ordering;
+
+use cranelift::prelude::{types, InstBuilder, TrapCode, Type, Value};
+use fluxc_ast::TypeExpr;
+use fluxc_types::{
+    cast::{conversion, is_signed, Conversion},
+    lower::erase,
+};
+
+use crate::TranslationContext;
+
+impl TranslationContext<'_> {
+    /// Emit the conversion of `value`, a value of the type `from`, to the type
+    /// `to`. The cast must have been checked by the type checker.
+    pub fn cast(&mut self, value: Value, from: &TypeExpr, to: &TypeExpr) -> Value {
+        match conversion(from, to).expect("cast has no conversion") {
+            Conversion::Coerce => self.coerce(value, from, to),
+            Conversion::IntToFloat => {
+                let ty = self.cast_type(to);
+                match is_signed(&erase(from)) {
+                    true => self.builder.ins().fcvt_from_sint(ty, value),
+                    false => self.builder.ins().fcvt_from_uint(ty, value),
+                }
+            }
+            // floats are converted to 64-bit integers, which are then narrowed
+            // to the target type
+            Conversion::FloatToInt => {
+                let wide = match is_signed(&erase(to)) {
+                    true => self.builder.ins().fcvt_to_sint_sat(types::I64, value),
+                    false => self.builder.ins().fcvt_to_uint_sat(types::I64, value),
+                };
+                self.resize(wide, types::I64, self.cast_type(to), false)
+            }
+            Conversion::Resize => {
+                let (from_ty, to_ty) = (self.cast_type(from), self.cast_type(to));
+                self.resize(value, from_ty, to_ty, is_signed(&erase(from)))
+            }
+            Conversion::FloatResize => {
+                let (from_ty, to_ty) = (self.cast_type(from), self.cast_type(to));
+                match from_ty.bits().cmp(&to_ty.bits()) {
+                    Ordering::Equal => value,
+                    Ordering::Less => self.builder.ins().fpromote(to_ty, value),
+                    Ordering::Greater => self.builder.ins().fdemote(to_ty, value),
+                }
+            }
+            // the payload of a union is only read once its tag is known to be
+            // one of the target's
+            Conversion::Extract => {
+                let union =
+                    self.types.resolve(from).expect("cannot cast from an unresolved type").id;
+                let holds = self.is_variant(value, union, to);
+                self.builder.ins().trapz(holds, TrapCode::User(0));
+                self.narrow(value, union, to)
+            }
+        }
+    }
+
+    /// Emit the widening or narrowing of the integer `value` from `from` to
+    /// `to`. Narrowing keeps the lowest bits, and widening extends the sign of
+    /// signed integers.
+    fn resize(&mut self, value: Value, from: Type, to: Type, signed: bool) -> Value {
+        match from.bits().cmp(&to.bits()) {
+            Ordering::Equal => value,
+            Ordering::Less if signed => self.builder.ins().sextend(to, value),
+            Ordering::Less => self.builder.ins().uextend(to, value),
+            Ordering::Greater => self.builder.ins().ireduce(to, value),
+        }
+    }
+
+    /// Return the Cranelift type holding values of the given scalar type.
+    fn cast_type(&self, ty: &TypeExpr) -> Type {
+        let entry = self.types.resolve(ty).expect("cannot cast an unresolved type");
+        self.ir_type(entry)
+    }
+}
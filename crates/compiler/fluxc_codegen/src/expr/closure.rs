@@ -0,0 +1,145 @@
+use cranelift::{
+    codegen::ir::{Inst, Signature},
+    prelude::{AbiParam, InstBuilder, Value},
+};
+use cranelift_module::{FuncId, FuncOrDataId, Module};
+use fluxc_ast::TypeExpr;
+use fluxc_hir::{Closure, Expr, Variable};
+use fluxc_types::{lower::env_name, TypeId};
+
+use crate::{mangle_closure, mangle_thunk, TranslationContext};
+
+impl TranslationContext<'_> {
+    /// Emit a closure value of the function type `ty` holding the declared
+    /// function `name`. It is called through its thunk, with no environment.
+    pub fn function_value(&mut self, name: &str, ty: &TypeExpr) -> Value {
+        let closure = self.types.resolve(ty).expect("function of an unresolved type").id;
+        let thunk = self.thunk_id(name);
+        let pointer_type = self.pointer_type();
+        let env = self.builder.ins().iconst(pointer_type, 0);
+        self.make_closure(closure, thunk, env)
+    }
+
+    /// Emit the closure `expr` of the function type `ty`, capturing the
+    /// current values of the variables it captures.
+    pub fn closure(&mut self, expr: &Closure, ty: &TypeExpr) -> Value {
+        let values: Vec<_> =
+            expr.captures.iter().map(|variable| self.local(variable.id, &variable.ty)).collect();
+        let captures: Vec<_> = expr.captures.iter().zip(values).collect();
+        let env = self.capture(&expr.function.name, &captures);
+        let code = self.closure_id(&expr.function.name);
+        let closure = self.types.resolve(ty).expect("closure of an unresolved type").id;
+        self.make_closure(closure, code, env)
+    }
+
+    /// Emit a call through the closure value `callee` of the function type
+    /// `ty`, passing it `args`. Returns the value it returns.
+    pub fn call_value(&mut self, callee: Value, ty: &TypeExpr, args: &[Expr]) -> Value {
+        let TypeExpr::Function(params, _) = ty else {
+            panic!("type `{ty}` is not a function type");
+        };
+        let args = self.call_args(params, args);
+        let closure = self.types.resolve(ty).expect("callee of an unresolved type").id;
+        let signature = self.closure_signature(ty);
+        let call = self.call_closure(closure, callee, signature, &args);
+        self.call_result(call)
+    }
+
+    /// Compute the Cranelift signature of calls through closure values of the
+    /// given function type, which take the environment of the closure as
+    /// their first argument.
+    pub fn closure_signature(&self, ty: &TypeExpr) -> Signature {
+        let TypeExpr::Function(params, ret) = ty else {
+            panic!("type `{ty}` is not a function type");
+        };
+        let mut signature = self.module.make_signature();
+        signature.params.push(AbiParam::new(self.pointer_type()));
+        signature
+            .params
+            .extend(params.iter().filter_map(|ty| self.abi_type(ty)).map(AbiParam::new));
+        signature.returns.extend(self.abi_type(ret).map(AbiParam::new));
+        signature
+    }
+
+    /// Find the thunk through which closure values holding the named function
+    /// call it.
+    pub fn thunk_id(&self, name: &str) -> FuncId {
+        match self.module.get_name(&mangle_thunk(name)) {
+            Some(FuncOrDataId::Func(id)) => id,
+            _ => panic!("`{name}` has no thunk"),
+        }
+    }
+
+    /// Find the function the named closure created by the function being
+    /// translated is lifted to.
+    pub fn closure_id(&self, name: &str) -> FuncId {
+        *self.closures.get(name).expect("closure was not lifted")
+    }
+
+    /// Emit a closure value of the function type `closure`, calling the
+    /// function `code` with the environment at `env`. Returns the address of
+    /// the closure.
+    pub fn make_closure(&mut self, closure: TypeId, code: FuncId, env: Value) -> Value {
+        let ptr_ty = self.module.target_config().pointer_type();
+        let slot = self.alloc_struct(closure);
+        let base = self.builder.ins().stack_addr(ptr_ty, slot, 0);
+        let code = self.module.declare_func_in_func(code, self.builder.func);
+        let code = self.builder.ins().func_addr(ptr_ty, code);
+        self.store_field(base, closure, "code", code);
+        self.store_field(base, closure, "env", env);
+        base
+    }
+
+    /// Emit the environment of the named closure created by the function
+    /// being translated, copying in the value of each of its captured
+    /// variables. Environments may outlive the function creating them, so are
+    /// allocated on the heap. Returns the address of the environment, which is
+    /// null for closures capturing nothing.
+    pub fn capture(&mut self, name: &str, captures: &[(&Variable, Value)]) -> Value {
+        let ptr_ty = self.module.target_config().pointer_type();
+        if captures.is_empty() {
+            return self.builder.ins().iconst(ptr_ty, 0);
+        }
+        let env = self
+            .types
+            .find_by_name(env_name(&mangle_closure(&self.enclosing, name)))
+            .expect("failed to find environment");
+        let (env, size) = (env.id, env.size.expect("environment is unsized"));
+        let base = self.malloc(size);
+        for (variable, value) in captures {
+            let field = self.field_address(base, env, &variable.id.to_string());
+            self.store_value(field, &variable.ty, *value);
+        }
+        base
+    }
+
+    /// Emit the value of the given variable captured by the function being
+    /// translated, which must be a lifted closure, from its environment at
+    /// `env`. Captured aggregates are held by reference into the environment.
+    pub fn load_capture(&mut self, env: Value, variable: &Variable) -> Value {
+        let env_ty = self
+            .types
+            .find_by_name(env_name(&self.enclosing))
+            .expect("failed to find environment")
+            .id;
+        let field = self.field_address(env, env_ty, &variable.id.to_string());
+        self.load_value(field, &variable.ty)
+    }
+
+    /// Emit an indirect call through the closure value of the function type
+    /// `closure` at `base`. The environment of the closure is passed ahead of
+    /// `args`. `signature` must include the environment.
+    pub fn call_closure(
+        &mut self,
+        closure: TypeId,
+        base: Value,
+        signature: Signature,
+        args: &[Value],
+    ) -> Inst {
+        let code = self.load_field(base, closure, "code");
+        let env = self.load_field(base, closure, "env");
+        let signature = self.builder.import_signature(signature);
+        let args = std::iter::once(env).chain(args.iter().copied()).collect::<Vec<_>>();
+        self.builder.ins().call_indirect(signature, code, &args)
+    }
+}
@@ -1,10 +1,63 @@
-use fluxc_ast::Conditional;
-use fluxc_errors::CompilerError;
+use cranelift::prelude::{InstBuilder, TrapCode, Value};
+use fluxc_ast::TypeExpr;
+use fluxc_hir::{Block, Conditional};
 
 use crate::{Translate, TranslationContext};
 
-impl Translate for Conditional {
-    fn translate<'a>(&self, _ctx: &mut TranslationContext<'a>) -> Result<(), CompilerError> {
-        todo!()
+impl TranslationContext<'_> {
+    /// Emit the conditional `expr`, which evaluates to a value of the type
+    /// `ty`. Each condition is checked in turn, running the block guarded by
+    /// the first that holds, or the `else` block if none do.
+    pub fn conditional(&mut self, expr: &Conditional, ty: &TypeExpr) -> Value {
+        let (merge, result) = self.merge_block(ty);
+        for (condition, block) in &expr.branches {
+            let holds = condition.translate(self);
+            let then_block = self.builder.create_block();
+            let next_block = self.builder.create_block();
+            self.builder.ins().brnz(holds, then_block, &[]);
+            self.builder.ins().jump(next_block, &[]);
+
+            self.builder.switch_to_block(then_block);
+            self.builder.seal_block(then_block);
+            let value = self.branch_value(block, ty);
+            self.jump_to(merge, &[value]);
+
+            self.builder.switch_to_block(next_block);
+            self.builder.seal_block(next_block);
+        }
+        match &expr.else_block {
+            Some(block) => {
+                let value = self.branch_value(block, ty);
+                self.jump_to(merge, &[value]);
+            }
+            // conditionals producing a value without an `else` are exhaustive,
+            // so some condition always holds
+            None if self.abi_type(ty).is_some() => {
+                self.builder.ins().trap(TrapCode::UnreachableCodeReached);
+            }
+            None => {
+                let unit = self.unit();
+                self.jump_to(merge, &[unit]);
+            }
+        }
+        self.builder.switch_to_block(merge);
+        self.builder.seal_block(merge);
+        result
+    }
+
+    /// Emit the given block, which produces a value of the type `ty` for the
+    /// conditional it is a branch of.
+    fn branch_value(&mut self, block: &Block, ty: &TypeExpr) -> Value {
+        let value = block.translate(self);
+        self.coerce_branch(value, &block.ty, ty)
+    }
+
+    /// Coerce `value`, the value of the type `from` produced by a branch of a
+    /// conditional or match, to the type `ty` of the conditional or match.
+    pub fn coerce_branch(&mut self, value: Value, from: &TypeExpr, ty: &TypeExpr) -> Value {
+        match self.abi_type(ty) {
+            Some(_) => self.coerce(value, from, ty),
+            None => self.unit(),
+        }
     }
 }
@@ -1,10 +1,25 @@
-use fluxc_ast::Loop;
-use fluxc_errors::CompilerError;
+use cranelift::prelude::{InstBuilder, Value};
+use fluxc_hir::Loop;
 
 use crate::{Translate, TranslationContext};
 
-impl Translate for Loop {
-    fn translate<'a>(&self, _ctx: &mut TranslationContext<'a>) -> Result<(), CompilerError> {
-        todo!()
+impl TranslationContext<'_> {
+    /// Emit the unconditional loop `expr`, which runs its block until it
+    /// breaks.
+    pub fn loop_expr(&mut self, expr: &Loop) -> Value {
+        let body = self.builder.create_block();
+        let exit = self.builder.create_block();
+        self.builder.ins().jump(body, &[]);
+
+        self.builder.switch_to_block(body);
+        self.loops.push(exit);
+        expr.block.translate(self);
+        self.loops.pop();
+        self.jump_to(body, &[]);
+        self.builder.seal_block(body);
+
+        self.builder.switch_to_block(exit);
+        self.builder.seal_block(exit);
+        self.unit()
     }
 }
@@ -1,10 +1,52 @@
-use fluxc_ast::Match;
-use fluxc_errors::CompilerError;
+use cranelift::prelude::{InstBuilder, TrapCode, Value};
+use fluxc_ast::TypeExpr;
+use fluxc_hir::Match;
 
 use crate::{Translate, TranslationContext};
 
-impl Translate for Match {
-    fn translate<'a>(&self, _ctx: &mut TranslationContext<'a>) -> Result<(), CompilerError> {
-        todo!()
+impl TranslationContext<'_> {
+    /// Emit the match `expr`, which evaluates to a value of the type `ty`. The
+    /// value matched is compared to each pattern in turn, producing the value
+    /// of the first branch whose pattern it equals, or of the `else` branch if
+    /// none do.
+    pub fn match_expr(&mut self, expr: &Match, ty: &TypeExpr) -> Value {
+        let scrutinee = expr.expr.translate(self);
+        let (merge, result) = self.merge_block(ty);
+        for (pattern, value) in &expr.branches {
+            let pattern_value = pattern.translate(self);
+            let matches = self.equals(scrutinee, &expr.expr.ty, pattern_value, &pattern.ty);
+            let then_block = self.builder.create_block();
+            let next_block = self.builder.create_block();
+            self.builder.ins().brnz(matches, then_block, &[]);
+            self.builder.ins().jump(next_block, &[]);
+
+            self.builder.switch_to_block(then_block);
+            self.builder.seal_block(then_block);
+            let translated = value.translate(self);
+            let value = self.coerce_branch(translated, &value.ty, ty);
+            self.jump_to(merge, &[value]);
+
+            self.builder.switch_to_block(next_block);
+            self.builder.seal_block(next_block);
+        }
+        // matches producing a value are exhaustive, so some pattern always
+        // matches unless there is an `else` branch
+        match (&expr.else_value, self.abi_type(ty)) {
+            (Some(value), _) => {
+                let translated = value.translate(self);
+                let value = self.coerce_branch(translated, &value.ty, ty);
+                self.jump_to(merge, &[value]);
+            }
+            (None, Some(_)) => {
+                self.builder.ins().trap(TrapCode::UnreachableCodeReached);
+            }
+            (None, None) => {
+                let unit = self.unit();
+                self.jump_to(merge, &[unit]);
+            }
+        }
+        self.builder.switch_to_block(merge);
+        self.builder.seal_block(merge);
+        result
     }
 }
@@ -1,10 +1,32 @@
-use fluxc_ast::While;
-use fluxc_errors::CompilerError;
+use cranelift::prelude::{InstBuilder, Value};
+use fluxc_hir::While;
 
 use crate::{Translate, TranslationContext};
 
-impl Translate for While {
-    fn translate<'a>(&self, _ctx: &mut TranslationContext<'a>) -> Result<(), CompilerError> {
-        todo!()
+impl TranslationContext<'_> {
+    /// Emit the conditional loop `expr`, which runs its block for as long as
+    /// its condition holds, or until it breaks.
+    pub fn while_expr(&mut self, expr: &While) -> Value {
+        let header = self.builder.create_block();
+        let body = self.builder.create_block();
+        let exit = self.builder.create_block();
+        self.builder.ins().jump(header, &[]);
+
+        self.builder.switch_to_block(header);
+        let holds = expr.condition.translate(self);
+        self.builder.ins().brz(holds, exit, &[]);
+        self.builder.ins().jump(body, &[]);
+
+        self.builder.switch_to_block(body);
+        self.builder.seal_block(body);
+        self.loops.push(exit);
+        expr.block.translate(self);
+        self.loops.pop();
+        self.jump_to(header, &[]);
+        self.builder.seal_block(header);
+
+        self.builder.switch_to_block(exit);
+        self.builder.seal_block(exit);
+        self.unit()
     }
 }
@@ -0,0 +1,63 @@
+use cranelift::{
+    codegen::ir::{Inst, Signature},
+    prelude::{InstBuilder, Value},
+};
+use cranelift_module::{DataId, Module};
+use fluxc_ast::TypeExpr;
+use fluxc_types::{vtable::vtable_name, TypeId};
+
+use crate::TranslationContext;
+
+impl TranslationContext<'_> {
+    /// Emit a fat pointer of the interface type `interface`, pointing to the
+    /// value at `data` and the vtable `vtable`. Returns the address of the
+    /// fat pointer.
+    pub fn make_fat_pointer(&mut self, interface: TypeId, data: Value, vtable: DataId) -> Value {
+        let ptr_ty = self.module.target_config().pointer_type();
+        let slot = self.alloc_struct(interface);
+        let fat = self.builder.ins().stack_addr(ptr_ty, slot, 0);
+        let vtable = self.module.declare_data_in_func(vtable, self.builder.func);
+        let vtable = self.builder.ins().global_value(ptr_ty, vtable);
+        self.store_field(fat, interface, "data", data);
+        self.store_field(fat, interface, "vtable", vtable);
+        fat
+    }
+
+    /// Emit a value of the interface type `interface` from `value`, a value of
+    /// the type `ty` implementing it through the vtable `vtable`. The value
+    /// may outlive the stack slot holding it along with the fat pointer, so
+    /// the fat pointer refers to a copy of it on the heap.
+    pub fn upcast(
+        &mut self,
+        value: Value,
+        ty: &TypeExpr,
+        interface: &TypeExpr,
+        vtable: DataId,
+    ) -> Value {
+        let fat = self.types.resolve(interface).expect("cannot upcast to an unresolved type").id;
+        let data = self.heap_copy(value, ty);
+        self.make_fat_pointer(fat, data, vtable)
+    }
+
+    /// Emit an indirect call to the named method through the fat pointer of
+    /// the interface type `interface` at `fat`. The data pointer is passed as
+    /// the receiver, ahead of `args`. `signature` must include the receiver.
+    pub fn call_dyn(
+        &mut self,
+        interface: TypeId,
+        fat: Value,
+        method: &str,
+        signature: Signature,
+        args: &[Value],
+    ) -> Inst {
+        let name = &self.types.find(interface).expect("failed to find type").name;
+        let vtable_ty =
+            self.types.find_by_name(vtable_name(name)).expect("failed to find vtable").id;
+        let data = self.load_field(fat, interface, "data");
+        let vtable = self.load_field(fat, interface, "vtable");
+        let callee = self.load_field(vtable, vtable_ty, method);
+        let signature = self.builder.import_signature(signature);
+        let args = std::iter::once(data).chain(args.iter().copied()).collect::<Vec<_>>();
+        self.builder.ins().call_indirect(signature, callee, &args)
+    }
+}
@@ -0,0 +1,64 @@
+use cranelift::prelude::{types, AbiParam, InstBuilder, Type, Value};
+use cranelift_module::{Linkage, Module};
+
+use crate::TranslationContext;
+
+impl TranslationContext<'_> {
+    /// Emit a call to the named function of the C standard library, which
+    /// takes arguments of the types `params` and returns a value of the type
+    /// `ret`. Returns the value it returns.
+    pub fn call_libc(&mut self, name: &str, params: &[Type], ret: Type, args: &[Value]) -> Value {
+        let mut signature = self.module.make_signature();
+        signature.params.extend(params.iter().copied().map(AbiParam::new));
+        signature.returns.push(AbiParam::new(ret));
+        let func = self
+            .module
+            .declare_function(name, Linkage::Import, &signature)
+            .unwrap_or_else(|_| panic!("failed to declare {name}"));
+        let func = self.module.declare_func_in_func(func, self.builder.func);
+        let call = self.builder.ins().call(func, args);
+        self.builder.inst_results(call)[0]
+    }
+
+    /// Emit a call to `malloc`, allocating `size` bytes on the heap. Returns
+    /// the address of the allocation.
+    pub fn malloc(&mut self, size: usize) -> Value {
+        let ptr_ty = self.pointer_type();
+        let size = self.builder.ins().iconst(ptr_ty, size as i64);
+        self.call_libc("malloc", &[ptr_ty], ptr_ty, &[size])
+    }
+
+    /// Emit a call to `strcmp`, comparing the strings `lhs` and `rhs`.
+    /// Returns a negative, zero or positive 32-bit integer if `lhs` is
+    /// ordered before, equal to or after `rhs`.
+    pub fn strcmp(&mut self, lhs: Value, rhs: Value) -> Value {
+        let ptr_ty = self.pointer_type();
+        self.call_libc("strcmp", &[ptr_ty, ptr_ty], types::I32, &[lhs, rhs])
+    }
+
+    /// Emit a call to `memcmp`, comparing the `size` bytes at `lhs` and `rhs`.
+    /// Returns zero if they are equal.
+    pub fn memcmp(&mut self, lhs: Value, rhs: Value, size: usize) -> Value {
+        let pointer_type = self.pointer_type();
+        let size = self.builder.ins().iconst(pointer_type, size as i64);
+        self.builder.call_memcmp(self.module.target_config(), lhs, rhs, size)
+    }
+
+    /// Emit the concatenation of the strings `lhs` and `rhs` into a new
+    /// string on the heap. Returns the address of the new string.
+    pub fn concat(&mut self, lhs: Value, rhs: Value) -> Value {
+        let ptr_ty = self.pointer_type();
+        let lhs_len = self.call_libc("strlen", &[ptr_ty], ptr_ty, &[lhs]);
+        let rhs_len = self.call_libc("strlen", &[ptr_ty], ptr_ty, &[rhs]);
+        let len = self.builder.ins().iadd(lhs_len, rhs_len);
+        let size = self.builder.ins().iadd_imm(len, 1);
+        let string = self.call_libc("malloc", &[ptr_ty], ptr_ty, &[size]);
+        let memcpy = [ptr_ty, ptr_ty, ptr_ty];
+        self.call_libc("memcpy", &memcpy, ptr_ty, &[string, lhs, lhs_len]);
+        // the null terminator of `rhs` terminates the new string
+        let tail = self.builder.ins().iadd(string, lhs_len);
+        let rhs_size = self.builder.ins().iadd_imm(rhs_len, 1);
+        self.call_libc("memcpy", &memcpy, ptr_ty, &[tail, rhs, rhs_size]);
+        string
+    }
+}
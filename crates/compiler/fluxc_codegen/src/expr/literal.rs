@@ -1,10 +1,74 @@
-use fluxc_ast::Literal;
-use fluxc_errors::CompilerError;
+use cranelift::prelude::{types, InstBuilder, Value};
+use cranelift_module::{DataContext, DataId, Module};
+use fluxc_ast::{Literal, Primitive, TypeExpr};
 
-use crate::{Translate, TranslationContext};
+use crate::{constant_data, TranslationContext};
 
-impl Translate for Literal {
-    fn translate<'a>(&self, _ctx: &mut TranslationContext<'a>) -> Result<(), CompilerError> {
-        todo!()
+impl TranslationContext<'_> {
+    /// Emit the value of the given literal of the type `ty`. Numbers are
+    /// emitted as the numeric type they were inferred to have, so `1` may be
+    /// a `u8` or a `float`.
+    pub fn literal(&mut self, literal: &Literal, ty: &TypeExpr) -> Value {
+        let Some(ir_ty) = self.abi_type(ty) else { return self.unit() };
+        let ins = self.builder.ins();
+        match literal {
+            Literal::Int(int) => match ir_ty {
+                types::F64 => ins.f64const(*int as f64),
+                types::F32 => ins.f32const(*int as f32),
+                _ => ins.iconst(ir_ty, *int),
+            },
+            Literal::Float(float) => match ir_ty {
+                types::F32 => ins.f32const(*float as f32),
+                _ => ins.f64const(*float),
+            },
+            Literal::Char(c) => ins.iconst(ir_ty, *c as i64),
+            Literal::Bool(bool) => ins.iconst(ir_ty, *bool as i64),
+            Literal::String(string) => self.string(string),
+            Literal::Unit => self.unit(),
+            Literal::Array(_) => unreachable!("array literals are not parsed"),
+        }
+    }
+
+    /// Emit the given string as anonymous read-only data. Returns the address
+    /// of the string.
+    pub fn string(&mut self, string: &str) -> Value {
+        let ty = TypeExpr::Primitive(Primitive::StringLiteral(string.to_string()));
+        let id = self.constant_data(&ty).expect("strings are held as data");
+        self.data_address(id)
+    }
+
+    /// Emit the value held by the given literal type as anonymous read-only
+    /// data, returning its ID, or `None` if it is emitted as an immediate
+    /// instead.
+    pub fn constant_data(&mut self, ty: &TypeExpr) -> Option<DataId> {
+        let bytes = constant_data(ty)?;
+        let mut data_ctx = DataContext::new();
+        data_ctx.define(bytes.into_boxed_slice());
+        let id = self.module.declare_anonymous_data(false, false).expect("failed to declare data");
+        self.module.define_data(id, &data_ctx).expect("failed to define data");
+        Some(id)
+    }
+
+    /// Emit the address of the given data object.
+    pub fn data_address(&mut self, id: DataId) -> Value {
+        let data = self.module.declare_data_in_func(id, self.builder.func);
+        let pointer_type = self.pointer_type();
+        self.builder.ins().global_value(pointer_type, data)
+    }
+
+    /// Emit the value held by the given literal type, such as the type of a
+    /// reference to a constant, as an immediate. Returns `None` if the type
+    /// does not hold a scalar value.
+    pub fn immediate(&mut self, ty: &TypeExpr) -> Option<Value> {
+        let TypeExpr::Primitive(primitive) = ty else { return None };
+        let ins = self.builder.ins();
+        Some(match primitive {
+            Primitive::IntLiteral(int) => ins.iconst(types::I64, *int),
+            Primitive::FloatLiteral(float) => ins.f64const(*float),
+            Primitive::CharLiteral(c) => ins.iconst(types::I64, *c as i64),
+            Primitive::True => ins.iconst(types::I8, 1),
+            Primitive::False => ins.iconst(types::I8, 0),
+            _ => return None,
+        })
     }
 }
@@ -1,6 +1,123 @@
 //! Contains the expression AST data structures.
 
 mod block_expr;
+mod call;
+mod cast;
+mod closure;
 mod control;
+mod dyn_call;
+mod libc;
 mod literal;
 mod operation;
+mod propagate;
+mod struct_expr;
+mod tagged;
+
+use cranelift::prelude::{types, Block, InstBuilder, TrapCode, Type, Value};
+use cranelift_module::Module;
+use fluxc_ast::TypeExpr;
+use fluxc_hir::{Expr, ExprKind};
+
+use crate::{abi_type, is_aggregate, Translate, TranslationContext};
+
+impl Translate for Expr {
+    type Output = Value;
+
+    fn translate(&self, ctx: &mut TranslationContext<'_>) -> Value {
+        match &self.kind {
+            ExprKind::Literal(literal) => ctx.literal(literal, &self.ty),
+            ExprKind::Local(id) => ctx.local(*id, &self.ty),
+            ExprKind::Function(name) => ctx.function_value(name, &self.ty),
+            ExprKind::Method(..) => unreachable!("methods without a receiver are only called"),
+            ExprKind::BinaryExpr(binary) => ctx.binary_expr(binary, &self.ty),
+            ExprKind::UnaryExpr(unary) => ctx.unary_expr(unary, &self.ty),
+            ExprKind::Cast(cast) => {
+                let value = cast.expr.translate(ctx);
+                ctx.cast(value, &cast.expr.ty, &self.ty)
+            }
+            ExprKind::Block(block) => block.translate(ctx),
+            ExprKind::Call(call) => ctx.call(call),
+            ExprKind::MethodCall(call) => ctx.method_call(call, &self.ty),
+            ExprKind::Conditional(conditional) => ctx.conditional(conditional, &self.ty),
+            ExprKind::Loop(loop_expr) => ctx.loop_expr(loop_expr),
+            ExprKind::While(while_expr) => ctx.while_expr(while_expr),
+            ExprKind::Match(match_expr) => ctx.match_expr(match_expr, &self.ty),
+            ExprKind::StructExpr(struct_expr) => ctx.struct_expr(struct_expr, &self.ty),
+            ExprKind::FieldAccess(access) => ctx.field_access(access, &self.ty),
+            ExprKind::Closure(closure) => ctx.closure(closure, &self.ty),
+        }
+    }
+}
+
+impl TranslationContext<'_> {
+    /// Return the Cranelift type of pointers on the target.
+    pub fn pointer_type(&self) -> Type {
+        self.module.target_config().pointer_type()
+    }
+
+    /// Return the Cranelift type used to pass values of the given type, or
+    /// `None` for zero-sized types.
+    pub fn abi_type(&self, ty: &TypeExpr) -> Option<Type> {
+        abi_type(self.types, ty, self.pointer_type())
+    }
+
+    /// Return the Cranelift type of the variables and block parameters
+    /// holding values of the given type. Zero-sized values are held as
+    /// placeholders.
+    pub fn var_type(&self, ty: &TypeExpr) -> Type {
+        self.abi_type(ty).unwrap_or(types::I8)
+    }
+
+    /// Returns true if values of the given type are held by reference.
+    pub fn is_aggregate(&self, ty: &TypeExpr) -> bool {
+        self.types.resolve(ty).is_some_and(is_aggregate)
+    }
+
+    /// Emit the placeholder zero-sized values evaluate to. Zero-sized values
+    /// hold nothing, so are never read.
+    pub fn unit(&mut self) -> Value {
+        self.builder.ins().iconst(types::I8, 0)
+    }
+
+    /// Copy the aggregate `value` of the type `ty` to a new stack slot, so
+    /// that it can be mutated without affecting the original. Returns the
+    /// address of the copy. Scalars are returned as they are.
+    pub fn copy_value(&mut self, value: Value, ty: &TypeExpr) -> Value {
+        if !self.is_aggregate(ty) {
+            return value;
+        }
+        let id = self.types.resolve(ty).expect("cannot copy an unresolved type").id;
+        let slot = self.alloc_struct(id);
+        let pointer_type = self.pointer_type();
+        let addr = self.builder.ins().stack_addr(pointer_type, slot, 0);
+        self.store_value(addr, ty, value);
+        addr
+    }
+
+    /// Create a block that the branches of a conditional, match or loop jump
+    /// to once they have produced a value of the type `ty`, which becomes its
+    /// parameter.
+    pub fn merge_block(&mut self, ty: &TypeExpr) -> (Block, Value) {
+        let block = self.builder.create_block();
+        let param = self.builder.append_block_param(block, self.var_type(ty));
+        (block, param)
+    }
+
+    /// Emit a jump to `block` passing it `args`, unless the current block is
+    /// unreachable, such as after a `return`, in which case the values may
+    /// not have been produced, so it traps instead.
+    pub fn jump_to(&mut self, block: Block, args: &[Value]) {
+        match self.builder.is_unreachable() {
+            true => self.builder.ins().trap(TrapCode::UnreachableCodeReached),
+            false => self.builder.ins().jump(block, args),
+        };
+    }
+
+    /// Switch to a new block that no code jumps to, so that the code following
+    /// a `return` or `break` can be emitted, although it never runs.
+    pub fn unreachable_block(&mut self) {
+        let block = self.builder.create_block();
+        self.builder.switch_to_block(block);
+        self.builder.seal_block(block);
+    }
+}
@@ -1,10 +1,305 @@
-use fluxc_ast::BinaryExpr;
-use fluxc_errors::CompilerError;
+use cranelift::prelude::{types, FloatCC, InstBuilder, IntCC, Value};
+use fluxc_ast::{BinaryOp, Exclude, Primitive, Simplify, TypeExpr};
+use fluxc_hir::{BinaryExpr, Expr, ExprKind};
+use fluxc_types::{
+    cast::{is_float, is_signed},
+    lower::{erase, is_tagged},
+};
 
 use crate::{Translate, TranslationContext};
 
-impl Translate for BinaryExpr {
-    fn translate<'a>(&self, _ctx: &mut TranslationContext<'a>) -> Result<(), CompilerError> {
-        todo!()
+impl TranslationContext<'_> {
+    /// Emit the binary operation `expr`, which evaluates to a value of the
+    /// type `ty`. Assignments evaluate to unit.
+    pub fn binary_expr(&mut self, expr: &BinaryExpr, ty: &TypeExpr) -> Value {
+        let (lhs, rhs) = (&expr.lhs, &expr.rhs);
+        match expr.kind {
+            BinaryOp::LogicalAnd | BinaryOp::LogicalOr => self.short_circuit(expr.kind, lhs, rhs),
+            BinaryOp::Coalesce => self.coalesce(lhs, rhs, ty),
+            BinaryOp::Assign => {
+                let value = rhs.translate(self);
+                self.assign(lhs, value, &rhs.ty);
+                self.unit()
+            }
+            kind if kind.is_assignment() => {
+                let value = match compound_operator(kind) {
+                    op @ (BinaryOp::LogicalAnd | BinaryOp::LogicalOr) => {
+                        self.short_circuit(op, lhs, rhs)
+                    }
+                    op => {
+                        let (lhs_value, rhs_value) = (lhs.translate(self), rhs.translate(self));
+                        self.arithmetic(op, lhs_value, rhs_value, &lhs.ty)
+                    }
+                };
+                self.assign(lhs, value, &lhs.ty);
+                self.unit()
+            }
+            kind => {
+                let (lhs_value, rhs_value) = (lhs.translate(self), rhs.translate(self));
+                match kind {
+                    BinaryOp::Eq => self.equals(lhs_value, &lhs.ty, rhs_value, &rhs.ty),
+                    BinaryOp::Ne => {
+                        let equals = self.equals(lhs_value, &lhs.ty, rhs_value, &rhs.ty);
+                        self.builder.ins().bxor_imm(equals, 1)
+                    }
+                    BinaryOp::Lt | BinaryOp::Gt | BinaryOp::Le | BinaryOp::Ge => {
+                        self.compare(kind, lhs_value, rhs_value, &lhs.ty)
+                    }
+                    kind => self.arithmetic(kind, lhs_value, rhs_value, &lhs.ty),
+                }
+            }
+        }
+    }
+
+    /// Emit the logical operation `kind` on `lhs` and `rhs`, which only
+    /// evaluates `rhs` if `lhs` does not decide the result.
+    fn short_circuit(&mut self, kind: BinaryOp, lhs: &Expr, rhs: &Expr) -> Value {
+        let lhs = lhs.translate(self);
+        let rhs_block = self.builder.create_block();
+        let merge = self.builder.create_block();
+        let result = self.builder.append_block_param(merge, types::I8);
+        match kind {
+            BinaryOp::LogicalAnd => self.builder.ins().brz(lhs, merge, &[lhs]),
+            _ => self.builder.ins().brnz(lhs, merge, &[lhs]),
+        };
+        self.builder.ins().jump(rhs_block, &[]);
+
+        self.builder.switch_to_block(rhs_block);
+        self.builder.seal_block(rhs_block);
+        let rhs = rhs.translate(self);
+        self.builder.ins().jump(merge, &[rhs]);
+
+        self.builder.switch_to_block(merge);
+        self.builder.seal_block(merge);
+        result
+    }
+
+    /// Emit the coalescing of `lhs` and `rhs` into a value of the type `ty`,
+    /// which is the value of `lhs` unless it is unit, in which case `rhs` is
+    /// evaluated instead.
+    fn coalesce(&mut self, lhs: &Expr, rhs: &Expr, ty: &TypeExpr) -> Value {
+        let value = lhs.translate(self);
+        let unit = TypeExpr::Primitive(Primitive::Unit);
+        // values that are not optional are always or never unit
+        if !is_tagged(&lhs.ty) {
+            return match erase(&lhs.ty) == unit {
+                true => {
+                    let value = rhs.translate(self);
+                    self.coerce(value, &rhs.ty, ty)
+                }
+                false => self.coerce(value, &lhs.ty, ty),
+            };
+        }
+        let union = self.types.resolve(&lhs.ty).expect("cannot coalesce an unresolved type").id;
+        let present_ty = erase(&lhs.ty).exclude(&unit).simplify();
+        let (merge, result) = self.merge_block(ty);
+        let present = self.builder.create_block();
+        let absent = self.builder.create_block();
+        let is_unit = self.is_variant(value, union, &unit);
+        self.builder.ins().brnz(is_unit, absent, &[]);
+        self.builder.ins().jump(present, &[]);
+
+        self.builder.switch_to_block(present);
+        self.builder.seal_block(present);
+        let present_value = self.narrow(value, union, &present_ty);
+        let present_value = self.coerce(present_value, &present_ty, ty);
+        self.builder.ins().jump(merge, &[present_value]);
+
+        self.builder.switch_to_block(absent);
+        self.builder.seal_block(absent);
+        let rhs_value = rhs.translate(self);
+        let rhs_value = self.coerce(rhs_value, &rhs.ty, ty);
+        self.jump_to(merge, &[rhs_value]);
+
+        self.builder.switch_to_block(merge);
+        self.builder.seal_block(merge);
+        result
+    }
+
+    /// Emit the arithmetic or bitwise operation `kind` on `lhs` and `rhs`,
+    /// which are both values of the type `ty`. Adding strings concatenates
+    /// them.
+    pub fn arithmetic(&mut self, kind: BinaryOp, lhs: Value, rhs: Value, ty: &TypeExpr) -> Value {
+        let ty = erase(ty);
+        if ty == TypeExpr::Primitive(Primitive::String) {
+            return self.concat(lhs, rhs);
+        }
+        let ins = self.builder.ins();
+        if is_float(&ty) {
+            return match kind {
+                BinaryOp::Plus => ins.fadd(lhs, rhs),
+                BinaryOp::Minus => ins.fsub(lhs, rhs),
+                BinaryOp::Mul => ins.fmul(lhs, rhs),
+                BinaryOp::Div => ins.fdiv(lhs, rhs),
+                // the remainder has the sign of the dividend, as in C
+                BinaryOp::Mod => {
+                    let quotient = ins.fdiv(lhs, rhs);
+                    let quotient = self.builder.ins().trunc(quotient);
+                    let product = self.builder.ins().fmul(quotient, rhs);
+                    self.builder.ins().fsub(lhs, product)
+                }
+                kind => unreachable!("`{kind:?}` is not an operation on floats"),
+            };
+        }
+        let signed = is_signed(&ty);
+        match kind {
+            BinaryOp::Plus => ins.iadd(lhs, rhs),
+            BinaryOp::Minus => ins.isub(lhs, rhs),
+            BinaryOp::Mul => ins.imul(lhs, rhs),
+            BinaryOp::Div if signed => ins.sdiv(lhs, rhs),
+            BinaryOp::Div => ins.udiv(lhs, rhs),
+            BinaryOp::Mod if signed => ins.srem(lhs, rhs),
+            BinaryOp::Mod => ins.urem(lhs, rhs),
+            BinaryOp::BitwiseAnd => ins.band(lhs, rhs),
+            BinaryOp::BitwiseOr => ins.bor(lhs, rhs),
+            BinaryOp::BitwiseXor => ins.bxor(lhs, rhs),
+            BinaryOp::Shl => ins.ishl(lhs, rhs),
+            BinaryOp::Shr if signed => ins.sshr(lhs, rhs),
+            BinaryOp::Shr => ins.ushr(lhs, rhs),
+            kind => unreachable!("`{kind:?}` is not an arithmetic operation"),
+        }
+    }
+
+    /// Emit a check that `lhs`, a value of the type `lhs_ty`, equals `rhs`, a
+    /// value of the type `rhs_ty`. Strings are compared by their contents, and
+    /// aggregates byte by byte. Tagged unions equal values of their members
+    /// if they hold an equal value of that member.
+    pub fn equals(
+        &mut self,
+        lhs: Value,
+        lhs_ty: &TypeExpr,
+        rhs: Value,
+        rhs_ty: &TypeExpr,
+    ) -> Value {
+        match (is_tagged(lhs_ty), is_tagged(rhs_ty)) {
+            (true, false) => return self.variant_equals(lhs, lhs_ty, rhs, rhs_ty),
+            (false, true) => return self.variant_equals(rhs, rhs_ty, lhs, lhs_ty),
+            _ => (),
+        }
+        let entry = self.types.resolve(lhs_ty).expect("cannot compare an unresolved type");
+        let ty = erase(lhs_ty);
+        match entry.size {
+            // zero-sized types have a single value
+            Some(0) => self.builder.ins().iconst(types::I8, 1),
+            _ if ty == TypeExpr::Primitive(Primitive::String) => {
+                let ordering = self.strcmp(lhs, rhs);
+                self.builder.ins().icmp_imm(IntCC::Equal, ordering, 0)
+            }
+            _ if is_float(&ty) => self.builder.ins().fcmp(FloatCC::Equal, lhs, rhs),
+            Some(size) if self.is_aggregate(lhs_ty) => {
+                let ordering = self.memcmp(lhs, rhs, size);
+                self.builder.ins().icmp_imm(IntCC::Equal, ordering, 0)
+            }
+            _ => self.builder.ins().icmp(IntCC::Equal, lhs, rhs),
+        }
+    }
+
+    /// Emit a check that the tagged union `union` of the type `union_ty`
+    /// holds `value`, a value of the member type `ty`. The payload is only
+    /// compared once the tag is known to be that of `ty`.
+    fn variant_equals(
+        &mut self,
+        union: Value,
+        union_ty: &TypeExpr,
+        value: Value,
+        ty: &TypeExpr,
+    ) -> Value {
+        let id = self.types.resolve(union_ty).expect("cannot compare an unresolved type").id;
+        let holds = self.is_variant(union, id, ty);
+        if self.abi_type(ty).is_none() {
+            return holds;
+        }
+        let compare = self.builder.create_block();
+        let merge = self.builder.create_block();
+        let result = self.builder.append_block_param(merge, types::I8);
+        self.builder.ins().brz(holds, merge, &[holds]);
+        self.builder.ins().jump(compare, &[]);
+
+        self.builder.switch_to_block(compare);
+        self.builder.seal_block(compare);
+        let payload = self.narrow(union, id, ty);
+        let equals = self.equals(payload, ty, value, ty);
+        self.builder.ins().jump(merge, &[equals]);
+
+        self.builder.switch_to_block(merge);
+        self.builder.seal_block(merge);
+        result
+    }
+
+    /// Emit the ordering comparison `kind` of `lhs` and `rhs`, which are both
+    /// values of the type `ty`. Strings are ordered lexicographically.
+    fn compare(&mut self, kind: BinaryOp, lhs: Value, rhs: Value, ty: &TypeExpr) -> Value {
+        let ty = erase(ty);
+        if is_float(&ty) {
+            let cond = match kind {
+                BinaryOp::Lt => FloatCC::LessThan,
+                BinaryOp::Gt => FloatCC::GreaterThan,
+                BinaryOp::Le => FloatCC::LessThanOrEqual,
+                _ => FloatCC::GreaterThanOrEqual,
+            };
+            return self.builder.ins().fcmp(cond, lhs, rhs);
+        }
+        // strings are compared by the sign of the result of `strcmp`
+        let string = ty == TypeExpr::Primitive(Primitive::String);
+        let (lhs, rhs) = match string {
+            true => (self.strcmp(lhs, rhs), self.builder.ins().iconst(types::I32, 0)),
+            false => (lhs, rhs),
+        };
+        let cond = match (kind, string || is_signed(&ty)) {
+            (BinaryOp::Lt, true) => IntCC::SignedLessThan,
+            (BinaryOp::Gt, true) => IntCC::SignedGreaterThan,
+            (BinaryOp::Le, true) => IntCC::SignedLessThanOrEqual,
+            (_, true) => IntCC::SignedGreaterThanOrEqual,
+            (BinaryOp::Lt, false) => IntCC::UnsignedLessThan,
+            (BinaryOp::Gt, false) => IntCC::UnsignedGreaterThan,
+            (BinaryOp::Le, false) => IntCC::UnsignedLessThanOrEqual,
+            (_, false) => IntCC::UnsignedGreaterThanOrEqual,
+        };
+        self.builder.ins().icmp(cond, lhs, rhs)
+    }
+
+    /// Emit an assignment of `value`, a value of the type `ty`, to the given
+    /// place, which is a local or a field of one. The value is coerced to the
+    /// declared type of the place, which may be wider than the type it has
+    /// been narrowed to.
+    pub fn assign(&mut self, place: &Expr, value: Value, ty: &TypeExpr) {
+        match &place.kind {
+            ExprKind::Local(id) => {
+                let declared = self.locals.get(id).expect("assignment to an undeclared local");
+                let declared = declared.1.clone();
+                let value = self.coerce(value, ty, &declared);
+                self.assign_local(*id, value)
+            }
+            ExprKind::FieldAccess(access) => {
+                let base = access.expr.translate(self);
+                let id =
+                    self.types.resolve(&access.expr.ty).expect("field of an unresolved type").id;
+                let field_ty = self.field_type(&access.expr.ty, &access.field);
+                let value = self.coerce(value, ty, &field_ty);
+                let field = self.field_address(base, id, &access.field);
+                self.store_value(field, &field_ty, value);
+            }
+            _ => unreachable!("assignment to an expression that is not a place"),
+        }
+    }
+}
+
+/// Return the operator the given compound assignment applies to its operands,
+/// such as `+` for `+=`.
+fn compound_operator(kind: BinaryOp) -> BinaryOp {
+    match kind {
+        BinaryOp::PlusEq => BinaryOp::Plus,
+        BinaryOp::MinusEq => BinaryOp::Minus,
+        BinaryOp::MulEq => BinaryOp::Mul,
+        BinaryOp::DivEq => BinaryOp::Div,
+        BinaryOp::ModEq => BinaryOp::Mod,
+        BinaryOp::BitwiseAndEq => BinaryOp::BitwiseAnd,
+        BinaryOp::BitwiseOrEq => BinaryOp::BitwiseOr,
+        BinaryOp::BitwiseXorEq => BinaryOp::BitwiseXor,
+        BinaryOp::LogicalAndEq => BinaryOp::LogicalAnd,
+        BinaryOp::LogicalOrEq => BinaryOp::LogicalOr,
+        BinaryOp::ShlEq => BinaryOp::Shl,
+        BinaryOp::ShrEq => BinaryOp::Shr,
+        kind => kind,
     }
 }
@@ -1,10 +1,74 @@
-use fluxc_ast::UnaryExpr;
-use fluxc_errors::CompilerError;
+use cranelift::prelude::{InstBuilder, Value};
+use fluxc_ast::{BinaryOp, Literal, TypeExpr, UnaryOp};
+use fluxc_hir::UnaryExpr;
+use fluxc_types::{cast::is_float, lower::erase};
 
 use crate::{Translate, TranslationContext};
 
-impl Translate for UnaryExpr {
-    fn translate<'a>(&self, _ctx: &mut TranslationContext<'a>) -> Result<(), CompilerError> {
-        todo!()
+impl TranslationContext<'_> {
+    /// Emit the unary operation `expr`, which evaluates to a value of the
+    /// type `ty`.
+    pub fn unary_expr(&mut self, expr: &UnaryExpr, ty: &TypeExpr) -> Value {
+        match &expr.kind {
+            // suffix operators evaluate to the value before it is updated
+            UnaryOp::Increment | UnaryOp::Decrement => {
+                let value = expr.expr.translate(self);
+                let one = self.literal(&Literal::Int(1), &expr.expr.ty);
+                let op = match expr.kind {
+                    UnaryOp::Increment => BinaryOp::Plus,
+                    _ => BinaryOp::Minus,
+                };
+                let updated = self.arithmetic(op, value, one, &expr.expr.ty);
+                self.assign(&expr.expr, updated, &expr.expr.ty);
+                value
+            }
+            UnaryOp::Propagate => {
+                let value = expr.expr.translate(self);
+                self.propagate(value, &expr.expr.ty, ty)
+            }
+            UnaryOp::LogicalNot => {
+                let value = expr.expr.translate(self);
+                self.builder.ins().bxor_imm(value, 1)
+            }
+            UnaryOp::BitwiseNot => {
+                let value = expr.expr.translate(self);
+                self.builder.ins().bnot(value)
+            }
+            UnaryOp::Negation => {
+                let value = expr.expr.translate(self);
+                match is_float(&erase(ty)) {
+                    true => self.builder.ins().fneg(value),
+                    false => self.builder.ins().ineg(value),
+                }
+            }
+            UnaryOp::Index(index) => {
+                let base = expr.expr.translate(self);
+                self.index(base, &expr.expr.ty, *index, ty)
+            }
+            // references are not yet distinguished from the values they
+            // refer to
+            UnaryOp::Reference | UnaryOp::MutReference | UnaryOp::Dereference => {
+                expr.expr.translate(self)
+            }
+        }
+    }
+
+    /// Emit the element at `index` of the array of the type `array` at
+    /// `base`, whose elements are of the type `elem`. Sized arrays hold their
+    /// elements inline, as fields named after their index, while unsized
+    /// arrays are held by reference to their first element.
+    fn index(&mut self, base: Value, array: &TypeExpr, index: u64, elem: &TypeExpr) -> Value {
+        let addr = match self.is_aggregate(array) {
+            true => {
+                let id = self.types.resolve(array).expect("cannot index an unresolved type").id;
+                self.field_address(base, id, &index.to_string())
+            }
+            false => {
+                let size = self.types.resolve(elem).and_then(|entry| entry.size);
+                let size = size.expect("cannot index an array of unsized elements");
+                self.builder.ins().iadd_imm(base, (index as usize * size) as i64)
+            }
+        };
+        self.load_value(addr, elem)
     }
 }
@@ -0,0 +1,31 @@
+use cranelift::prelude::{InstBuilder, Value};
+use fluxc_ast::{StructDecl, TypeExpr};
+
+use crate::TranslationContext;
+
+impl TranslationContext<'_> {
+    /// Emit the propagation of an error from the tagged union `from` at
+    /// `base`. If the union holds an `Error`, it is returned from the function
+    /// being translated. Otherwise, the union evaluates to the value it holds,
+    /// narrowed to `ty`, which excludes the error.
+    pub fn propagate(&mut self, base: Value, from: &TypeExpr, ty: &TypeExpr) -> Value {
+        let error = StructDecl::error_type();
+        let union = self.types.resolve(from).expect("cannot propagate from an unresolved type").id;
+        let is_error = self.is_variant(base, union, &error);
+        let error_block = self.builder.create_block();
+        let ok_block = self.builder.create_block();
+        self.builder.ins().brnz(is_error, error_block, &[]);
+        self.builder.ins().jump(ok_block, &[]);
+
+        // the early return path
+        self.builder.switch_to_block(error_block);
+        self.builder.seal_block(error_block);
+        let payload = self.payload(base, union, &error);
+        let value = self.load_value(payload, &error);
+        self.return_value(value, &error);
+
+        self.builder.switch_to_block(ok_block);
+        self.builder.seal_block(ok_block);
+        self.narrow(base, union, ty)
+    }
+}
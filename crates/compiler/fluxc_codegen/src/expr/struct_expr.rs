@@ -0,0 +1,196 @@
+use cranelift::{
+    codegen::ir::StackSlot,
+    prelude::{InstBuilder, MemFlags, StackSlotData, StackSlotKind, Type, Value},
+};
+use cranelift_module::Module;
+use fluxc_ast::{Exclude, Primitive, Simplify, TypeExpr};
+use fluxc_hir::{FieldAccess, StructExpr};
+use fluxc_types::{
+    lower::{erase, is_tagged},
+    KeyedTableEntry, TypeId,
+};
+
+use crate::{is_aggregate, scalar_type, Translate, TranslationContext};
+
+impl TranslationContext<'_> {
+    /// Emit the struct literal `expr` of the type `ty`, storing the value of
+    /// each field at its offset within a new stack slot. Returns the address
+    /// of the struct.
+    pub fn struct_expr(&mut self, expr: &StructExpr, ty: &TypeExpr) -> Value {
+        let values: Vec<_> =
+            expr.fields.iter().map(|(name, value)| (name, value, value.translate(self))).collect();
+        if self.abi_type(ty).is_none() {
+            return self.unit();
+        }
+        let id = self.types.resolve(ty).expect("struct literal of an unresolved type").id;
+        let slot = self.alloc_struct(id);
+        let pointer_type = self.pointer_type();
+        let base = self.builder.ins().stack_addr(pointer_type, slot, 0);
+        for (name, value, translated) in values {
+            let field_ty = self.field_type(ty, name);
+            let translated = self.coerce(translated, &value.ty, &field_ty);
+            let field = self.field_address(base, id, name);
+            self.store_value(field, &field_ty, translated);
+        }
+        base
+    }
+
+    /// Emit the access `expr` to a field of the type `ty`. Aggregate fields
+    /// are held inline, so evaluate to their address within the struct.
+    /// Optional accesses on a value that may be unit evaluate to unit if it
+    /// is.
+    pub fn field_access(&mut self, expr: &FieldAccess, ty: &TypeExpr) -> Value {
+        let base = expr.expr.translate(self);
+        if !expr.optional || !is_tagged(&expr.expr.ty) {
+            let id = self.types.resolve(&expr.expr.ty).expect("field of an unresolved type").id;
+            let field = self.field_address(base, id, &expr.field);
+            return self.load_value(field, ty);
+        }
+        let unit = TypeExpr::Primitive(Primitive::Unit);
+        let union = self.types.resolve(&expr.expr.ty).expect("field of an unresolved type").id;
+        let present_ty = erase(&expr.expr.ty).exclude(&unit).simplify();
+        let (merge, result) = self.merge_block(ty);
+        let present = self.builder.create_block();
+        let absent = self.builder.create_block();
+        let is_unit = self.is_variant(base, union, &unit);
+        self.builder.ins().brnz(is_unit, absent, &[]);
+        self.builder.ins().jump(present, &[]);
+
+        self.builder.switch_to_block(present);
+        self.builder.seal_block(present);
+        let receiver = self.narrow(base, union, &present_ty);
+        let id = self.types.resolve(&present_ty).expect("field of an unresolved type").id;
+        let field_ty = self.field_type(&present_ty, &expr.field);
+        let field = self.field_address(receiver, id, &expr.field);
+        let value = self.load_value(field, &field_ty);
+        let value = self.coerce(value, &field_ty, ty);
+        self.builder.ins().jump(merge, &[value]);
+
+        self.builder.switch_to_block(absent);
+        self.builder.seal_block(absent);
+        let value = self.unit();
+        let value = self.coerce(value, &unit, ty);
+        self.builder.ins().jump(merge, &[value]);
+
+        self.builder.switch_to_block(merge);
+        self.builder.seal_block(merge);
+        result
+    }
+
+    /// Return the declared type of the named field of the struct or class
+    /// `ty`.
+    pub fn field_type(&self, ty: &TypeExpr, field: &str) -> TypeExpr {
+        self.fields
+            .get(&ty.to_string())
+            .and_then(|fields| fields.iter().find(|f| f.name == field))
+            .map(|f| f.ty.clone())
+            .unwrap_or_else(|| panic!("type `{ty}` has no field `{field}`"))
+    }
+
+    /// Return the Cranelift type used to hold values of the given scalar type.
+    pub fn ir_type(&self, entry: &KeyedTableEntry) -> Type {
+        scalar_type(entry, self.module.target_config().pointer_type())
+    }
+
+    /// Compute the offset and type of the named field of the struct `ty`.
+    fn field_layout(&self, ty: TypeId, field: &str) -> (i32, Type) {
+        let entry = self.types.find(ty).expect("failed to find type");
+        let field_ty = entry
+            .fields
+            .iter()
+            .flatten()
+            .find(|f| f.name == field)
+            .and_then(|f| self.types.find(f.ty))
+            .expect("failed to find field");
+        let offset = self.types.field_offset(ty, field).expect("field has an unsized offset");
+        (offset as i32, self.ir_type(field_ty))
+    }
+
+    /// Allocate a stack slot large enough to hold a value of the struct `ty`.
+    /// The slot is zeroed, so that the padding of structs holding the same
+    /// values compares equal.
+    pub fn alloc_struct(&mut self, ty: TypeId) -> StackSlot {
+        let layout = self
+            .types
+            .find(ty)
+            .and_then(|entry| entry.layout())
+            .expect("cannot allocate an unsized type");
+        let slot = self.builder.create_sized_stack_slot(StackSlotData::new(
+            StackSlotKind::ExplicitSlot,
+            layout.size as u32,
+        ));
+        let pointer_type = self.pointer_type();
+        let addr = self.builder.ins().stack_addr(pointer_type, slot, 0);
+        self.builder.emit_small_memset(
+            self.module.target_config(),
+            addr,
+            0,
+            layout.size as u64,
+            layout.align as u8,
+            MemFlags::trusted(),
+        );
+        slot
+    }
+
+    /// Emit the address of the named field of the struct `ty` pointed to by
+    /// `base`.
+    pub fn field_address(&mut self, base: Value, ty: TypeId, field: &str) -> Value {
+        let (offset, _) = self.field_layout(ty, field);
+        self.builder.ins().iadd_imm(base, offset as i64)
+    }
+
+    /// Emit a load of the named scalar field of the struct `ty` pointed to by
+    /// `base`.
+    pub fn load_field(&mut self, base: Value, ty: TypeId, field: &str) -> Value {
+        let (offset, field_ty) = self.field_layout(ty, field);
+        self.builder.ins().load(field_ty, MemFlags::trusted(), base, offset)
+    }
+
+    /// Emit a store of `value` to the named scalar field of the struct `ty`
+    /// pointed to by `base`.
+    pub fn store_field(&mut self, base: Value, ty: TypeId, field: &str, value: Value) {
+        let (offset, _) = self.field_layout(ty, field);
+        self.builder.ins().store(MemFlags::trusted(), value, base, offset);
+    }
+
+    /// Emit a load of a value of the type `ty` from `addr`. Structs are held by
+    /// reference, so evaluate to `addr` itself. Zero-sized values are never
+    /// read, so evaluate to a placeholder.
+    pub fn load_value(&mut self, addr: Value, ty: &TypeExpr) -> Value {
+        let entry = self.types.resolve(ty).expect("failed to find type");
+        match entry.layout() {
+            Some(layout) if layout.size == 0 => self.unit(),
+            _ if is_aggregate(entry) => addr,
+            _ => {
+                let ty = self.ir_type(entry);
+                self.builder.ins().load(ty, MemFlags::trusted(), addr, 0)
+            }
+        }
+    }
+
+    /// Emit a store of `value`, a value of the type `ty`, to `addr`. Structs
+    /// are held by reference, so are copied to `addr`, and zero-sized values
+    /// store nothing.
+    pub fn store_value(&mut self, addr: Value, ty: &TypeExpr, value: Value) {
+        let entry = self.types.resolve(ty).expect("failed to find type");
+        match entry.layout() {
+            Some(layout) if layout.size == 0 => (),
+            Some(layout) if is_aggregate(entry) => {
+                let align = layout.align as u8;
+                self.builder.emit_small_memory_copy(
+                    self.module.target_config(),
+                    addr,
+                    value,
+                    layout.size as u64,
+                    align,
+                    align,
+                    true,
+                    MemFlags::trusted(),
+                );
+            }
+            _ => {
+                self.builder.ins().store(MemFlags::trusted(), value, addr, 0);
+            }
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use cranelift::prelude::{types, InstBuilder, IntCC, MemFlags, Value};
+use cranelift_module::{FuncOrDataId, Module};
+use fluxc_ast::{Primitive, TypeExpr};
+use fluxc_types::{
+    lower::{erase, is_tagged},
+    TypeId, TypeTable,
+};
+
+use crate::{mangle_vtable, TranslationContext};
+
+impl TranslationContext<'_> {
+    /// Emit a value of the type `to` from `value`, a value of the type `from`.
+    /// Values of a member of a tagged union are implicitly injected into the
+    /// union when assigned to it, and values of a union of its members are
+    /// re-tagged. Values of a type implementing an interface are wrapped in a
+    /// fat pointer when assigned to the interface. Values of any other type
+    /// are left as they are.
+    pub fn coerce(&mut self, value: Value, from: &TypeExpr, to: &TypeExpr) -> Value {
+        let (erased_from, erased_to) = (erase(from), erase(to));
+        let vtable = mangle_vtable(&erased_from.to_string(), &erased_to.to_string());
+        if let Some(FuncOrDataId::Data(vtable)) = self.module.get_name(&vtable) {
+            return self.upcast(value, from, to, vtable);
+        }
+        // values of `never` are never produced
+        if !is_tagged(to) || erased_from == erased_to || erased_from == Primitive::Never.into() {
+            return value;
+        }
+        let union = self.types.resolve(to).expect("cannot coerce to an unresolved type").id;
+        match is_tagged(from) {
+            true => {
+                let from = self.types.resolve(from).expect("cannot coerce an unresolved type").id;
+                self.retag(value, from, union, &erased_from)
+            }
+            false => self.inject(union, &erased_from, value),
+        }
+    }
+
+    /// Emit a value of the tagged union `union` holding `value`, a value of
+    /// the member type `member`. Returns the address of the union.
+    pub fn inject(&mut self, union: TypeId, member: &TypeExpr, value: Value) -> Value {
+        let ptr_ty = self.module.target_config().pointer_type();
+        let &[tag] = &self.types.tags(union, member)[..] else {
+            panic!("type `{member}` is not a member of the union");
+        };
+        let slot = self.alloc_struct(union);
+        let base = self.builder.ins().stack_addr(ptr_ty, slot, 0);
+        let tag = self.builder.ins().iconst(types::I64, tag as i64);
+        self.store_field(base, union, "tag", tag);
+        let payload = self.payload(base, union, member);
+        self.store_value(payload, member, value);
+        base
+    }
+
+    /// Emit the address of the payload of the tagged union `union` at `base`,
+    /// holding a value of the member type `member`.
+    pub fn payload(&mut self, base: Value, union: TypeId, member: &TypeExpr) -> Value {
+        let entry = self.types.find(union).expect("failed to find type");
+        let &[tag] = &self.types.tags(union, member)[..] else {
+            panic!("type `{member}` is not a member of the union");
+        };
+        let name = entry.fields.as_ref().expect("union has no fields")[tag + 1].name.clone();
+        self.field_address(base, union, &name)
+    }
+
+    /// Emit the value of the type `ty` held by the tagged union `union` at
+    /// `base`, which must be known to hold a value of that type, such as once
+    /// it has been narrowed. Values of a member of the union are read from its
+    /// payload. Values of a union of its members are re-tagged into a tagged
+    /// union of their own.
+    pub fn narrow(&mut self, base: Value, union: TypeId, ty: &TypeExpr) -> Value {
+        if !is_tagged(ty) {
+            let payload = self.payload(base, union, ty);
+            return self.load_value(payload, ty);
+        }
+        let narrowed = self.types.resolve(ty).expect("cannot narrow to an unresolved type").id;
+        self.retag(base, union, narrowed, ty)
+    }
+
+    /// Emit a copy of the tagged union `from` at `base` as a value of the
+    /// tagged union `to`, which must hold a value of one of the members of
+    /// `members`, a union of the members the two have in common. Returns the
+    /// address of the copy.
+    fn retag(&mut self, base: Value, from: TypeId, to: TypeId, members: &TypeExpr) -> Value {
+        let ptr_ty = self.module.target_config().pointer_type();
+        let slot = self.alloc_struct(to);
+        let retagged = self.builder.ins().stack_addr(ptr_ty, slot, 0);
+        // members are tagged in their canonical order in each union, so the
+        // tag of each member is looked up in both
+        let tag = self.load_field(base, from, "tag");
+        let mut new_tag = self.builder.ins().iconst(types::I64, 0);
+        let tags = self.types.tags(from, members).into_iter().zip(self.types.tags(to, members));
+        for (from_tag, to_tag) in tags {
+            let holds = self.builder.ins().icmp_imm(IntCC::Equal, tag, from_tag as i64);
+            let to_tag = self.builder.ins().iconst(types::I64, to_tag as i64);
+            new_tag = self.builder.ins().select(holds, to_tag, new_tag);
+        }
+        self.store_field(retagged, to, "tag", new_tag);
+        // every member is held at the offset of the payload, so the payload
+        // of the smaller union is copied
+        let payload = |types: &TypeTable, id: TypeId| {
+            let entry = types.find(id).expect("failed to find type");
+            let offset = entry.fields.as_ref().expect("union has no fields")[1]
+                .offset
+                .expect("union has an unsized payload");
+            (offset, entry.size.expect("union is unsized") - offset)
+        };
+        let ((from_offset, from_size), (to_offset, to_size)) =
+            (payload(self.types, from), payload(self.types, to));
+        let src = self.builder.ins().iadd_imm(base, from_offset as i64);
+        let dest = self.builder.ins().iadd_imm(retagged, to_offset as i64);
+        self.builder.emit_small_memory_copy(
+            self.module.target_config(),
+            dest,
+            src,
+            from_size.min(to_size) as u64,
+            1,
+            1,
+            true,
+            MemFlags::trusted(),
+        );
+        retagged
+    }
+
+    /// Emit a check that the tagged union `union` at `base` holds a value of
+    /// the type `ty`, which may be a member of the union or a union of its
+    /// members. This lowers the discriminant checks of `match` branches and
+    /// of the conditions that narrow union-typed values.
+    pub fn is_variant(&mut self, base: Value, union: TypeId, ty: &TypeExpr) -> Value {
+        let tag = self.load_field(base, union, "tag");
+        let tags = self.types.tags(union, ty);
+        let checks = tags
+            .into_iter()
+            .map(|t| self.builder.ins().icmp_imm(IntCC::Equal, tag, t as i64))
+            .collect::<Vec<_>>();
+        match checks.into_iter().reduce(|a, b| self.builder.ins().bor(a, b)) {
+            Some(check) => check,
+            None => self.builder.ins().iconst(types::I8, 0),
+        }
+    }
+}
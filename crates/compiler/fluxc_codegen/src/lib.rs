@@ -3,17 +3,33 @@
 //! Handles the generation of code for the Flux compiler.
 
 use std::{
+    cell::RefCell,
+    collections::HashMap,
     error::Error,
     sync::{Arc, RwLock},
 };
 
-use cranelift::{codegen::Context, frontend::FunctionBuilder, prelude::FunctionBuilderContext};
+use cranelift::{
+    codegen::{ir::Signature, Context},
+    frontend::FunctionBuilder,
+    prelude::{types, AbiParam, FunctionBuilderContext, InstBuilder, Type},
+};
 use cranelift_jit::{JITBuilder, JITModule};
 use cranelift_module::{
-    DataContext, FuncId, Linkage, Module, ModuleCompiledFunction, ModuleResult,
+    DataContext, DataId, FuncId, FuncOrDataId, Linkage, Module, ModuleCompiledFunction,
+    ModuleResult,
+};
+use fluxc_ast::{Mutability, Primitive, TypeExpr};
+use fluxc_hir::{
+    Class, Closure, Field, Function, FunctionKind, Impl, Instance, Interface, LocalId, Stmt,
+    Struct, Substitute, Variable,
+};
+use fluxc_types::{
+    builder::StructBuilder,
+    layout::Repr,
+    vtable::{vtable_name, VTableBuilder},
+    KeyedTableEntry, TypeId, TypeTable,
 };
-use fluxc_ast::{FuncDecl, Stmt, AST};
-use fluxc_errors::CompilerError;
 
 mod expr;
 mod stmt;
@@ -24,6 +40,20 @@ pub struct ModuleContext {
     ctx: Context,
     data_ctx: DataContext,
     module: JITModule,
+    types: TypeTable,
+    /// The type parameters and fields of generic structs and classes, whose
+    /// layouts are registered for each list of type arguments they are used
+    /// with.
+    generic_types: HashMap<String, (Vec<String>, Vec<Field>, Repr)>,
+    /// The argument types of the declared functions, methods and interface
+    /// methods, by the name they are declared under.
+    arg_types: HashMap<String, Vec<TypeExpr>>,
+    /// The read-only data holding the values of the top-level constants that
+    /// are not emitted as immediates, by the ID of their variable.
+    constants: HashMap<LocalId, DataId>,
+    /// The fields of the declared structs, classes and instances of generic
+    /// structs and classes, by the name they are registered under.
+    fields: HashMap<String, Vec<Field>>,
 }
 
 impl ModuleContext {
@@ -31,85 +61,663 @@ impl ModuleContext {
     pub fn for_module() -> Arc<RwLock<Self>> {
         let builder = JITBuilder::new(cranelift_module::default_libcall_names());
         let module = JITModule::new(builder.unwrap());
+        let types = TypeTable::with_pointer_size(module.target_config().pointer_bytes() as usize);
         Arc::new(RwLock::new(Self {
             builder_context: FunctionBuilderContext::new(),
             ctx: module.make_context(),
             data_ctx: DataContext::new(),
             module,
+            types,
+            generic_types: HashMap::new(),
+            arg_types: HashMap::new(),
+            constants: HashMap::new(),
+            fields: HashMap::new(),
         }))
     }
 
-    /// Enter the given function and create the translation context.
-    pub fn declare_function(&mut self, decl: &FuncDecl) -> ModuleResult<FuncId> {
-        match decl {
-            FuncDecl::Local { ident, params, body, ret_ty } => {
-                let signature = self.module.make_signature();
-                self.module.declare_function(&ident.value, Linkage::Local, &signature)
-            }
-            FuncDecl::Export { ident, params, body, ret_ty } => {
-                let signature = self.module.make_signature();
-                self.module.declare_function(&ident.value, Linkage::Export, &signature)
-            }
-            FuncDecl::External { ident, params, ret_ty } => {
-                let signature = self.module.make_signature();
-                self.module.declare_function(&ident.value, Linkage::Import, &signature)
+    /// Resolve the type table entry for the given type expression, which must
+    /// already have been registered.
+    pub fn resolve_type(&self, ty: &TypeExpr) -> Option<&KeyedTableEntry> {
+        self.types.resolve(ty)
+    }
+
+    /// Register the runtime representation of the given type in the type
+    /// table, along with the instances of generic types it refers to,
+    /// returning its type ID.
+    pub fn register_type(&mut self, ty: &TypeExpr) -> Option<TypeId> {
+        let instances = RefCell::new(vec![]);
+        ty.replace(&|ty| {
+            if let TypeExpr::Generic(..) = ty {
+                instances.borrow_mut().push(ty.clone());
             }
+            None
+        });
+        for instance in instances.into_inner() {
+            self.instantiate_type(&instance);
+        }
+        self.types.lower(ty)
+    }
+
+    /// Return the Cranelift type used to pass values of the given type, or
+    /// `None` for zero-sized types. Structs, classes and interface values are
+    /// passed by reference.
+    pub fn abi_type(&self, ty: &TypeExpr) -> Option<Type> {
+        abi_type(&self.types, ty, self.module.target_config().pointer_type())
+    }
+
+    /// Compute the Cranelift signature of the given function. Methods take a
+    /// pointer to their receiver as their first argument, and closures a
+    /// pointer to their environment.
+    pub fn signature(&self, func: &Function) -> Signature {
+        let mut signature = self.module.make_signature();
+        if func.kind == FunctionKind::Closure {
+            signature.params.push(AbiParam::new(self.module.target_config().pointer_type()));
+        }
+        for arg in &func.args {
+            let ty = match arg.name.as_str() {
+                "self" if func.has_receiver() => Some(self.module.target_config().pointer_type()),
+                _ => self.abi_type(&arg.ty),
+            };
+            signature.params.extend(ty.map(AbiParam::new));
+        }
+        signature.returns.extend(self.abi_type(&func.return_type).map(AbiParam::new));
+        signature
+    }
+
+    /// Register a struct type with the given fields and representation in the
+    /// type table, returning its type ID.
+    fn build_struct(&mut self, name: &str, fields: &[Field], repr: Repr) -> TypeId {
+        for field in fields {
+            self.register_type(&field.ty);
+        }
+        let builder = fields.iter().fold(StructBuilder::new(name), |builder, field| {
+            let ty = self.resolve_type(&field.ty).expect("struct field has an unresolved type");
+            builder.field(&field.name, ty)
+        });
+        let builder = builder.repr(repr);
+        self.fields.insert(name.to_string(), fields.to_vec());
+        builder.build(&mut self.types).id
+    }
+
+    /// Register the given struct in the type table, returning its type ID.
+    pub fn declare_struct(&mut self, decl: &Struct) -> TypeId {
+        self.build_struct(&decl.name, &decl.fields, decl.repr)
+    }
+
+    /// Record a generic struct or class, whose layout is registered once it is
+    /// instantiated with type arguments.
+    pub fn declare_generic(
+        &mut self,
+        name: &str,
+        generics: &[String],
+        fields: &[Field],
+        repr: Repr,
+    ) {
+        self.generic_types.insert(name.to_string(), (generics.to_vec(), fields.to_vec(), repr));
+    }
+
+    /// Register the layout of the instance of a generic struct or class the
+    /// given type refers to, if it has not been already, along with the types
+    /// of its fields.
+    pub fn instantiate_type(&mut self, ty: &TypeExpr) {
+        let TypeExpr::Generic(name, args) = ty else { return };
+        let mangled = mangle_instance(name, args);
+        if self.types.find_by_name(&mangled).is_some() {
+            return;
+        }
+        let Some((generics, fields, repr)) = self.generic_types.get(name).cloned() else {
+            return;
+        };
+        let args = generics.into_iter().zip(args.iter().cloned()).collect();
+        let fields: Vec<_> = fields
+            .into_iter()
+            .map(|field| Field { name: field.name, ty: field.ty.substitute(&args) })
+            .collect();
+        self.build_struct(&mangled, &fields, repr);
+    }
+
+    /// Register the types referred to by the signature of the given function.
+    fn instantiate_signature(&mut self, decl: &Function) {
+        for arg in &decl.args {
+            self.register_type(&arg.ty);
+        }
+        self.register_type(&decl.return_type);
+    }
+
+    /// Record the argument types of the given function, declared under the
+    /// given name, which calls to it coerce their arguments to.
+    fn record_args(&mut self, name: &str, decl: &Function) {
+        let types = decl.args.iter().map(|arg| arg.ty.clone()).collect();
+        self.arg_types.insert(name.to_string(), types);
+    }
+
+    /// Register the given class in the type table and declare its methods,
+    /// returning the type ID of the class and the IDs of its methods.
+    pub fn declare_class(&mut self, decl: &Class) -> ModuleResult<(TypeId, Vec<FuncId>)> {
+        let ty = self.build_struct(&decl.name, &decl.fields, Repr::Flux);
+        let methods = decl
+            .methods
+            .iter()
+            .map(|method| self.declare_method(&decl.name, method))
+            .collect::<ModuleResult<_>>()?;
+        Ok((ty, methods))
+    }
+
+    /// Declare the methods of the given interface implementation, returning
+    /// their IDs. Inherited default methods are declared as a copy specialised
+    /// to the implementing type, so that every call to an interface method on
+    /// a known type can be statically dispatched.
+    pub fn declare_impl(&mut self, decl: &Impl) -> ModuleResult<Vec<FuncId>> {
+        decl.methods.iter().map(|method| self.declare_method(&decl.ty, method)).collect()
+    }
+
+    /// Register the vtable and fat pointer types of the given interface,
+    /// returning the type ID of the fat pointer. Only methods with a receiver
+    /// can be dynamically dispatched, so only they are given vtable slots.
+    pub fn declare_interface(&mut self, decl: &Interface) -> TypeId {
+        for method in &decl.methods {
+            self.record_args(&mangle_method(&decl.name, &method.name), method);
+        }
+        let (fat, _) = decl
+            .methods
+            .iter()
+            .filter(|method| method.has_receiver())
+            .fold(VTableBuilder::new(&decl.name), |builder, method| builder.method(&method.name))
+            .build(&mut self.types);
+        fat
+    }
+
+    /// Emit the vtable of the given interface implementation as a data
+    /// object, filling each slot with the address of the method the
+    /// implementing type dispatches to. The methods of the implementation
+    /// must already have been declared.
+    pub fn declare_vtable(&mut self, decl: &Impl, interface: &Interface) -> ModuleResult<DataId> {
+        let vtable = self
+            .types
+            .find_by_name(vtable_name(&interface.name))
+            .expect("vtable of an undeclared interface")
+            .id;
+        let size = self.types.find(vtable).and_then(|entry| entry.size).unwrap_or(0);
+        self.data_ctx.define_zeroinit(size);
+        for method in interface.methods.iter().filter(|method| method.has_receiver()) {
+            let id = self.method_id(&decl.ty, &method.name).expect("undeclared interface method");
+            let offset =
+                self.types.field_offset(vtable, &method.name).expect("missing vtable slot");
+            let func = self.module.declare_func_in_data(id, &mut self.data_ctx);
+            self.data_ctx.write_function_addr(offset as u32, func);
+        }
+        let id = self.module.declare_data(
+            &mangle_vtable(&decl.ty, &interface.name),
+            Linkage::Local,
+            false,
+            false,
+        )?;
+        let result = self.module.define_data(id, &self.data_ctx);
+        self.data_ctx.clear();
+        result.map(|_| id)
+    }
+
+    /// Emit the value of the given constant as read-only data, returning its
+    /// ID. Constants holding scalar values are emitted as immediates wherever
+    /// they are referred to instead, so have no data.
+    pub fn declare_constant(&mut self, constant: &Variable) -> ModuleResult<Option<DataId>> {
+        let Some(bytes) = constant_data(&constant.ty) else { return Ok(None) };
+        self.data_ctx.define(bytes.into_boxed_slice());
+        let id = self.module.declare_data(
+            &mangle_constant(&constant.name, constant.id),
+            Linkage::Local,
+            false,
+            false,
+        )?;
+        let result = self.module.define_data(id, &self.data_ctx);
+        self.data_ctx.clear();
+        self.constants.insert(constant.id, id);
+        result.map(|_| Some(id))
+    }
+
+    /// Find the vtable of the implementation of the given interface for the
+    /// given type.
+    pub fn vtable_id(&self, ty: &str, interface: &str) -> Option<DataId> {
+        match self.module.get_name(&mangle_vtable(ty, interface)) {
+            Some(FuncOrDataId::Data(id)) => Some(id),
+            _ => None,
+        }
+    }
+
+    /// Find the function a method call on a value of the given type statically
+    /// dispatches to.
+    pub fn method_id(&self, ty: &str, method: &str) -> Option<FuncId> {
+        match self.module.get_name(&mangle_method(ty, method)) {
+            Some(FuncOrDataId::Func(id)) => Some(id),
+            _ => None,
+        }
+    }
+
+    /// Declare the given method of a type as a free function.
+    pub fn declare_method(&mut self, ty: &str, decl: &Function) -> ModuleResult<FuncId> {
+        let name = mangle_method(ty, &decl.name);
+        self.instantiate_signature(decl);
+        self.record_args(&name, decl);
+        let signature = self.signature(decl);
+        self.module.declare_function(&name, Linkage::Local, &signature)
+    }
+
+    /// Declare the given function.
+    pub fn declare_function(&mut self, decl: &Function) -> ModuleResult<FuncId> {
+        let linkage = match decl.kind {
+            FunctionKind::External => Linkage::Import,
+            _ => Linkage::Local,
+        };
+        self.instantiate_signature(decl);
+        self.record_args(&decl.name, decl);
+        let signature = self.signature(decl);
+        self.module.declare_function(&decl.name, linkage, &signature)
+    }
+
+    /// Declare an instance of a generic function, whose type parameters have
+    /// been replaced by the given type arguments.
+    pub fn declare_instance(
+        &mut self,
+        name: &str,
+        type_args: &[TypeExpr],
+        decl: &Function,
+    ) -> ModuleResult<FuncId> {
+        let name = mangle_instance(name, type_args);
+        self.instantiate_signature(decl);
+        self.record_args(&name, decl);
+        let signature = self.signature(decl);
+        self.module.declare_function(&name, Linkage::Local, &signature)
+    }
+
+    /// Declare the function the given closure, created by the function
+    /// declared as `enclosing`, is lifted to, and register the type of its
+    /// environment.
+    pub fn declare_closure(&mut self, enclosing: &str, closure: &Closure) -> ModuleResult<FuncId> {
+        let name = mangle_closure(enclosing, &closure.function.name);
+        let captures: Vec<_> = closure
+            .captures
+            .iter()
+            .map(|capture| (capture.id.to_string(), capture.ty.clone()))
+            .collect();
+        for (_, ty) in &captures {
+            self.register_type(ty);
         }
+        self.types.environment(&name, &captures);
+        self.instantiate_signature(&closure.function);
+        let signature = self.signature(&closure.function);
+        self.module.declare_function(&name, Linkage::Local, &signature)
+    }
+
+    /// Define the thunk through which closure values holding the given
+    /// declared function call it, returning its ID. The thunk takes the
+    /// environment of the closure as its first argument, like the functions
+    /// closures are lifted to, but ignores it and forwards the rest of its
+    /// arguments to the function.
+    pub fn define_thunk(&mut self, decl: &Function, id: FuncId) -> ModuleResult<FuncId> {
+        let mut signature = self.signature(decl);
+        signature.params.insert(0, AbiParam::new(self.module.target_config().pointer_type()));
+        let thunk =
+            self.module.declare_function(&mangle_thunk(&decl.name), Linkage::Local, &signature)?;
+        self.ctx.func.signature = signature;
+        let mut builder = FunctionBuilder::new(&mut self.ctx.func, &mut self.builder_context);
+        let block = builder.create_block();
+        builder.append_block_params_for_function_params(block);
+        builder.switch_to_block(block);
+        builder.seal_block(block);
+        let args = builder.block_params(block)[1..].to_vec();
+        let callee = self.module.declare_func_in_func(id, builder.func);
+        let call = builder.ins().call(callee, &args);
+        let results = builder.inst_results(call).to_vec();
+        builder.ins().return_(&results);
+        builder.finalize();
+
+        let result = self.module.define_function(thunk, &mut self.ctx);
+        self.module.clear_context(&mut self.ctx);
+        result.map(|_| thunk)
     }
 
-    /// Define the given function.
+    /// Define the given function or method, along with the functions the
+    /// closures it creates are lifted to.
     pub fn define_function(
         &mut self,
-        decl: &FuncDecl,
+        decl: &Function,
         id: FuncId,
     ) -> ModuleResult<ModuleCompiledFunction> {
-        let mut builder = FunctionBuilder::new(&mut self.ctx.func, &mut self.builder_context);
-        let mut ctx = TranslationContext { builder, module: Arc::new(RwLock::new(self)) };
+        self.define(decl, id, &[])
+    }
+
+    /// Define the given function, which captures the given variables if it is
+    /// a lifted closure, along with the functions the closures it creates are
+    /// lifted to.
+    fn define(
+        &mut self,
+        decl: &Function,
+        id: FuncId,
+        captures: &[Variable],
+    ) -> ModuleResult<ModuleCompiledFunction> {
+        // closures must be declared before the function creating them is
+        // translated, so that their code and environment can be referred to
+        let enclosing = self.module.declarations().get_function_decl(id).name.clone();
+        let mut closures = vec![];
+        decl.closures(&mut closures);
+        let lifted = closures
+            .iter()
+            .map(|closure| Ok((closure, self.declare_closure(&enclosing, closure)?)))
+            .collect::<ModuleResult<Vec<_>>>()?;
+
+        // the values of the body must be laid out before it is translated
+        let mut types = vec![];
+        decl.value_types(&mut types);
+        for ty in &types {
+            self.register_type(ty);
+        }
+        self.ctx.func.signature = self.signature(decl);
+        let builder = FunctionBuilder::new(&mut self.ctx.func, &mut self.builder_context);
+        let closures =
+            lifted.iter().map(|(closure, id)| (closure.function.name.clone(), *id)).collect();
+        let mut ctx = TranslationContext {
+            builder,
+            module: &mut self.module,
+            types: &self.types,
+            arg_types: &self.arg_types,
+            fields: &self.fields,
+            enclosing,
+            closures,
+            captures: captures.to_vec(),
+            return_type: decl.return_type.clone(),
+            constants: self.constants.clone(),
+            locals: HashMap::new(),
+            loops: vec![],
+        };
 
         // translate function body
         decl.translate(&mut ctx);
 
-        self.module.define_function(id, &mut self.ctx)
+        let result = self.module.define_function(id, &mut self.ctx);
+        self.module.clear_context(&mut self.ctx);
+        for (closure, id) in lifted {
+            self.define(&closure.function, id, &closure.captures)?;
+        }
+        result
+    }
+
+    /// Declare and define the items of the given HIR module.
+    pub fn define_module(&mut self, hir: &fluxc_hir::Module) -> Result<(), Box<dyn Error>> {
+        // declare structs, classes and interface fat pointer and vtable types.
+        // generic types are only laid out once instantiated
+        for decl in &hir.structs {
+            if !decl.generics.is_empty() {
+                self.declare_generic(&decl.name, &decl.generics, &decl.fields, decl.repr);
+                continue;
+            }
+            self.declare_struct(decl);
+        }
+        let mut methods = vec![];
+        for decl in &hir.classes {
+            if !decl.generics.is_empty() {
+                self.declare_generic(&decl.name, &decl.generics, &decl.fields, Repr::Flux);
+                continue;
+            }
+            let (_, ids) = self.declare_class(decl)?;
+            methods.extend(decl.methods.iter().zip(ids));
+        }
+        for decl in &hir.interfaces {
+            self.declare_interface(decl);
+        }
+
+        // declare interface implementations, emitting a vtable for each so
+        // that values of the implementing type can be dynamically dispatched
+        // through the interface
+        for decl in &hir.impls {
+            let ids = self.declare_impl(decl)?;
+            methods.extend(decl.methods.iter().zip(ids));
+            let interface = hir
+                .interfaces
+                .iter()
+                .find(|interface| interface.name == decl.interface)
+                .expect("implementation of an undeclared interface");
+            self.declare_vtable(decl, interface)?;
+        }
+
+        // emit top-level constants that cannot be held in immediates
+        for stmt in &hir.stmts {
+            if let Stmt::Declaration(decl) = stmt {
+                if decl.variable.mutability == Mutability::Constant {
+                    self.declare_constant(&decl.variable)?;
+                }
+            }
+        }
+
+        // declare functions, except for generic functions, which are declared
+        // once instantiated
+        let mut funcs = Vec::with_capacity(hir.functions.len());
+        for decl in hir.functions.iter().filter(|decl| !decl.is_generic()) {
+            funcs.push((decl, self.declare_function(decl)?));
+        }
+        let instances = instantiate_generics(self, hir, &funcs, &methods)?;
+        // emit a thunk for each function, so that it can be held by closure
+        // values
+        for (decl, id) in &funcs {
+            self.define_thunk(decl, *id)?;
+        }
+        // define functions, methods and instances of generic functions and
+        // methods
+        let instances = instances.iter().map(|(decl, id)| (decl, *id));
+        for (decl, id) in funcs.into_iter().chain(methods).chain(instances) {
+            if decl.body.is_some() {
+                self.define_function(decl, id)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Finalize the functions and data defined so far, so that they can be
+    /// run.
+    pub fn finalize(&mut self) -> ModuleResult<()> {
+        self.module.finalize_definitions()
+    }
+
+    /// Return the address of the code of the named function, which must have
+    /// been defined and finalized.
+    pub fn function_ptr(&self, name: &str) -> Option<*const u8> {
+        match self.module.get_name(name) {
+            Some(FuncOrDataId::Func(id)) => Some(self.module.get_finalized_function(id)),
+            _ => None,
+        }
+    }
+}
+
+/// Return the Cranelift type used to pass values of the given type, which must
+/// have been registered in `types`, or `None` for zero-sized types.
+pub fn abi_type(types: &TypeTable, ty: &TypeExpr, pointer_type: Type) -> Option<Type> {
+    let entry = types.resolve(ty).expect("cannot lower an unresolved type");
+    match (is_aggregate(entry), entry.size) {
+        (_, Some(0)) => None,
+        (true, _) => Some(pointer_type),
+        (false, _) => Some(scalar_type(entry, pointer_type)),
+    }
+}
+
+/// Returns true if values of the given type are aggregates, such as structs,
+/// tagged unions and closures, which are held by reference. Primitives have
+/// no fields, and zero-sized types hold nothing to refer to.
+pub fn is_aggregate(entry: &KeyedTableEntry) -> bool {
+    entry.fields.as_ref().is_some_and(|fields| !fields.is_empty()) && entry.size != Some(0)
+}
+
+/// Return the Cranelift type used to hold values of the given scalar type.
+pub fn scalar_type(entry: &KeyedTableEntry, pointer_type: Type) -> Type {
+    match (entry.name.as_str(), entry.size) {
+        ("float", _) => types::F64,
+        ("f32", _) => types::F32,
+        ("ref", _) => pointer_type,
+        (_, Some(1)) => types::I8,
+        (_, Some(2)) => types::I16,
+        (_, Some(4)) => types::I32,
+        _ => types::I64,
+    }
+}
+
+/// Return the bytes of the read-only data holding the value of the given
+/// literal type, or `None` if the value is emitted as an immediate instead.
+pub fn constant_data(ty: &TypeExpr) -> Option<Vec<u8>> {
+    match ty {
+        TypeExpr::Primitive(Primitive::StringLiteral(string)) => {
+            // strings are null-terminated so they can be passed to C
+            let mut bytes = string.clone().into_bytes();
+            bytes.push(0);
+            Some(bytes)
+        }
+        _ => None,
     }
 }
 
+/// Mangle the name of a method into the name of the free function it is
+/// lowered to. Flux identifiers cannot contain `.`, so mangled names never
+/// collide with the names of free functions.
+pub fn mangle_method(class: &str, method: &str) -> String {
+    format!("{}.{}", class, method)
+}
+
+/// Mangle the name of an instance of a generic function or type into the name
+/// it is declared under, which is the type it would be written as, such as
+/// `Pair<int, string>`. Flux identifiers cannot contain `<`, so mangled names
+/// never collide with the names of other functions and types.
+pub fn mangle_instance(name: &str, type_args: &[TypeExpr]) -> String {
+    TypeExpr::Generic(name.to_string(), type_args.to_vec()).to_string()
+}
+
+/// Mangle the name of the vtable of the implementation of an interface for a
+/// type.
+pub fn mangle_vtable(ty: &str, interface: &str) -> String {
+    format!("{}.{}.vtable", ty, interface)
+}
+
+/// Mangle the name of the read-only data holding the value of a constant. The
+/// ID of the constant's variable tells apart constants of the same name
+/// declared in different scopes.
+pub fn mangle_constant(name: &str, id: LocalId) -> String {
+    format!("{}.{}.const", name, id)
+}
+
+/// Mangle the name of a closure into the name of the function it is lifted
+/// to. Closures are named after the function declared as `enclosing` that
+/// creates them, so that each instance of a generic function lifts its own
+/// copy of the closures it creates.
+pub fn mangle_closure(enclosing: &str, closure: &str) -> String {
+    format!("{}.{}", enclosing, closure)
+}
+
+/// Mangle the name of a function into the name of the thunk through which
+/// closure values holding it call it.
+pub fn mangle_thunk(name: &str) -> String {
+    format!("{}.thunk", name)
+}
+
 /// The context in which code generation is occuring.
 pub struct TranslationContext<'a> {
     pub builder: FunctionBuilder<'a>,
-    pub module: Arc<RwLock<ModuleContext>>,
+    pub module: &'a mut JITModule,
+    pub types: &'a TypeTable,
+    /// The argument types of the declared functions and methods, by the name
+    /// they are declared under.
+    pub arg_types: &'a HashMap<String, Vec<TypeExpr>>,
+    /// The fields of the declared structs and classes, by the name they are
+    /// registered under.
+    pub fields: &'a HashMap<String, Vec<Field>>,
+    /// The name the function being translated is declared under.
+    pub enclosing: String,
+    /// The functions the closures created by the function being translated
+    /// are lifted to, by the name of their closure.
+    pub closures: HashMap<String, FuncId>,
+    /// The variables captured by the function being translated, if it is a
+    /// lifted closure.
+    pub captures: Vec<Variable>,
+    /// The return type of the function being translated.
+    pub return_type: TypeExpr,
+    /// The read-only data holding the values of the constants in scope that
+    /// are not emitted as immediates, by the ID of their variable.
+    pub constants: HashMap<LocalId, DataId>,
+    /// The Cranelift variables holding the locals of the function being
+    /// translated, along with their declared types. Aggregates are held by
+    /// the address of the stack slot holding them.
+    pub locals: HashMap<LocalId, (cranelift::frontend::Variable, TypeExpr)>,
+    /// The blocks following the loops enclosing the code being translated,
+    /// innermost last, which `break` jumps to.
+    pub loops: Vec<cranelift::prelude::Block>,
 }
 
-/// Trait implemented by types that can generate code.
+/// Trait implemented by types that can generate code. Code generation cannot
+/// fail, as it only runs on modules the type checker has accepted.
 pub trait Translate {
+    /// The value the emitted code evaluates to.
+    type Output;
+
     /// Emit code for this type.
-    fn translate<'a>(&self, ctx: &mut TranslationContext<'a>) -> Result<(), CompilerError>;
+    fn translate(&self, ctx: &mut TranslationContext<'_>) -> Self::Output;
 }
 
-/// Handle the AST code generation.
+/// Handle the code generation of the given HIR module.
 #[tracing::instrument]
-pub fn codegen(ast: AST) -> Result<(), Box<dyn Error>> {
-    let mut module_ctx = ModuleContext::for_module();
-
-    // collect all function declarations
-    let func_decls = ast
-        .stmts
-        .iter()
-        .filter(|stmt| matches!(stmt.value, Stmt::FuncDecl(_)))
-        .map(|node| match &node.value {
-            Stmt::FuncDecl(decl) => &decl.value,
-            _ => unreachable!(),
-        })
-        .collect::<Vec<_>>();
-
-    // declare functions
-    let mut funcs = Vec::with_capacity(func_decls.len());
-    for func_decl in func_decls {
-        funcs.push((func_decl, module_ctx.declare_function(func_decl)?));
-    }
-    // define functions
-    for (decl, id) in funcs {
-        module_ctx.define_function(decl, id)?;
-    }
-
+pub fn codegen(hir: &fluxc_hir::Module) -> Result<(), Box<dyn Error>> {
+    let module_ctx = ModuleContext::for_module();
+    let mut module_ctx = module_ctx.write().expect("module context poisoned");
+    module_ctx.define_module(hir)?;
+    module_ctx.finalize()?;
     Ok(())
 }
+
+/// Declare every instance of the generic functions and classes in the given
+/// module that it uses, starting from the given functions and methods and the
+/// top-level statements of the module. Instances may themselves use further
+/// instances, which are declared in turn. Returns the declared functions and
+/// methods of every instance.
+fn instantiate_generics(
+    module_ctx: &mut ModuleContext,
+    hir: &fluxc_hir::Module,
+    funcs: &[(&Function, FuncId)],
+    methods: &[(&Function, FuncId)],
+) -> ModuleResult<Vec<(Function, FuncId)>> {
+    let mut queue = vec![];
+    for (decl, _) in funcs.iter().chain(methods) {
+        decl.instances(&mut queue);
+    }
+    for stmt in &hir.stmts {
+        stmt.instances(&mut queue);
+    }
+    let mut seen = vec![];
+    let mut instances = vec![];
+    while let Some(instance) = queue.pop() {
+        if seen.contains(&instance) {
+            continue;
+        }
+        match &instance {
+            Instance::Function(name, type_args) => {
+                let Some(generic) = hir.functions.iter().find(|decl| &decl.name == name) else {
+                    continue;
+                };
+                let decl = generic.instantiate(type_args);
+                decl.instances(&mut queue);
+                let id = module_ctx.declare_instance(name, type_args, &decl)?;
+                instances.push((decl, id));
+            }
+            Instance::Class(name, type_args) => {
+                let Some(class) = hir.classes.iter().find(|decl| &decl.name == name) else {
+                    continue;
+                };
+                let ty = TypeExpr::Generic(name.clone(), type_args.clone());
+                module_ctx.instantiate_type(&ty);
+                let args = class.generics.iter().cloned().zip(type_args.iter().cloned()).collect();
+                for method in &class.methods {
+                    let mut decl = method.clone();
+                    decl.substitute(&args);
+                    decl.instances(&mut queue);
+                    let id = module_ctx.declare_method(&ty.to_string(), &decl)?;
+                    instances.push((decl, id));
+                }
+            }
+        }
+        seen.push(instance);
+    }
+    Ok(instances)
+}
@@ -1,10 +1,71 @@
-use fluxc_ast::Declaration;
-use fluxc_errors::CompilerError;
+use cranelift::{frontend::Variable as Var, prelude::Value};
+use fluxc_ast::{Mutability, TypeExpr};
+use fluxc_hir::{Declaration, LocalId, Variable};
+use fluxc_types::lower::{erase, is_tagged};
 
 use crate::{Translate, TranslationContext};
 
 impl Translate for Declaration {
-    fn translate<'a>(&self, _ctx: &mut TranslationContext<'a>) -> Result<(), CompilerError> {
-        todo!()
+    type Output = ();
+
+    fn translate(&self, ctx: &mut TranslationContext<'_>) {
+        // constants are folded into the places they are referred to, so only
+        // those that cannot be held in immediates are emitted
+        if self.variable.mutability == Mutability::Constant {
+            if let Some(id) = ctx.constant_data(&self.variable.ty) {
+                ctx.constants.insert(self.variable.id, id);
+            }
+            return;
+        }
+        let value = self.value.translate(ctx);
+        let value = ctx.coerce(value, &self.value.ty, &self.variable.ty);
+        let value = ctx.copy_value(value, &self.variable.ty);
+        ctx.declare_local(&self.variable, value);
+    }
+}
+
+impl TranslationContext<'_> {
+    /// Declare the Cranelift variable holding the given local, initialised to
+    /// `value`. Aggregates are held by the address of their slot, so `value`
+    /// must be a copy the local owns.
+    pub fn declare_local(&mut self, variable: &Variable, value: Value) {
+        let var = Var::from_u32(variable.id as u32);
+        self.builder.declare_var(var, self.var_type(&variable.ty));
+        self.builder.def_var(var, value);
+        self.locals.insert(variable.id, (var, variable.ty.clone()));
+    }
+
+    /// Emit the value of the local with the given ID, where it has the type
+    /// `ty`. Locals of tagged unions that have been narrowed to some of their
+    /// members are narrowed to `ty`. Constants are read from their literal
+    /// type where possible, and from the data holding them otherwise.
+    pub fn local(&mut self, id: LocalId, ty: &TypeExpr) -> Value {
+        if let Some((var, declared)) = self.locals.get(&id).cloned() {
+            let value = self.builder.use_var(var);
+            if !is_tagged(&declared) || erase(ty) == erase(&declared) {
+                return value;
+            }
+            let union = self.types.resolve(&declared).expect("local of an unresolved type").id;
+            return self.narrow(value, union, ty);
+        }
+        if let Some(value) = self.immediate(ty) {
+            return value;
+        }
+        let data = *self.constants.get(&id).expect("constant was not declared");
+        self.data_address(data)
+    }
+
+    /// Emit an assignment of `value` to the local with the given ID.
+    /// Aggregates are copied into the slot the local already holds, so that
+    /// the value assigned can be mutated without affecting the local.
+    pub fn assign_local(&mut self, id: LocalId, value: Value) {
+        let (var, ty) = self.locals.get(&id).cloned().expect("assignment to an undeclared local");
+        match self.is_aggregate(&ty) {
+            true => {
+                let addr = self.builder.use_var(var);
+                self.store_value(addr, &ty, value);
+            }
+            false => self.builder.def_var(var, value),
+        }
     }
 }
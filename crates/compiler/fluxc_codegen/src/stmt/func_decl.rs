@@ -1,10 +1,55 @@
-use fluxc_ast::FuncDecl;
-use fluxc_errors::CompilerError;
+use cranelift::prelude::{InstBuilder, TrapCode};
+use fluxc_hir::{Function, FunctionKind};
 
 use crate::{Translate, TranslationContext};
 
-impl Translate for FuncDecl {
-    fn translate<'a>(&self, _ctx: &mut TranslationContext<'a>) -> Result<(), CompilerError> {
-        todo!()
+impl Translate for Function {
+    type Output = ();
+
+    fn translate(&self, ctx: &mut TranslationContext<'_>) {
+        let body = self.body.as_ref().expect("cannot translate a function without a body");
+        let entry = ctx.builder.create_block();
+        ctx.builder.append_block_params_for_function_params(entry);
+        ctx.builder.switch_to_block(entry);
+        ctx.builder.seal_block(entry);
+        let mut params = ctx.builder.block_params(entry).to_vec().into_iter();
+        // lifted closures take the environment of their closure first, from
+        // which their captured variables are loaded
+        if self.kind == FunctionKind::Closure {
+            let env = params.next().expect("closure takes no environment");
+            for variable in ctx.captures.clone() {
+                let value = ctx.load_capture(env, &variable);
+                ctx.declare_local(&variable, value);
+            }
+        }
+        for (i, variable) in body.params.iter().enumerate() {
+            let value = match (i == 0 && self.has_receiver(), ctx.abi_type(&variable.ty)) {
+                // receivers are passed by reference, so that methods can
+                // mutate them in place
+                (true, _) => {
+                    let receiver = params.next().expect("method takes no receiver");
+                    ctx.load_value(receiver, &variable.ty)
+                }
+                // other arguments are copied, so that they can be mutated
+                // without affecting the caller
+                (false, Some(_)) => {
+                    let value = params.next().expect("function takes too few arguments");
+                    ctx.copy_value(value, &variable.ty)
+                }
+                (false, None) => ctx.unit(),
+            };
+            ctx.declare_local(variable, value);
+        }
+        let value = body.block.translate(ctx);
+        // bodies ending without a `return`, such as those of closures ending
+        // in an expression, return the value of their block
+        match ctx.builder.is_unreachable() {
+            true => {
+                ctx.builder.ins().trap(TrapCode::UnreachableCodeReached);
+            }
+            false => ctx.return_value(value, &body.block.ty),
+        }
+        ctx.builder.seal_all_blocks();
+        ctx.builder.finalize();
     }
 }
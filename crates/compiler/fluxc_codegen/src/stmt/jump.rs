@@ -0,0 +1,40 @@
+use cranelift::prelude::{InstBuilder, Value};
+use fluxc_ast::TypeExpr;
+
+use crate::TranslationContext;
+
+impl TranslationContext<'_> {
+    /// Emit a return of `value`, a value of the type `ty`, from the function
+    /// being translated. Aggregates may outlive the stack slot holding them,
+    /// so a copy of them on the heap is returned instead.
+    pub fn return_value(&mut self, value: Value, ty: &TypeExpr) {
+        let ret = self.return_type.clone();
+        let value = self.coerce(value, ty, &ret);
+        match self.abi_type(&ret) {
+            None => self.builder.ins().return_(&[]),
+            Some(_) if self.is_aggregate(&ret) => {
+                let copy = self.heap_copy(value, &ret);
+                self.builder.ins().return_(&[copy])
+            }
+            Some(_) => self.builder.ins().return_(&[value]),
+        };
+        self.unreachable_block();
+    }
+
+    /// Emit a jump out of the innermost loop enclosing the code being
+    /// translated.
+    pub fn break_loop(&mut self) {
+        let exit = *self.loops.last().expect("`break` outside of a loop");
+        self.builder.ins().jump(exit, &[]);
+        self.unreachable_block();
+    }
+
+    /// Copy `value`, a value of the type `ty`, to the heap. Returns the
+    /// address of the copy.
+    pub fn heap_copy(&mut self, value: Value, ty: &TypeExpr) -> Value {
+        let size = self.types.resolve(ty).and_then(|entry| entry.size);
+        let addr = self.malloc(size.expect("cannot copy an unsized type"));
+        self.store_value(addr, ty, value);
+        addr
+    }
+}
@@ -2,6 +2,32 @@
 
 pub(crate) mod declaration;
 pub(crate) mod func_decl;
+pub(crate) mod jump;
 
 pub use declaration::*;
 pub use func_decl::*;
+
+use fluxc_hir::Stmt;
+
+use crate::{Translate, TranslationContext};
+
+impl Translate for Stmt {
+    type Output = ();
+
+    fn translate(&self, ctx: &mut TranslationContext<'_>) {
+        match self {
+            Stmt::Declaration(decl) => decl.translate(ctx),
+            Stmt::Return(expr) => {
+                let value = expr.translate(ctx);
+                ctx.return_value(value, &expr.ty);
+            }
+            Stmt::Break(expr) => {
+                expr.translate(ctx);
+                ctx.break_loop();
+            }
+            Stmt::Expr(expr) => {
+                expr.translate(ctx);
+            }
+        }
+    }
+}
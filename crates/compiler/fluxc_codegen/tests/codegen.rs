@@ -0,0 +1,446 @@
+use std::{mem, rc::Rc};
+
+use fluxc_codegen::ModuleContext;
+use fluxc_hir::Module;
+use fluxc_lexer::lex;
+use fluxc_parser::parse;
+use fluxc_span::SpanContext;
+use fluxc_walker::Walker;
+
+/// Parse the given source and lower it into HIR.
+fn walk(src: &str) -> Module {
+    let ast = parse(lex(src).expect("Lexing failed!")).expect("Parsing failed!");
+    let mut walker =
+        Walker::new(Rc::new(SpanContext { source: src.into(), path: "test.flx".into() }));
+    walker.walk(&ast).expect("Walking failed!")
+}
+
+/// Compile the given source and run its `main` function, returning the
+/// integer it returns.
+fn run(src: &str) -> i64 {
+    let module = walk(src);
+    let module_ctx = ModuleContext::for_module();
+    let mut module_ctx = module_ctx.write().unwrap();
+    module_ctx.define_module(&module).expect("Codegen failed!");
+    module_ctx.finalize().expect("Finalizing failed!");
+    let main = module_ctx.function_ptr("main").expect("`main` was not defined");
+    // SAFETY: `main` takes no arguments and returns an `int`
+    let main: extern "C" fn() -> i64 = unsafe { mem::transmute(main) };
+    main()
+}
+
+#[test]
+fn test_codegen_struct_fields() {
+    let result = run(r#"
+struct Point { x: int, y: int }
+main -> int {
+    let p = Point { x: 3, y: 4 }
+    return p.x * p.y
+}
+"#);
+    assert_eq!(result, 12);
+}
+
+#[test]
+fn test_codegen_empty_structs() {
+    let result = run(r#"
+struct Empty {}
+struct Wrapper { empty: Empty, value: int }
+main -> int {
+    let w = Wrapper { empty: Empty {}, value: 7 }
+    if w.empty == (Empty {}) {
+        return w.value
+    }
+    return 0
+}
+"#);
+    assert_eq!(result, 7);
+}
+
+#[test]
+fn test_codegen_field_assignment() {
+    let result = run(r#"
+struct Point { x: int, y: int }
+main -> int {
+    mut p = Point { x: 1, y: 2 }
+    let q = p
+    p.x = 10
+    return p.x + q.x
+}
+"#);
+    // `q` holds a copy of `p`, so is unaffected by the assignment
+    assert_eq!(result, 11);
+}
+
+#[test]
+fn test_codegen_methods() {
+    let result = run(r#"
+class Counter {
+    count: int
+
+    new -> Counter {
+        return Counter { count: 0 }
+    }
+
+    add mut self, n: int -> {
+        self.count += n
+    }
+
+    get self -> int {
+        return self.count
+    }
+}
+main -> int {
+    mut counter = Counter.new ()
+    counter.add 2
+    counter.add 3
+    return counter.get ()
+}
+"#);
+    assert_eq!(result, 5);
+}
+
+#[test]
+fn test_codegen_control_flow() {
+    let result = run(r#"
+add a, b -> int {
+    return a + b
+}
+main -> int {
+    mut i = 0
+    mut sum = 0
+    while i < 10 {
+        if i % 2 == 0 {
+            let next = add sum, i
+            sum = next
+        }
+        i += 1
+    }
+    return sum
+}
+"#);
+    assert_eq!(result, 20);
+}
+
+#[test]
+fn test_codegen_logical_precedence() {
+    let result = run(r#"
+main -> int {
+    let yes = true
+    let no = false
+    mut result = 0
+    if yes || no && no {
+        result += 1
+    }
+    if no && yes || yes {
+        result += 10
+    }
+    return result
+}
+"#);
+    assert_eq!(result, 11);
+}
+
+#[test]
+fn test_codegen_match_else() {
+    let result = run(r#"
+describe n: int -> int {
+    let k = 5
+    return match n {
+        0 => 1,
+        k => 10,
+        else => 100,
+    }
+}
+main -> int {
+    return (describe 0) + (describe 5) + (describe 7)
+}
+"#);
+    assert_eq!(result, 111);
+}
+
+#[test]
+fn test_codegen_integer_casts() {
+    // widening extends the sign of signed integers only
+    let result = run("main -> int {\n    let x: i8 = -1\n    return x as int\n}");
+    assert_eq!(result, -1);
+    let result = run("main -> int {\n    let x: u8 = 255\n    return x as int\n}");
+    assert_eq!(result, 255);
+    // narrowing keeps the lowest bits
+    let result = run("main -> int {\n    let x = 300\n    return x as u8 as int\n}");
+    assert_eq!(result, 44);
+    let result = run("main -> int {\n    let x = 65535\n    return x as i16 as int\n}");
+    assert_eq!(result, -1);
+}
+
+#[test]
+fn test_codegen_float_casts() {
+    let result = run("main -> int {\n    let ratio = 7 as float / 2 as float\n    return (ratio * 4.0) as int\n}");
+    assert_eq!(result, 14);
+    // floats are truncated towards zero
+    let result = run("main -> int {\n    let x = 3.7\n    return (0.0 - x) as int\n}");
+    assert_eq!(result, -3);
+    let result = run("main -> int {\n    let x = 2.5f32\n    return x as float as int\n}");
+    assert_eq!(result, 2);
+}
+
+#[test]
+fn test_codegen_char_casts() {
+    let result = run("main -> int {\n    return 'a' as int\n}");
+    assert_eq!(result, 97);
+    let result = run("main -> int {\n    let c = 97 as char\n    return (c as u8 + 1) as int\n}");
+    assert_eq!(result, 98);
+}
+
+#[test]
+fn test_codegen_constants() {
+    let result = run(r#"
+const LIMIT = 10
+const HALF = 3 as float / 2.0
+const ON = true
+const GREETING = "hello, " + "world"
+main -> int {
+    const LOCAL = "hello, world"
+    mut result = LIMIT
+    if ON {
+        result += (HALF * 2.0) as int
+    }
+    if GREETING == LOCAL {
+        result += 100
+    }
+    return result
+}
+"#);
+    assert_eq!(result, 113);
+}
+
+#[test]
+fn test_codegen_optionals() {
+    let result = run(r#"
+find id: int -> int? {
+    if id == 0 {
+        return ()
+    }
+    return id * 2
+}
+main -> int {
+    let a = find 0
+    let b = find 4
+    mut total = 0
+    if b != () {
+        total += b
+    }
+    if a == () {
+        total += 1
+    }
+    total += a ?? 100
+    total += b ?? 100
+    if b == 8 {
+        total += 1000
+    }
+    return total
+}
+"#);
+    assert_eq!(result, 1117);
+}
+
+#[test]
+fn test_codegen_optional_fields() {
+    let result = run(r#"
+struct Point { x: int, y: int }
+struct Node { value: int, next: Point? }
+main -> int {
+    mut p: Point? = ()
+    let missing = p?.x ?? 5
+    p = Point { x: 3, y: 4 }
+    let present = p?.x ?? 5
+    let n = Node { value: 1, next: Point { x: 10, y: 20 } }
+    let y = n.next?.y ?? 0
+    let m: int? = 7
+    return missing + present + y + n.value + m as int
+}
+"#);
+    assert_eq!(result, 36);
+}
+
+#[test]
+fn test_codegen_union_widening() {
+    let result = run(r#"
+widen value: int? -> int | string | unit {
+    return value
+}
+main -> int {
+    let present: int? = 5
+    let absent: int? = ()
+    let w = widen present
+    let v = widen absent
+    mut total = 0
+    if w == 5 {
+        total += 1
+    }
+    if v == () {
+        total += 10
+    }
+    if w != "five" {
+        total += 100
+    }
+    return total
+}
+"#);
+    // unions are re-tagged when passed where a union of more members is
+    // expected
+    assert_eq!(result, 111);
+}
+
+#[test]
+fn test_codegen_union_arguments() {
+    let result = run(r#"
+widen value: int | string -> int {
+    if value == "five" {
+        return 5
+    }
+    return value as int
+}
+main -> int {
+    let a = widen 7
+    let b = widen "five"
+    return a * 10 + b
+}
+"#);
+    // members are injected into the union when passed as arguments
+    assert_eq!(result, 75);
+}
+
+#[test]
+fn test_codegen_error_propagation() {
+    let result = run(r#"
+check n: int -> int | Error {
+    if n < 0 {
+        return Error { message: "negative" }
+    }
+    return n * 2
+}
+twice n: int -> int | Error {
+    let checked = check n
+    let doubled = checked?
+    return doubled + 1
+}
+main -> int {
+    let negative = 0 - 1
+    let ok = twice 5
+    let failed = twice negative
+    mut total = 0
+    if ok == 11 {
+        total += 1
+    }
+    let error = failed as Error
+    if error.message == "negative" {
+        total += 10
+    }
+    return total
+}
+"#);
+    // errors are returned from the function propagating them, while other
+    // values are unwrapped
+    assert_eq!(result, 11);
+}
+
+#[test]
+fn test_codegen_dynamic_dispatch() {
+    let result = run(r#"
+interface Shape {
+    scale self, n: int -> int
+}
+struct Square { size: int }
+struct Line { length: int, width: int }
+impl Shape for Square {
+    scale self, n: int -> int {
+        return self.size * n
+    }
+}
+impl Shape for Line {
+    scale self, n: int -> int {
+        return self.length * self.width + n
+    }
+}
+measure shape: Shape -> int {
+    return shape.scale 2
+}
+main -> int {
+    let square = Square { size: 3 }
+    let line = Line { length: 4, width: 5 }
+    let x = measure square
+    let y = measure line
+    return x * 100 + y
+}
+"#);
+    // each value is scaled by the method of its own type
+    assert_eq!(result, 622);
+}
+
+#[test]
+fn test_codegen_closures() {
+    let result = run(r#"
+apply f: (int) -> int, x: int -> int {
+    return f x
+}
+double x: int -> int {
+    return x * 2
+}
+main -> int {
+    let n = 10
+    let add = (x: int) -> int { x + n }
+    let a = apply add, 1
+    let b = apply double, 4
+    return a * 100 + b
+}
+"#);
+    // closures see their captures, and functions are passed through thunks
+    assert_eq!(result, 1108);
+}
+
+#[test]
+fn test_codegen_struct_captures() {
+    let result = run(r#"
+struct Point { x: int, y: int }
+apply f: (int) -> int, x: int -> int {
+    return f x
+}
+main -> int {
+    let p = Point { x: 3, y: 4 }
+    let offset = (k: int) -> int { p.x * k + p.y }
+    return apply offset, 5
+}
+"#);
+    // captured structs are read through the environment of the closure
+    assert_eq!(result, 19);
+}
+
+#[test]
+fn test_codegen_interface_bound() {
+    let result = run(r#"
+interface Comparable {
+    compare self, other: Self -> int
+}
+struct N { value: int }
+impl Comparable for N {
+    compare self, other: N -> int {
+        return self.value - other.value
+    }
+}
+max<T extends Comparable> a: T, b: T -> T {
+    let order = a.compare b
+    if order > 0 {
+        return a
+    }
+    return b
+}
+main -> int {
+    let a = max N { value: 3 }, N { value: 7 }
+    let b = max N { value: 9 }, N { value: 2 }
+    return a.value * 10 + b.value
+}
+"#);
+    // the instance of `max` for `N` calls the method of its implementation
+    assert_eq!(result, 79);
+}
@@ -0,0 +1,46 @@
+use std::rc::Rc;
+
+use fluxc_ast::{Primitive, TypeExpr};
+use fluxc_codegen::ModuleContext;
+use fluxc_hir::Module;
+use fluxc_lexer::lex;
+use fluxc_parser::parse;
+use fluxc_span::SpanContext;
+use fluxc_walker::Walker;
+
+/// Parse the given source and lower it into HIR.
+fn walk(src: &str) -> Module {
+    let ast = parse(lex(src).expect("Lexing failed!")).expect("Parsing failed!");
+    let mut walker =
+        Walker::new(Rc::new(SpanContext { source: src.into(), path: "test.flx".into() }));
+    walker.walk(&ast).expect("Walking failed!")
+}
+
+/// Return the size of the named struct and the offsets of its fields, in
+/// declaration order.
+fn layout(module_ctx: &ModuleContext, name: &str) -> (usize, Vec<usize>) {
+    let entry = module_ctx
+        .resolve_type(&TypeExpr::Primitive(Primitive::Ref(name.to_string())))
+        .expect("struct was not registered");
+    let offsets = entry.fields.iter().flatten().map(|field| field.offset.unwrap()).collect();
+    (entry.size.unwrap(), offsets)
+}
+
+#[test]
+fn test_extern_struct_layout() {
+    let module = walk(
+        r#"
+struct Packed { r: u8, value: int, g: u8 }
+extern struct Pixel { r: u8, value: int, g: u8 }
+"#,
+    );
+    let module_ctx = ModuleContext::for_module();
+    let mut module_ctx = module_ctx.write().unwrap();
+    for decl in &module.structs {
+        module_ctx.declare_struct(decl);
+    }
+    // flux structs reorder their fields to avoid padding
+    assert_eq!(layout(&module_ctx, "Packed"), (16, vec![8, 0, 9]));
+    // extern structs keep declaration order, as C would
+    assert_eq!(layout(&module_ctx, "Pixel"), (24, vec![0, 8, 16]));
+}
@@ -13,6 +13,111 @@ pub enum ErrorKind {
     /// E0101 - Expected token, but found token.
     #[error("E0101 - expected `{0}`, but found `{1}`")]
     E0101(String, String),
+    /// E0200 - Undeclared interface.
+    #[error("E0200 - cannot find interface `{0}`")]
+    E0200(String),
+    /// E0201 - Missing interface method.
+    #[error("E0201 - type `{0}` does not implement `{1}`, required by interface `{2}`")]
+    E0201(String, String, String),
+    /// E0202 - Method is not a member of the interface.
+    #[error("E0202 - method `{0}` is not a member of interface `{1}`")]
+    E0202(String, String),
+    /// E0203 - Method signature does not match the interface.
+    #[error("E0203 - method `{0}` has an incompatible signature for interface `{1}`")]
+    E0203(String, String),
+    /// E0204 - Mismatched types.
+    #[error("E0204 - mismatched types: expected `{0}`, found `{1}`")]
+    E0204(String, String),
+    /// E0205 - Undeclared value.
+    #[error("E0205 - cannot find value `{0}` in this scope")]
+    E0205(String),
+    /// E0206 - Infinite type.
+    #[error("E0206 - cannot construct the infinite type `{0}`")]
+    E0206(String),
+    /// E0207 - Unknown field.
+    #[error("E0207 - no field `{0}` on type `{1}`")]
+    E0207(String, String),
+    /// E0208 - Unknown method.
+    #[error("E0208 - no method `{0}` on type `{1}`")]
+    E0208(String, String),
+    /// E0209 - Undeclared type.
+    #[error("E0209 - cannot find type `{0}` in this scope")]
+    E0209(String),
+    /// E0210 - Duplicate definition.
+    #[error("E0210 - the name `{0}` is defined multiple times")]
+    E0210(String),
+    /// E0211 - Mutation of an immutable variable.
+    #[error("E0211 - cannot mutate immutable variable `{0}`")]
+    E0211(String),
+    /// E0212 - Mutation of a constant.
+    #[error("E0212 - cannot mutate constant `{0}`")]
+    E0212(String),
+    /// E0213 - Constant initializer is not evaluable at compile-time.
+    #[error("E0213 - the value of constant `{0}` cannot be evaluated at compile-time")]
+    E0213(String),
+    /// E0214 - Constant evaluation failed.
+    #[error("E0214 - evaluation of constant `{0}` failed: {1}")]
+    E0214(String, String),
+    /// E0215 - Cyclic type alias.
+    #[error("E0215 - type alias `{0}` refers to itself")]
+    E0215(String),
+    /// E0216 - Infinitely sized type.
+    #[error("E0216 - recursive type `{0}` has infinite size")]
+    E0216(String),
+    /// E0217 - Type argument does not satisfy the bound of its parameter.
+    #[error("E0217 - type `{0}` does not satisfy the bound `{1}`")]
+    E0217(String, String),
+    /// E0218 - Wrong number of type arguments.
+    #[error("E0218 - type `{0}` takes {1} type arguments, but {2} were given")]
+    E0218(String, usize, usize),
+    /// E0219 - Type captured outside of a conditional type.
+    #[error("E0219 - `infer {0}` may only be used in the `extends` clause of a conditional type")]
+    E0219(String),
+    /// E0220 - Non-exhaustive match.
+    #[error("E0220 - non-exhaustive match: values of type `{0}` are not covered")]
+    E0220(String),
+    /// E0221 - Mutation of a variable captured by a closure.
+    #[error("E0221 - cannot mutate `{0}`, which is captured by value by a closure")]
+    E0221(String),
+    /// E0222 - Use of an optional value before it is narrowed.
+    #[error("E0222 - value of optional type `{0}` must be checked for `()` before it is used")]
+    E0222(String),
+    /// E0223 - Propagation of an error from a value that cannot be an error.
+    #[error(
+        "E0223 - cannot propagate an error from a value of type `{0}`, which is never an `Error`"
+    )]
+    E0223(String),
+    /// E0224 - Cast between types with no conversion between them.
+    #[error("E0224 - cannot cast a value of type `{0}` to `{1}`")]
+    E0224(String, String),
+    /// E0225 - Arithmetic between different numeric types.
+    #[error(
+        "E0225 - mismatched numeric types `{0}` and `{1}`, which must be cast to a common type"
+    )]
+    E0225(String, String),
+    /// E0226 - Number literal outside of the range of its type.
+    #[error("E0226 - literal `{0}` is out of range for `{1}`")]
+    E0226(String, String),
+    /// E0227 - Struct literal missing a field.
+    #[error("E0227 - missing field `{0}` in initializer of `{1}`")]
+    E0227(String, String),
+    /// E0228 - Method taking a receiver called on a class.
+    #[error("E0228 - method `{0}` takes `self`, so must be called on a value of type `{1}`")]
+    E0228(String, String),
+    /// E0229 - Non-exhaustive `if` used as a value.
+    #[error("E0229 - non-exhaustive `if` produces a value of type `{0}` without an `else` branch")]
+    E0229(String),
+}
+
+/// An enum of all possible warnings raised by the compiler.
+#[derive(Debug, Error, Clone)]
+pub enum WarningKind {
+    /// W0001 - Unused variable.
+    #[error("W0001 - unused variable `{0}`")]
+    W0001(String),
+    /// W0002 - Unused import.
+    #[error("W0002 - unused import `{0}`")]
+    W0002(String),
 }
 
 /// A fatal error thrown by the compiler.
@@ -22,6 +127,19 @@ pub struct FatalError {
     pub kind: ErrorKind,
     /// The span of the source file that caused this error.
     pub span: Span,
+    /// Other spans of the source file relevant to this error, such as the
+    /// declaration of the value the error concerns.
+    pub related: Vec<Span>,
+}
+
+/// A warning raised by the compiler. Unlike a fatal error, a warning does not
+/// stop compilation.
+#[derive(Debug, Clone)]
+pub struct Warning {
+    /// The warning kind.
+    pub kind: WarningKind,
+    /// The span of the source file that caused this warning.
+    pub span: Span,
 }
 
 /// An enum of possible error types.
@@ -33,6 +151,19 @@ pub enum CompilerError {
 impl FatalError {
     /// Creates a new fatal error.
     pub fn new(span: Span, kind: ErrorKind) -> FatalError {
-        FatalError { span, kind }
+        FatalError { span, kind, related: vec![] }
+    }
+
+    /// Attach a related span to this error.
+    pub fn with_related(mut self, span: Span) -> FatalError {
+        self.related.push(span);
+        self
+    }
+}
+
+impl Warning {
+    /// Creates a new warning.
+    pub fn new(span: Span, kind: WarningKind) -> Warning {
+        Warning { span, kind }
     }
 }
@@ -1,3 +1,4 @@
+#[derive(Clone)]
 pub struct Package {
 	name: String,
 	version: String,
@@ -26,10 +27,10 @@ impl Package {
 	}
 
 	pub fn flatten_to(&self, out: &mut Vec<Package>) {
-		for d in self.dependencies {
+		for d in &self.dependencies {
 			d.package.flatten_to(out);
 		}
-		out.push(self);
+		out.push(self.clone());
 	}
 
 	pub fn flatten(self) -> Vec<Package> {
@@ -39,8 +40,22 @@ impl Package {
 	}
 }
 
+#[derive(Clone)]
 pub struct Dependency {
 	package: Package,
 	optional: bool,
 }
 
+impl Dependency {
+	pub fn new(package: Package, optional: bool) -> Self {
+		Self { package, optional }
+	}
+
+	pub fn package(&self) -> &Package {
+		&self.package
+	}
+
+	pub fn optional(&self) -> bool {
+		self.optional
+	}
+}
@@ -1,2 +1,119 @@
+use fluxc_ast::{ClassDecl, MethodDecl, Primitive, StructDecl, StructField, TypeExpr};
+use fluxc_types::layout::Repr;
+
+use crate::{Argument, Function, FunctionKind};
+
+/// HIR datatype representing a Flux struct.
+#[derive(Debug, Clone)]
+pub struct Struct {
+    /// The name of this struct.
+    pub name: String,
+    /// The names of the type parameters of this struct.
+    pub generics: Vec<String>,
+    /// The fields of this struct, in declaration order.
+    pub fields: Vec<Field>,
+    /// The representation its fields are laid out with.
+    pub repr: Repr,
+}
+
 /// HIR datatype representing a Flux class.
-pub struct Class {}
+#[derive(Debug, Clone)]
+pub struct Class {
+    /// The name of this class.
+    pub name: String,
+    /// The names of the type parameters of this class.
+    pub generics: Vec<String>,
+    /// The fields of this class, in declaration order.
+    pub fields: Vec<Field>,
+    /// The methods of this class.
+    pub methods: Vec<Function>,
+}
+
+/// A field on a struct or class.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field {
+    /// The name of this field.
+    pub name: String,
+    /// The type of this field.
+    pub ty: TypeExpr,
+}
+
+/// Trait providing the `as_struct` method.
+pub trait AsStruct {
+    /// This method returns `self` as a `Struct` type.
+    fn as_struct(&self) -> Struct;
+}
+
+impl AsStruct for StructDecl {
+    fn as_struct(&self) -> Struct {
+        Struct {
+            name: self.ident.value.clone(),
+            generics: self.generics.iter().map(|param| param.value.ident.value.clone()).collect(),
+            fields: self.fields.iter().map(|x| (&x.value).into()).collect(),
+            repr: if self.external { Repr::C } else { Repr::Flux },
+        }
+    }
+}
+
+/// Trait providing the `as_class` method.
+pub trait AsClass {
+    /// This method returns `self` as a `Class` type.
+    fn as_class(&self) -> Class;
+}
+
+impl AsClass for ClassDecl {
+    fn as_class(&self) -> Class {
+        let generics: Vec<String> =
+            self.generics.iter().map(|param| param.value.ident.value.clone()).collect();
+        let mut methods: Vec<Function> =
+            self.methods.iter().map(|x| x.value.as_method(&self.ident.value)).collect();
+        // the receivers of methods of generic classes are generic over the
+        // class's type parameters
+        if !generics.is_empty() {
+            let params = generics.iter().map(|name| Primitive::Ref(name.clone()).into()).collect();
+            let this = TypeExpr::Generic(self.ident.value.clone(), params);
+            for method in methods.iter_mut().filter(|method| method.has_receiver()) {
+                method.args[0].ty = this.clone();
+            }
+        }
+        Class {
+            name: self.ident.value.clone(),
+            generics,
+            fields: self.fields.iter().map(|x| (&x.value).into()).collect(),
+            methods,
+        }
+    }
+}
+
+/// Trait providing the `as_method` method.
+pub trait AsMethod {
+    /// This method returns `self` as a method `Function` of the given class.
+    fn as_method(&self, class: &str) -> Function;
+}
+
+impl AsMethod for MethodDecl {
+    fn as_method(&self, class: &str) -> Function {
+        // the receiver is passed as the first argument
+        let receiver = self.receiver.as_ref().map(|_| Argument {
+            name: "self".into(),
+            ty: TypeExpr::Primitive(Primitive::Ref(class.into())),
+        });
+        Function {
+            name: self.ident.value.clone(),
+            generics: vec![],
+            args: receiver
+                .into_iter()
+                .chain(self.params.iter().map(|x| (&x.value).into()))
+                .collect(),
+            kind: FunctionKind::Method,
+            return_type: self.ret_ty.clone_inner(),
+            body: None,
+        }
+    }
+}
+
+impl From<&StructField> for Field {
+    fn from(field: &StructField) -> Self {
+        Field { name: field.ident.clone_inner(), ty: field.ty.clone_inner() }
+    }
+}
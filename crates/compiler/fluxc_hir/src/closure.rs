@@ -0,0 +1,90 @@
+//! Defines the collection of the closures created by HIR nodes, which are
+//! lifted to functions of their own during code generation.
+//!
+//! Only the closures created directly by a node are collected. Closures
+//! created within the body of another closure are collected from the function
+//! that closure is lifted to.
+
+use crate::{Block, Closure, Expr, ExprKind, Function, Stmt};
+
+impl Function {
+    /// Collect the closures created by the body of this function.
+    pub fn closures<'a>(&'a self, closures: &mut Vec<&'a Closure>) {
+        if let Some(body) = &self.body {
+            body.block.closures(closures);
+        }
+    }
+}
+
+impl Block {
+    /// Collect the closures created by this block.
+    pub fn closures<'a>(&'a self, closures: &mut Vec<&'a Closure>) {
+        self.stmts.iter().for_each(|stmt| stmt.closures(closures));
+    }
+}
+
+impl Stmt {
+    /// Collect the closures created by this statement.
+    pub fn closures<'a>(&'a self, closures: &mut Vec<&'a Closure>) {
+        match self {
+            Stmt::Declaration(decl) => decl.value.closures(closures),
+            Stmt::Return(expr) | Stmt::Break(expr) | Stmt::Expr(expr) => expr.closures(closures),
+        }
+    }
+}
+
+impl Expr {
+    /// Collect the closures created by this expression.
+    pub fn closures<'a>(&'a self, closures: &mut Vec<&'a Closure>) {
+        match &self.kind {
+            ExprKind::Literal(_)
+            | ExprKind::Local(_)
+            | ExprKind::Function(_)
+            | ExprKind::Method(..) => (),
+            ExprKind::BinaryExpr(binary) => {
+                binary.lhs.closures(closures);
+                binary.rhs.closures(closures);
+            }
+            ExprKind::UnaryExpr(unary) => unary.expr.closures(closures),
+            ExprKind::Cast(cast) => cast.expr.closures(closures),
+            ExprKind::Block(block) => block.closures(closures),
+            ExprKind::Call(call) => {
+                call.callee.closures(closures);
+                call.args.iter().for_each(|arg| arg.closures(closures));
+            }
+            ExprKind::MethodCall(call) => {
+                call.receiver.closures(closures);
+                call.args.iter().for_each(|arg| arg.closures(closures));
+            }
+            ExprKind::Conditional(conditional) => {
+                for (condition, block) in &conditional.branches {
+                    condition.closures(closures);
+                    block.closures(closures);
+                }
+                if let Some(block) = &conditional.else_block {
+                    block.closures(closures);
+                }
+            }
+            ExprKind::Loop(loop_expr) => loop_expr.block.closures(closures),
+            ExprKind::While(while_expr) => {
+                while_expr.condition.closures(closures);
+                while_expr.block.closures(closures);
+            }
+            ExprKind::Match(match_expr) => {
+                match_expr.expr.closures(closures);
+                for (pattern, value) in &match_expr.branches {
+                    pattern.closures(closures);
+                    value.closures(closures);
+                }
+                if let Some(value) = &match_expr.else_value {
+                    value.closures(closures);
+                }
+            }
+            ExprKind::StructExpr(struct_expr) => {
+                struct_expr.fields.iter().for_each(|(_, value)| value.closures(closures))
+            }
+            ExprKind::FieldAccess(access) => access.expr.closures(closures),
+            ExprKind::Closure(closure) => closures.push(closure),
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use std::ops::Range;
+
+use fluxc_ast::{BinaryOp, Literal, TypeExpr, UnaryOp};
+
+use crate::{Block, Function, LocalId, Variable};
+
+/// HIR datatype representing a typed Flux expression.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expr {
+    /// The kind of this expression.
+    pub kind: ExprKind,
+    /// The inferred type of this expression.
+    pub ty: TypeExpr,
+    /// The span of the source code that this expression represents.
+    pub span: Range<usize>,
+}
+
+/// Enumeration of expression kinds.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExprKind {
+    /// A literal value.
+    Literal(Literal),
+    /// A reference to a local variable or parameter.
+    Local(LocalId),
+    /// A reference to a declared function.
+    Function(String),
+    /// A reference to a method without a receiver of the given type, such as
+    /// `Counter.new`.
+    Method(TypeExpr, String),
+    /// A binary operation.
+    BinaryExpr(BinaryExpr),
+    /// A unary operation.
+    UnaryExpr(UnaryExpr),
+    /// A conversion of a value to another type.
+    Cast(Cast),
+    /// A block of code.
+    Block(Block),
+    /// A function call.
+    Call(Call),
+    /// A method call.
+    MethodCall(MethodCall),
+    /// A conditional expression.
+    Conditional(Conditional),
+    /// An unconditional loop.
+    Loop(Loop),
+    /// A conditional loop.
+    While(While),
+    /// A match expression.
+    Match(Match),
+    /// A struct literal.
+    StructExpr(StructExpr),
+    /// A field access.
+    FieldAccess(FieldAccess),
+    /// An anonymous function.
+    Closure(Closure),
+}
+
+/// A binary operation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BinaryExpr {
+    /// The operator.
+    pub kind: BinaryOp,
+    /// The left hand side of the operation.
+    pub lhs: Box<Expr>,
+    /// The right hand side of the operation.
+    pub rhs: Box<Expr>,
+}
+
+/// A unary operation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnaryExpr {
+    /// The operator.
+    pub kind: UnaryOp,
+    /// The operand.
+    pub expr: Box<Expr>,
+}
+
+/// A conversion of a value to another type, which is the type of the cast
+/// expression itself.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cast {
+    /// The value being converted.
+    pub expr: Box<Expr>,
+}
+
+/// A function call.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Call {
+    /// The function being called, which is either a declared function or an
+    /// expression evaluating to a closure.
+    pub callee: Box<Expr>,
+    /// The arguments passed to the function.
+    pub args: Vec<Expr>,
+    /// The type arguments a generic function is instantiated with, in the
+    /// order of its type parameters. Calls to functions that are not generic
+    /// have none.
+    pub type_args: Vec<TypeExpr>,
+}
+
+/// A method call.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MethodCall {
+    /// The value the method is called on.
+    pub receiver: Box<Expr>,
+    /// The name of the method.
+    pub method: String,
+    /// The arguments passed to the method, excluding the receiver.
+    pub args: Vec<Expr>,
+    /// How the call is dispatched.
+    pub dispatch: Dispatch,
+}
+
+/// Enumeration of method dispatch kinds.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Dispatch {
+    /// The method is called directly on the named type. Instances of generic
+    /// classes are named with their type arguments, such as `Box<int>`.
+    Static(String),
+    /// The method is called through the vtable of the named interface.
+    Dynamic(String),
+}
+
+/// A conditional expression.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conditional {
+    /// The conditions and the blocks they guard, in order.
+    pub branches: Vec<(Expr, Block)>,
+    /// The block run if no condition holds.
+    pub else_block: Option<Block>,
+}
+
+/// An unconditional loop.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Loop {
+    /// The name of this loop, if it has one.
+    pub name: Option<String>,
+    /// The body of the loop.
+    pub block: Block,
+}
+
+/// A conditional loop.
+#[derive(Debug, Clone, PartialEq)]
+pub struct While {
+    /// The loop condition.
+    pub condition: Box<Expr>,
+    /// The body of the loop.
+    pub block: Block,
+}
+
+/// A match expression.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Match {
+    /// The value being matched.
+    pub expr: Box<Expr>,
+    /// The patterns and the values they produce, in order.
+    pub branches: Vec<(Expr, Expr)>,
+    /// The value produced if no pattern matches.
+    pub else_value: Option<Box<Expr>>,
+}
+
+/// A struct literal.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructExpr {
+    /// The name of the struct.
+    pub name: String,
+    /// The values of the fields of the struct.
+    pub fields: Vec<(String, Expr)>,
+}
+
+/// A field access.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldAccess {
+    /// The value whose field is accessed.
+    pub expr: Box<Expr>,
+    /// The name of the field.
+    pub field: String,
+    /// Whether the access is optional, in which case it evaluates to unit if
+    /// the value is unit, rather than accessing its field.
+    pub optional: bool,
+}
+
+/// An anonymous function, along with the variables it captures from the
+/// scopes enclosing it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Closure {
+    /// The function the closure is lifted to, named uniquely within its
+    /// module.
+    pub function: Function,
+    /// The variables captured by the closure, in the order of the captures of
+    /// its body. Their values are copied into the environment of the closure
+    /// when it is created.
+    pub captures: Vec<Variable>,
+}
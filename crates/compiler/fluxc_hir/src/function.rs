@@ -1,29 +1,54 @@
-use fluxc_ast::{FuncDecl, ParenArgument, TypeExpr};
+use std::fmt::{self, Display};
+
+use fluxc_ast::{FuncDecl, FuncParam, Primitive, TypeExpr};
+
+use crate::{Block, LocalId, Variable};
 
 /// HIR datatype representing a Flux function.
 ///
 /// This trait provides utility methods for quickly accessing function
 /// information without knowing if it is a class method,
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Function {
     /// The name of this function.
     pub name: String,
+    /// The names of the type parameters of this function, which its argument
+    /// and return types may refer to.
+    pub generics: Vec<String>,
     /// The kind of this function.
     pub kind: FunctionKind,
     /// The arguments of this function.
     pub args: Vec<Argument>,
     /// The return value of this function.
     pub return_type: TypeExpr,
+    /// The body of this function, once it has been analysed. Functions
+    /// without a body, such as external functions, have none.
+    pub body: Option<Body>,
+}
+
+/// The body of a function.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Body {
+    /// The variables bound to the arguments of the function, in order.
+    pub params: Vec<Variable>,
+    /// The block of statements making up the function.
+    pub block: Block,
+    /// The variables declared outside of the function that its body refers
+    /// to.
+    pub captures: Vec<LocalId>,
 }
 
 /// Enumeration of function kinds for use in compile-time reflection.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum FunctionKind {
     /// A standard function declaration of the form `x -> y`.
     Orphan,
     /// An inline function declaration of the form `x -> y`, that gets inlined
     /// at compile-time.
     InlineOrphan,
+    /// A function declaration of the form `const x -> y`, that may be
+    /// evaluated at compile-time.
+    Const,
     /// An external function declaration.
     External,
     /// A method declaration inside a class.
@@ -36,15 +61,61 @@ pub enum FunctionKind {
     Default,
     /// An inline default method implementation inside an interface.
     InlineDefault,
+    /// An anonymous function, which takes the environment of the closure it
+    /// was created in as its first argument.
+    Closure,
 }
 
 /// An argument to a function definition.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct Argument {
     pub name: String,
     pub ty: TypeExpr,
 }
 
+impl Function {
+    /// Returns `true` if this function has type parameters, and so must be
+    /// instantiated with type arguments before code can be generated for it.
+    pub fn is_generic(&self) -> bool {
+        !self.generics.is_empty()
+    }
+
+    /// Returns `true` if this function is a method taking a `self` receiver.
+    pub fn has_receiver(&self) -> bool {
+        self.args.first().is_some_and(|arg| arg.name == "self")
+    }
+
+    /// Returns `true` if this function takes the same argument types and
+    /// returns the same type as the given function.
+    pub fn signature_eq(&self, other: &Function) -> bool {
+        self.args.len() == other.args.len()
+            && self.args.iter().zip(&other.args).all(|(a, b)| a.ty == b.ty)
+            && self.return_type == other.return_type
+    }
+}
+
+/// Functions are displayed as their signature, in the form it would be
+/// declared in, e.g. `add x: int, y: int -> int`.
+impl Display for Function {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.kind == FunctionKind::Const {
+            write!(f, "const ")?;
+        }
+        write!(f, "{}", self.name)?;
+        if !self.generics.is_empty() {
+            write!(f, "<{}>", self.generics.join(", "))?;
+        }
+        for (i, arg) in self.args.iter().enumerate() {
+            write!(f, "{}", if i == 0 { " " } else { ", " })?;
+            match i == 0 && self.has_receiver() {
+                true => write!(f, "self")?,
+                false => write!(f, "{}: {}", arg.name, arg.ty)?,
+            }
+        }
+        write!(f, " -> {}", self.return_type)
+    }
+}
+
 /// Trait providing the `as_function` method.
 pub trait AsFunction {
     /// This method returns `self` as a `Function` type.
@@ -54,27 +125,50 @@ pub trait AsFunction {
 impl AsFunction for FuncDecl {
     fn as_function(&self) -> Function {
         match self {
-            FuncDecl::Local { ident, params: args, body: _, ret_ty } => Function {
+            FuncDecl::Local { ident, generics, params: args, body: _, ret_ty, constant } => {
+                Function {
+                    name: ident.value.clone(),
+                    generics: generics
+                        .iter()
+                        .map(|param| param.value.ident.value.clone())
+                        .collect(),
+                    args: args.iter().map(|x| (&x.value).into()).collect(),
+                    kind: match constant {
+                        true => FunctionKind::Const,
+                        false => FunctionKind::Orphan,
+                    },
+                    return_type: ret_ty.clone_inner(),
+                    body: None,
+                }
+            }
+            FuncDecl::Export { ident, generics, params: args, body: _, ret_ty } => Function {
                 name: ident.value.clone(),
+                generics: generics.iter().map(|param| param.value.ident.value.clone()).collect(),
                 args: args.iter().map(|x| (&x.value).into()).collect(),
                 kind: FunctionKind::Orphan,
-                return_type: ret_ty.clone_inner(),
+                return_type: ret_ty
+                    .as_ref()
+                    .map(|ty| ty.clone_inner())
+                    .unwrap_or(TypeExpr::Primitive(Primitive::Unit)),
+                body: None,
             },
-            FuncDecl::Export { ident: _, args: _, body: _, ret_ty: _ } => {
-                todo!("remove FuncDecl::Export")
-            }
             FuncDecl::External { ident, params: args, ret_ty } => Function {
                 name: ident.value.clone(),
+                generics: vec![],
                 args: args.iter().map(|x| (&x.value).into()).collect(),
                 kind: FunctionKind::External,
-                return_type: ret_ty.clone_inner(),
+                return_type: ret_ty
+                    .as_ref()
+                    .map(|ty| ty.clone_inner())
+                    .unwrap_or(TypeExpr::Primitive(Primitive::Unit)),
+                body: None,
             },
         }
     }
 }
 
-impl Into<Argument> for &ParenArgument {
-    fn into(self) -> Argument {
-        Argument { name: self.ident.clone_inner(), ty: self.ty.clone_inner() }
+impl From<&FuncParam> for Argument {
+    fn from(param: &FuncParam) -> Self {
+        Argument { name: param.ident.clone_inner(), ty: param.ty.clone_inner() }
     }
 }
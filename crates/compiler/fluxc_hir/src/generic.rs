@@ -0,0 +1,252 @@
+//! Defines the instantiation of generic functions and classes.
+//!
+//! Generic functions are monomorphized: a copy of the function is made for
+//! every distinct list of type arguments it is called with, in which every
+//! reference to a type parameter is replaced by its type argument. Likewise,
+//! the methods of generic classes are copied for every list of type arguments
+//! of the receivers they are called on.
+
+use std::collections::HashMap;
+
+use fluxc_ast::TypeExpr;
+
+use crate::{Block, Body, Closure, Dispatch, Expr, ExprKind, Function, Stmt, Variable};
+
+/// Trait implemented by HIR nodes whose types may refer to type parameters.
+pub trait Substitute {
+    /// Replace every reference to a type parameter with the type argument it
+    /// is mapped to.
+    fn substitute(&mut self, args: &HashMap<String, TypeExpr>);
+}
+
+impl Substitute for Function {
+    fn substitute(&mut self, args: &HashMap<String, TypeExpr>) {
+        for arg in &mut self.args {
+            arg.ty = arg.ty.substitute(args);
+        }
+        self.return_type = self.return_type.substitute(args);
+        if let Some(body) = &mut self.body {
+            body.substitute(args);
+        }
+    }
+}
+
+impl Substitute for Body {
+    fn substitute(&mut self, args: &HashMap<String, TypeExpr>) {
+        self.params.iter_mut().for_each(|param| param.substitute(args));
+        self.block.substitute(args);
+    }
+}
+
+impl Substitute for Variable {
+    fn substitute(&mut self, args: &HashMap<String, TypeExpr>) {
+        self.ty = self.ty.substitute(args);
+    }
+}
+
+impl Substitute for Block {
+    fn substitute(&mut self, args: &HashMap<String, TypeExpr>) {
+        self.stmts.iter_mut().for_each(|stmt| stmt.substitute(args));
+        self.ty = self.ty.substitute(args);
+    }
+}
+
+impl Substitute for Stmt {
+    fn substitute(&mut self, args: &HashMap<String, TypeExpr>) {
+        match self {
+            Stmt::Declaration(decl) => {
+                decl.variable.substitute(args);
+                decl.value.substitute(args);
+            }
+            Stmt::Return(expr) | Stmt::Break(expr) | Stmt::Expr(expr) => expr.substitute(args),
+        }
+    }
+}
+
+impl Substitute for Expr {
+    fn substitute(&mut self, args: &HashMap<String, TypeExpr>) {
+        self.ty = self.ty.substitute(args);
+        match &mut self.kind {
+            ExprKind::Literal(_) | ExprKind::Local(_) | ExprKind::Function(_) => (),
+            ExprKind::Method(ty, _) => *ty = ty.substitute(args),
+            ExprKind::BinaryExpr(binary) => {
+                binary.lhs.substitute(args);
+                binary.rhs.substitute(args);
+            }
+            ExprKind::UnaryExpr(unary) => unary.expr.substitute(args),
+            ExprKind::Cast(cast) => cast.expr.substitute(args),
+            ExprKind::Block(block) => block.substitute(args),
+            ExprKind::Call(call) => {
+                call.callee.substitute(args);
+                call.args.iter_mut().for_each(|arg| arg.substitute(args));
+                call.type_args = call.type_args.iter().map(|ty| ty.substitute(args)).collect();
+            }
+            ExprKind::MethodCall(call) => {
+                call.receiver.substitute(args);
+                call.args.iter_mut().for_each(|arg| arg.substitute(args));
+                // calls on instances of generic classes dispatch to the copy
+                // of the method for the receiver's type arguments, and calls
+                // on type parameters to the method of their type argument
+                if let Dispatch::Static(ty) = &call.dispatch {
+                    if matches!(call.receiver.ty, TypeExpr::Generic(..)) || args.contains_key(ty) {
+                        call.dispatch = Dispatch::Static(call.receiver.ty.to_string());
+                    }
+                }
+            }
+            ExprKind::Conditional(conditional) => {
+                for (condition, block) in &mut conditional.branches {
+                    condition.substitute(args);
+                    block.substitute(args);
+                }
+                if let Some(block) = &mut conditional.else_block {
+                    block.substitute(args);
+                }
+            }
+            ExprKind::Loop(loop_expr) => loop_expr.block.substitute(args),
+            ExprKind::While(while_expr) => {
+                while_expr.condition.substitute(args);
+                while_expr.block.substitute(args);
+            }
+            ExprKind::Match(match_expr) => {
+                match_expr.expr.substitute(args);
+                for (pattern, value) in &mut match_expr.branches {
+                    pattern.substitute(args);
+                    value.substitute(args);
+                }
+                if let Some(value) = &mut match_expr.else_value {
+                    value.substitute(args);
+                }
+            }
+            ExprKind::StructExpr(struct_expr) => {
+                struct_expr.fields.iter_mut().for_each(|(_, value)| value.substitute(args))
+            }
+            ExprKind::FieldAccess(access) => access.expr.substitute(args),
+            ExprKind::Closure(closure) => closure.substitute(args),
+        }
+    }
+}
+
+impl Substitute for Closure {
+    fn substitute(&mut self, args: &HashMap<String, TypeExpr>) {
+        self.function.substitute(args);
+        self.captures.iter_mut().for_each(|capture| capture.substitute(args));
+    }
+}
+
+/// A generic function or class, instantiated with type arguments.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Instance {
+    /// A generic function, called with the given type arguments.
+    Function(String, Vec<TypeExpr>),
+    /// A generic class, whose methods are called on a receiver with the given
+    /// type arguments.
+    Class(String, Vec<TypeExpr>),
+}
+
+impl Function {
+    /// Return a copy of this generic function with its type parameters
+    /// replaced by the given type arguments.
+    pub fn instantiate(&self, type_args: &[TypeExpr]) -> Function {
+        let args = self.generics.iter().cloned().zip(type_args.iter().cloned()).collect();
+        let mut instance = self.clone();
+        instance.generics.clear();
+        instance.substitute(&args);
+        instance
+    }
+
+    /// Collect the instances of generic functions and classes used by the body
+    /// of this function.
+    pub fn instances(&self, instances: &mut Vec<Instance>) {
+        if let Some(body) = &self.body {
+            body.block.instances(instances);
+        }
+    }
+}
+
+impl Block {
+    /// Collect the instances of generic functions and classes used by this
+    /// block.
+    pub fn instances(&self, instances: &mut Vec<Instance>) {
+        self.stmts.iter().for_each(|stmt| stmt.instances(instances));
+    }
+}
+
+impl Stmt {
+    /// Collect the instances of generic functions and classes used by this
+    /// statement.
+    pub fn instances(&self, instances: &mut Vec<Instance>) {
+        match self {
+            Stmt::Declaration(decl) => decl.value.instances(instances),
+            Stmt::Return(expr) | Stmt::Break(expr) | Stmt::Expr(expr) => expr.instances(instances),
+        }
+    }
+}
+
+impl Expr {
+    /// Collect the instances of generic functions and classes used by this
+    /// expression.
+    pub fn instances(&self, instances: &mut Vec<Instance>) {
+        match &self.kind {
+            ExprKind::Literal(_) | ExprKind::Local(_) | ExprKind::Function(_) => (),
+            ExprKind::Method(ty, _) => {
+                if let TypeExpr::Generic(name, args) = ty {
+                    instances.push(Instance::Class(name.clone(), args.clone()));
+                }
+            }
+            ExprKind::BinaryExpr(binary) => {
+                binary.lhs.instances(instances);
+                binary.rhs.instances(instances);
+            }
+            ExprKind::UnaryExpr(unary) => unary.expr.instances(instances),
+            ExprKind::Cast(cast) => cast.expr.instances(instances),
+            ExprKind::Block(block) => block.instances(instances),
+            ExprKind::Call(call) => {
+                if let (ExprKind::Function(name), false) =
+                    (&call.callee.kind, call.type_args.is_empty())
+                {
+                    instances.push(Instance::Function(name.clone(), call.type_args.clone()));
+                }
+                call.callee.instances(instances);
+                call.args.iter().for_each(|arg| arg.instances(instances));
+            }
+            ExprKind::MethodCall(call) => {
+                if let (TypeExpr::Generic(name, args), Dispatch::Static(_)) =
+                    (&call.receiver.ty, &call.dispatch)
+                {
+                    instances.push(Instance::Class(name.clone(), args.clone()));
+                }
+                call.receiver.instances(instances);
+                call.args.iter().for_each(|arg| arg.instances(instances));
+            }
+            ExprKind::Conditional(conditional) => {
+                for (condition, block) in &conditional.branches {
+                    condition.instances(instances);
+                    block.instances(instances);
+                }
+                if let Some(block) = &conditional.else_block {
+                    block.instances(instances);
+                }
+            }
+            ExprKind::Loop(loop_expr) => loop_expr.block.instances(instances),
+            ExprKind::While(while_expr) => {
+                while_expr.condition.instances(instances);
+                while_expr.block.instances(instances);
+            }
+            ExprKind::Match(match_expr) => {
+                match_expr.expr.instances(instances);
+                for (pattern, value) in &match_expr.branches {
+                    pattern.instances(instances);
+                    value.instances(instances);
+                }
+                if let Some(value) = &match_expr.else_value {
+                    value.instances(instances);
+                }
+            }
+            ExprKind::StructExpr(struct_expr) => {
+                struct_expr.fields.iter().for_each(|(_, value)| value.instances(instances))
+            }
+            ExprKind::FieldAccess(access) => access.expr.instances(instances),
+            ExprKind::Closure(closure) => closure.function.instances(instances),
+        }
+    }
+}
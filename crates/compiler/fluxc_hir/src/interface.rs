@@ -1,2 +1,93 @@
+use std::collections::HashMap;
+
+use fluxc_ast::{InterfaceDecl, InterfaceMethod, Primitive, TypeExpr};
+
+use crate::{Argument, Function, FunctionKind};
+
 /// Defines the HIR datatype for a Flux interface.
-pub struct Interface {}
+#[derive(Debug, Clone)]
+pub struct Interface {
+    /// The name of this interface.
+    pub name: String,
+    /// The methods declared by this interface. Methods without a default
+    /// implementation are `FunctionKind::Abstract`, and those with one are
+    /// `FunctionKind::Default`. The implementing type is referred to as
+    /// `Self`.
+    pub methods: Vec<Function>,
+}
+
+/// HIR datatype representing the implementation of an interface for a type.
+#[derive(Debug, Clone)]
+pub struct Impl {
+    /// The name of the implemented interface.
+    pub interface: String,
+    /// The name of the implementing type.
+    pub ty: String,
+    /// The methods of this implementation, including default methods
+    /// inherited from the interface.
+    pub methods: Vec<Function>,
+}
+
+/// Trait providing the `as_interface` method.
+pub trait AsInterface {
+    /// This method returns `self` as an `Interface` type.
+    fn as_interface(&self) -> Interface;
+}
+
+impl AsInterface for InterfaceDecl {
+    fn as_interface(&self) -> Interface {
+        Interface {
+            name: self.ident.value.clone(),
+            methods: self.methods.iter().map(|x| (&x.value).into()).collect(),
+        }
+    }
+}
+
+impl From<&InterfaceMethod> for Function {
+    fn from(method: &InterfaceMethod) -> Self {
+        let receiver = method.receiver.as_ref().map(|_| Argument {
+            name: "self".into(),
+            ty: TypeExpr::Primitive(Primitive::Ref("Self".into())),
+        });
+        Function {
+            name: method.ident.value.clone(),
+            generics: vec![],
+            args: receiver
+                .into_iter()
+                .chain(method.params.iter().map(|x| (&x.value).into()))
+                .collect(),
+            kind: match method.body {
+                Some(_) => FunctionKind::Default,
+                None => FunctionKind::Abstract,
+            },
+            return_type: method.ret_ty.clone_inner(),
+            body: None,
+        }
+    }
+}
+
+impl Function {
+    /// Return a copy of this function with every reference to `Self` in its
+    /// signature replaced by the given type. The body is copied unchanged.
+    pub fn with_self(&self, ty: &str) -> Function {
+        Function {
+            name: self.name.clone(),
+            generics: self.generics.clone(),
+            kind: self.kind.clone(),
+            args: self
+                .args
+                .iter()
+                .map(|arg| Argument { name: arg.name.clone(), ty: substitute_self(&arg.ty, ty) })
+                .collect(),
+            return_type: substitute_self(&self.return_type, ty),
+            body: self.body.clone(),
+        }
+    }
+}
+
+/// Replace every reference to `Self` in the given type expression with the
+/// given type.
+pub fn substitute_self(ty: &TypeExpr, name: &str) -> TypeExpr {
+    let this = TypeExpr::Primitive(Primitive::Ref(name.into()));
+    ty.substitute(&HashMap::from([("Self".to_string(), this)]))
+}
@@ -1,13 +1,21 @@
 //! Defines HIR types for Flux code.
 
 mod class;
+mod closure;
+mod expr;
 mod function;
+mod generic;
 mod interface;
 mod module;
+mod stmt;
+mod types;
 mod variable;
 
 pub use class::*;
+pub use expr::*;
 pub use function::*;
+pub use generic::*;
 pub use interface::*;
 pub use module::*;
+pub use stmt::*;
 pub use variable::*;
@@ -1,8 +1,23 @@
 use std::path::PathBuf;
 
+use crate::{Class, Function, Impl, Interface, Stmt, Struct};
+
 /// HIR datatype representing a Flux module and its exported symbols.
+#[derive(Debug, Default)]
 pub struct Module {
     pub path: PathBuf,
+    /// The structs declared in this module.
+    pub structs: Vec<Struct>,
+    /// The classes declared in this module.
+    pub classes: Vec<Class>,
+    /// The interfaces declared in this module.
+    pub interfaces: Vec<Interface>,
+    /// The interface implementations declared in this module.
+    pub impls: Vec<Impl>,
+    /// The functions declared in this module.
+    pub functions: Vec<Function>,
+    /// The top-level statements of this module, run when it is loaded.
+    pub stmts: Vec<Stmt>,
 }
 
 /// HIR datatype representing an `import` directive.
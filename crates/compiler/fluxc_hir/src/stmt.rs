@@ -0,0 +1,34 @@
+use fluxc_ast::TypeExpr;
+
+use crate::{Expr, Variable};
+
+/// HIR datatype representing a Flux statement.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Stmt {
+    /// A variable declaration.
+    Declaration(Declaration),
+    /// A function return statement.
+    Return(Expr),
+    /// A loop break statement.
+    Break(Expr),
+    /// An expression.
+    Expr(Expr),
+}
+
+/// A variable declaration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Declaration {
+    /// The declared variable.
+    pub variable: Variable,
+    /// The value of the variable.
+    pub value: Expr,
+}
+
+/// A block of statements.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Block {
+    /// The statements in this block.
+    pub stmts: Vec<Stmt>,
+    /// The type of the value this block produces.
+    pub ty: TypeExpr,
+}
@@ -0,0 +1,100 @@
+//! Defines the collection of the types of the values held by HIR nodes, so
+//! that their runtime representations can be laid out ahead of code
+//! generation.
+
+use fluxc_ast::TypeExpr;
+
+use crate::{Block, Expr, ExprKind, Function, Stmt};
+
+impl Function {
+    /// Collect the types of the parameters, variables and expressions in the
+    /// body of this function.
+    pub fn value_types(&self, types: &mut Vec<TypeExpr>) {
+        if let Some(body) = &self.body {
+            types.extend(body.params.iter().map(|param| param.ty.clone()));
+            body.block.value_types(types);
+        }
+    }
+}
+
+impl Block {
+    /// Collect the types of the variables and expressions in this block.
+    pub fn value_types(&self, types: &mut Vec<TypeExpr>) {
+        types.push(self.ty.clone());
+        self.stmts.iter().for_each(|stmt| stmt.value_types(types));
+    }
+}
+
+impl Stmt {
+    /// Collect the types of the variables and expressions in this statement.
+    pub fn value_types(&self, types: &mut Vec<TypeExpr>) {
+        match self {
+            Stmt::Declaration(decl) => {
+                types.push(decl.variable.ty.clone());
+                decl.value.value_types(types);
+            }
+            Stmt::Return(expr) | Stmt::Break(expr) | Stmt::Expr(expr) => expr.value_types(types),
+        }
+    }
+}
+
+impl Expr {
+    /// Collect the types of this expression and the expressions within it.
+    pub fn value_types(&self, types: &mut Vec<TypeExpr>) {
+        types.push(self.ty.clone());
+        match &self.kind {
+            ExprKind::Literal(_)
+            | ExprKind::Local(_)
+            | ExprKind::Function(_)
+            | ExprKind::Method(..) => (),
+            ExprKind::BinaryExpr(binary) => {
+                binary.lhs.value_types(types);
+                binary.rhs.value_types(types);
+            }
+            ExprKind::UnaryExpr(unary) => unary.expr.value_types(types),
+            ExprKind::Cast(cast) => cast.expr.value_types(types),
+            ExprKind::Block(block) => block.value_types(types),
+            ExprKind::Call(call) => {
+                call.callee.value_types(types);
+                call.args.iter().for_each(|arg| arg.value_types(types));
+            }
+            ExprKind::MethodCall(call) => {
+                call.receiver.value_types(types);
+                call.args.iter().for_each(|arg| arg.value_types(types));
+            }
+            ExprKind::Conditional(conditional) => {
+                for (condition, block) in &conditional.branches {
+                    condition.value_types(types);
+                    block.value_types(types);
+                }
+                if let Some(block) = &conditional.else_block {
+                    block.value_types(types);
+                }
+            }
+            ExprKind::Loop(loop_expr) => loop_expr.block.value_types(types),
+            ExprKind::While(while_expr) => {
+                while_expr.condition.value_types(types);
+                while_expr.block.value_types(types);
+            }
+            ExprKind::Match(match_expr) => {
+                match_expr.expr.value_types(types);
+                for (pattern, value) in &match_expr.branches {
+                    pattern.value_types(types);
+                    value.value_types(types);
+                }
+                if let Some(value) = &match_expr.else_value {
+                    value.value_types(types);
+                }
+            }
+            ExprKind::StructExpr(struct_expr) => {
+                struct_expr.fields.iter().for_each(|(_, value)| value.value_types(types))
+            }
+            ExprKind::FieldAccess(access) => access.expr.value_types(types),
+            // the body of a closure is laid out along with the function it is
+            // lifted to, but the environment holds the captured values
+            ExprKind::Closure(closure) => {
+                types.extend(closure.captures.iter().map(|capture| capture.ty.clone()))
+            }
+        }
+    }
+}
@@ -1,7 +1,15 @@
+use std::fmt::{self, Display};
+
 use fluxc_ast::{Mutability, TypeExpr};
 
-#[derive(Debug)]
+/// Type alias for the IDs of local variables. IDs are unique within a module,
+/// so shadowed variables can be told apart.
+pub type LocalId = usize;
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct Variable {
+    /// The ID of this variable.
+    pub id: LocalId,
     /// The name of this variable.
     pub name: String,
     /// The mutability of this variable.
@@ -9,3 +17,16 @@ pub struct Variable {
     /// The type of this variable.
     pub ty: TypeExpr,
 }
+
+/// Variables are displayed in the form they would be declared in, e.g.
+/// `let x: int`.
+impl Display for Variable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let keyword = match self.mutability {
+            Mutability::Mutable => "mut",
+            Mutability::Immutable => "let",
+            Mutability::Constant => "const",
+        };
+        write!(f, "{} {}: {}", keyword, self.name, self.ty)
+    }
+}
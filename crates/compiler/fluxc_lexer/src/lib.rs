@@ -8,10 +8,15 @@ use logos::Logos;
 /// A token lexed by the Flux lexer.
 #[derive(Logos, Debug, PartialEq, Eq, Clone, Hash)]
 pub enum Token {
-    #[regex(r"[ \t\n\f]+", logos::skip)]
+    #[regex(r"[ \t\f]+", logos::skip)]
     #[error]
     Error,
 
+    // one or more line breaks. calls end at the end of a line, so the parser
+    // uses these to tell calls apart from expressions on following lines
+    #[regex(r"\n[ \t\n\f]*")]
+    Newline,
+
     #[regex("[A-Za-z_][A-Za-z_0-9]*", |lex| lex.slice().to_string())]
     Ident(String),
 
@@ -100,6 +105,33 @@ pub enum Token {
     #[token("->")]
     TokenArrow,
 
+    #[token("=>")]
+    TokenFatArrow,
+
+    #[token(".")]
+    TokenDot,
+
+    #[token("<")]
+    TokenLt,
+
+    #[token(">")]
+    TokenGt,
+
+    #[token("<=")]
+    TokenLe,
+
+    #[token(">=")]
+    TokenGe,
+
+    #[token("?")]
+    TokenQuestion,
+
+    #[token("?.")]
+    TokenQuestionDot,
+
+    #[token("??")]
+    TokenCoalesce,
+
     // keywords
     #[token("let")]
     KeywordLet,
@@ -155,9 +187,30 @@ pub enum Token {
     #[token("match")]
     KeywordMatch,
 
+    #[token("struct")]
+    KeywordStruct,
+
+    #[token("class")]
+    KeywordClass,
+
+    #[token("interface")]
+    KeywordInterface,
+
+    #[token("impl")]
+    KeywordImpl,
+
+    #[token("extends")]
+    KeywordExtends,
+
+    #[token("keyof")]
+    KeywordKeyof,
+
+    #[token("infer")]
+    KeywordInfer,
+
     // literals - these only consume strings as the actual parsing should be handled by the parser
     // crate.
-    #[regex("-?[0-9]+", |lex| lex.slice().parse())]
+    #[regex("-?[0-9][0-9_]*", |lex| lex.slice().replace('_', "").parse())]
     LiteralInt(i64),
 
     // use raw float bytes since floats are not hashable
@@ -168,10 +221,22 @@ pub enum Token {
 	)]
     LiteralFloat([u8; 8]),
 
+    // numbers with a suffix naming their type, e.g. `255u8`, split into the
+    // number and the suffix
+    #[regex(
+		"-?[0-9][0-9_]*(\\.[0-9]+)?(i8|i16|i32|i64|u8|u16|u32|u64|usize|f32)",
+		|lex| {
+			let slice = lex.slice().replace('_', "");
+			let (number, suffix) = slice.split_at(slice.find(['i', 'u', 'f']).unwrap());
+			(number.to_string(), suffix.to_string())
+		}
+	)]
+    LiteralSized((String, String)),
+
     #[regex(r#""([^"\\]|\\t|\\u|\\n|\\")*""#, |lex| lex.slice().to_string())]
     LiteralStr(String),
 
-    #[regex(r#"'([^'\\]|\\t|\\u|\\n|\\')'"#, |lex| lex.slice().parse())]
+    #[regex(r#"'([^'\\]|\\t|\\u|\\n|\\')'"#, |lex| char_literal(lex.slice()))]
     LiteralChar(char),
 
     #[regex("(true)|(false)", |lex| lex.slice().parse(), priority = 2)]
@@ -188,6 +253,7 @@ impl Display for Token {
             "{}",
             match self {
                 Token::Error => "unexpected token",
+                Token::Newline => "newline",
                 Token::Ident(_) => "identifier",
                 Token::TokenAssign => "=",
                 Token::TokenEq => "==",
@@ -210,6 +276,15 @@ impl Display for Token {
                 Token::TokenComma => ",",
                 Token::TokenColon => ":",
                 Token::TokenArrow => "->",
+                Token::TokenFatArrow => "=>",
+                Token::TokenDot => ".",
+                Token::TokenLt => "<",
+                Token::TokenGt => ">",
+                Token::TokenLe => "<=",
+                Token::TokenGe => ">=",
+                Token::TokenQuestion => "?",
+                Token::TokenQuestionDot => "?.",
+                Token::TokenCoalesce => "??",
                 Token::KeywordLet => "let",
                 Token::KeywordMut => "mut",
                 Token::KeywordConst => "const",
@@ -227,8 +302,16 @@ impl Display for Token {
                 Token::KeywordExport => "export",
                 Token::KeywordExtern => "extern",
                 Token::KeywordMatch => "match",
+                Token::KeywordStruct => "struct",
+                Token::KeywordClass => "class",
+                Token::KeywordInterface => "interface",
+                Token::KeywordImpl => "impl",
+                Token::KeywordExtends => "extends",
+                Token::KeywordKeyof => "keyof",
+                Token::KeywordInfer => "infer",
                 Token::LiteralInt(_) => "integer",
                 Token::LiteralFloat(_) => "float",
+                Token::LiteralSized(_) => "sized number",
                 Token::LiteralStr(_) => "str",
                 Token::LiteralChar(_) => "char",
                 Token::LiteralBool(_) => "bool",
@@ -265,3 +348,14 @@ pub fn lex<S: AsRef<str> + Debug>(s: S) -> Result<TokenStream, TokenStream> {
     }
     Ok(tokens)
 }
+
+/// Parse the character a char literal such as `'a'` or `'\n'` stands for,
+/// stripping its quotes and resolving escapes.
+fn char_literal(slice: &str) -> Option<char> {
+    match &slice[1..slice.len() - 1] {
+        "\\t" => Some('\t'),
+        "\\n" => Some('\n'),
+        "\\'" => Some('\''),
+        inner => inner.parse().ok(),
+    }
+}
@@ -0,0 +1,17 @@
+use fluxc_lexer::{lex, Token};
+use pretty_assertions::assert_eq;
+
+#[test]
+fn test_lex_char_literals() {
+    let src = include_str!("./char-literals.flx");
+    let chars: Vec<_> = lex(src)
+        .unwrap()
+        .into_iter()
+        .filter_map(|(token, span)| match token {
+            Token::LiteralChar(c) => Some((c, span)),
+            _ => None,
+        })
+        .collect();
+    // quotes are stripped and escapes resolved
+    assert_eq!(vec![('a', 8..11), ('\t', 20..24), ('\'', 33..37)], chars);
+}
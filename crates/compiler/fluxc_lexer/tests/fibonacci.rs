@@ -13,24 +13,32 @@ fn test_lex_fibonacci() {
             (Token::Ident("y".to_string()), 7..8),
             (Token::TokenAssign, 9..10),
             (Token::LiteralInt(1), 11..12),
+            (Token::Newline, 12..13),
             (Token::KeywordLet, 13..16),
             (Token::Ident("z".to_string()), 17..18),
             (Token::TokenAssign, 19..20),
             (Token::LiteralInt(0), 21..22),
+            (Token::Newline, 22..23),
             (Token::KeywordLoop, 23..27),
             (Token::TokenBraceLeft, 28..29),
+            (Token::Newline, 29..34),
             (Token::Ident("z".to_string()), 34..35),
             (Token::TokenAssign, 36..37),
             (Token::Ident("x".to_string()), 38..39),
+            (Token::Newline, 39..44),
             (Token::Ident("x".to_string()), 44..45),
             (Token::TokenPlusEq, 46..48),
             (Token::Ident("y".to_string()), 49..50),
+            (Token::Newline, 50..55),
             (Token::Ident("y".to_string()), 55..56),
             (Token::TokenAssign, 57..58),
             (Token::Ident("z".to_string()), 59..60),
+            (Token::Newline, 60..65),
             (Token::Ident("print".to_string()), 65..70),
             (Token::Ident("z".to_string()), 71..72),
-            (Token::TokenBraceRight, 73..74)
+            (Token::Newline, 72..73),
+            (Token::TokenBraceRight, 73..74),
+            (Token::Newline, 74..75),
         ],
         tokens
     )
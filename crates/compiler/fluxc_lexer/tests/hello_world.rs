@@ -8,7 +8,8 @@ fn test_lex_hello_world() {
     assert_eq!(
         vec![
             (Token::Ident("print".to_string()), 0..5),
-            (Token::LiteralStr("\"hello, world!\"".to_string()), 6..21)
+            (Token::LiteralStr("\"hello, world!\"".to_string()), 6..21),
+            (Token::Newline, 21..22),
         ],
         tokens
     )
@@ -0,0 +1,23 @@
+use fluxc_lexer::{lex, Token};
+use pretty_assertions::assert_eq;
+
+#[test]
+fn test_lex_sized_literals() {
+    let src = include_str!("./sized-literals.flx");
+    let tokens = lex(src).unwrap();
+    let sized = |number: &str, suffix: &str| Token::LiteralSized((number.into(), suffix.into()));
+    assert_eq!(
+        vec![
+            (Token::KeywordLet, 0..3),
+            (Token::Ident("mask".to_string()), 4..8),
+            (Token::TokenAssign, 9..10),
+            (sized("255", "u8"), 11..16),
+            (Token::TokenPlus, 17..18),
+            (sized("1000", "i64"), 19..27),
+            (Token::TokenMinus, 28..29),
+            (sized("1.5", "f32"), 30..36),
+            (Token::Newline, 36..37),
+        ],
+        tokens
+    )
+}
@@ -1,6 +1,10 @@
-use std::{error::Error, fs::File, io::Read, path::Path, time::Instant};
+use std::{error::Error, fmt::Write, fs::File, io::Read, mem, path::Path, rc::Rc, time::Instant};
 
-use log::{debug, info};
+use fluxc_codegen::ModuleContext;
+use fluxc_hir::Stmt;
+use fluxc_span::SpanContext;
+use fluxc_walker::Walker;
+use log::{debug, info, warn};
 
 /// Enum of possible compiler modes.
 pub enum Mode<'i> {
@@ -10,35 +14,39 @@ pub enum Mode<'i> {
     AOT(&'i Path),
 }
 
-/// Compile the target input string into memory.
-pub fn compile_to_mem(input: String) -> Result<fn() -> u32, Box<dyn Error>> {
-    // 1. Parse input source
-    let tokens = fluxc_lexer::lex(input).unwrap();
-    let ast = fluxc_parser::parse(tokens).unwrap();
-    // // 2. Run AST validation on the AST
-    // fluxc_ast_passes::perform_ast_passes(&mut ast)?;
-    // // 3. Generate IR
-    // let (pointer, _) = fluxc_codegen::IrTranslator::default().build(ast)?;
-    // let code_fn;
-    // unsafe {
-    //     code_fn = mem::transmute::<_, fn() -> u32>(pointer);
-    // }
-    println!("{:#?}", ast);
-    todo!()
+/// Compile the target input string into memory, returning its `main`
+/// function. Warnings raised while analysing the input are logged.
+pub fn compile_to_mem<P: AsRef<Path>>(
+    input: String,
+    path: P,
+) -> Result<extern "C" fn() -> i64, Box<dyn Error>> {
+    let tokens = fluxc_lexer::lex(&input).map_err(|_| "failed to lex input")?;
+    let ast = fluxc_parser::parse(tokens).map_err(|_| "failed to parse input")?;
+    let context = Rc::new(SpanContext { source: input, path: path.as_ref().into() });
+    let mut walker = Walker::new(context);
+    let module = walker.walk(&ast).map_err(|e| e.kind.to_string())?;
+    for warning in walker.warnings() {
+        warn!("{}", warning.kind);
+    }
+
+    let module_ctx = ModuleContext::for_module();
+    let mut module_ctx = module_ctx.write().map_err(|_| "module context poisoned")?;
+    module_ctx.define_module(&module)?;
+    module_ctx.finalize()?;
+    let main = module_ctx.function_ptr("main").ok_or("no `main` function was defined")?;
+    // SAFETY: `main` takes no arguments and returns an `int`, and the code of
+    // JIT-compiled functions is never freed
+    Ok(unsafe { mem::transmute::<*const u8, extern "C" fn() -> i64>(main) })
 }
 
 /// Compile the target input string into memory and execute it immediately.
-fn compile_and_execute(input: String) -> Result<(), Box<dyn Error>> {
+fn compile_and_execute<P: AsRef<Path>>(input: String, path: P) -> Result<(), Box<dyn Error>> {
     let now = Instant::now();
-    match compile_to_mem(input) {
-        Ok(mem) => {
-            info!("Compiled in {}ms", now.elapsed().as_millis());
-            let res = mem();
-            info!("program output was {}", res);
-            Ok(())
-        }
-        Err(e) => Err(e),
-    }
+    let main = compile_to_mem(input, path)?;
+    info!("Compiled in {}ms", now.elapsed().as_millis());
+    let res = main();
+    info!("program output was {}", res);
+    Ok(())
 }
 
 /// Compile the target input string into an executable binary.
@@ -46,10 +54,8 @@ pub fn compile_to_binary<P: AsRef<Path>>(_: String, _: P) -> Result<(), Box<dyn
     todo!("unsupported compiler mode");
 }
 
-/// Compile the target file using the given compiler mode.
-pub fn compile<P: AsRef<Path>>(target: P, mode: Mode) -> Result<(), Box<dyn Error>> {
-    debug!("Compiling {:?}", target.as_ref());
-
+/// Read the source of the target file.
+fn read_source<P: AsRef<Path>>(target: P) -> Result<String, Box<dyn Error>> {
     let mut file = match File::open(target) {
         Ok(f) => f,
         Err(e) => return Err(e.into()),
@@ -57,12 +63,48 @@ pub fn compile<P: AsRef<Path>>(target: P, mode: Mode) -> Result<(), Box<dyn Erro
 
     let mut buf = String::new();
     match file.read_to_string(&mut buf) {
-        Ok(_) => (),
-        Err(e) => return Err(e.into()),
-    };
+        Ok(_) => Ok(buf),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Analyse the target input string, returning the types of its top-level
+/// variables and functions, one per line.
+pub fn analyse_types<P: AsRef<Path>>(input: String, path: P) -> Result<String, Box<dyn Error>> {
+    let tokens = fluxc_lexer::lex(&input).map_err(|_| "failed to lex input")?;
+    let ast = fluxc_parser::parse(tokens).map_err(|_| "failed to parse input")?;
+    let context = Rc::new(SpanContext { source: input, path: path.as_ref().into() });
+    let module = Walker::new(context).walk(&ast).map_err(|e| e.kind.to_string())?;
+
+    let mut out = String::new();
+    for stmt in &module.stmts {
+        if let Stmt::Declaration(decl) = stmt {
+            writeln!(out, "{}", decl.variable)?;
+        }
+    }
+    for function in &module.functions {
+        writeln!(out, "{}", function)?;
+    }
+    Ok(out)
+}
+
+/// Print the types of the top-level variables and functions of the target
+/// file.
+pub fn emit_types<P: AsRef<Path>>(target: P) -> Result<(), Box<dyn Error>> {
+    debug!("Analysing {:?}", target.as_ref());
+    let buf = read_source(&target)?;
+    print!("{}", analyse_types(buf, target)?);
+    Ok(())
+}
+
+/// Compile the target file using the given compiler mode.
+pub fn compile<P: AsRef<Path>>(target: P, mode: Mode) -> Result<(), Box<dyn Error>> {
+    debug!("Compiling {:?}", target.as_ref());
+
+    let buf = read_source(&target)?;
 
     match mode {
         Mode::AOT(dest) => compile_to_binary(buf, dest),
-        Mode::JIT => compile_and_execute(buf),
+        Mode::JIT => compile_and_execute(buf, target),
     }
 }
@@ -0,0 +1,25 @@
+use fluxc_main::compile_to_mem;
+
+#[test]
+fn test_compile_to_mem() {
+    let src = r#"
+add a: int, b: int -> int {
+    return a + b
+}
+main -> int {
+    mut x = 0
+    x = add x, 42
+    return x
+}
+"#;
+    let main = compile_to_mem(src.into(), "test.flx").unwrap();
+    assert_eq!(main(), 42);
+}
+
+#[test]
+fn test_compile_to_mem_errors() {
+    let err = compile_to_mem("let x = y".into(), "test.flx").unwrap_err();
+    assert_eq!(err.to_string(), "E0205 - cannot find value `y` in this scope");
+    let err = compile_to_mem("let x = 1".into(), "test.flx").unwrap_err();
+    assert_eq!(err.to_string(), "no `main` function was defined");
+}
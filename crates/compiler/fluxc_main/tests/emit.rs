@@ -0,0 +1,22 @@
+use fluxc_main::analyse_types;
+
+#[test]
+fn test_emit_types() {
+    let src = r#"
+const C = 1 + 2
+let name: string | () = "flux"
+add a: int, b: int -> int {
+    return a + b
+}
+let x = add C, 4
+"#;
+    let out = analyse_types(src.into(), "test.flx").unwrap();
+    // variables are listed before functions
+    assert_eq!(out, "const C: 3\nlet name: unit | string\nlet x: int\nadd a: int, b: int -> int\n");
+}
+
+#[test]
+fn test_emit_types_errors() {
+    let err = analyse_types("let x = y".into(), "test.flx").unwrap_err();
+    assert_eq!(err.to_string(), "E0205 - cannot find value `y` in this scope");
+}
@@ -1,87 +1,304 @@
 //! The Flux parser, written using the `chumsky` library.
 
+use std::{ops::Range, rc::Rc};
+
 use chumsky::{prelude::*, Stream};
 use fluxc_ast::{
-    BinaryExpr, BinaryOp, Block, Conditional, Declaration, Expr, FuncCall, FuncDecl, FuncParam,
-    IfStmt, Intersection, Literal, Loop, Mutability, Node, Operation, Primitive, Stmt,
-    TypeDeclaration, TypeExpr, Union, AST,
+    BinaryExpr, BinaryOp, Block, Cast, ClassDecl, Closure, Conditional, ConditionalType,
+    Declaration, Expr, FieldAccess, FuncCall, FuncDecl, FuncParam, GenericParam, IfStmt, ImplDecl,
+    Import, InterfaceDecl, InterfaceMethod, Intersection, Literal, Loop, Match, MatchBranch,
+    MethodCall, MethodDecl, ModuleSymbol, Mutability, Node, Numeric, Operation, Primitive, Stmt,
+    StructDecl, StructExpr, StructExprField, StructField, TypeDeclaration, TypeExpr, UnaryExpr,
+    UnaryOp, Union, While, AST,
 };
 use fluxc_lexer::{Token, TokenStream};
 
-fn parser() -> impl Parser<Token, AST, Error = Simple<Token>> {
-    // ident parser
+/// A member of a class declaration.
+#[derive(Clone)]
+enum ClassMember {
+    Field(Node<StructField>),
+    Method(Node<MethodDecl>),
+}
+
+/// Strip the quotes surrounding a lexed string literal.
+fn unquote(str: &str) -> String {
+    str[1..str.len() - 1].to_string()
+}
+
+/// Desugar a number with a suffix naming its type, such as `255u8`, into a
+/// cast of the number to that type. The number must be within the range of
+/// the type. Unsigned values above `i64::MAX` do not fit the integer literals
+/// of the AST, so are written as the `i64` with the same bits, reinterpreted as
+/// the type.
+fn sized_literal(
+    (number, suffix): (String, String),
+    span: Range<usize>,
+) -> Result<Node<Expr>, Simple<Token>> {
+    let numeric = Numeric::from_name(&suffix).expect("lexed an unknown suffix");
+    let cast = |expr: Node<Expr>, numeric: Numeric| {
+        let ty = Node::new(TypeExpr::Primitive(numeric.into()), span.clone());
+        Node::new(
+            Expr::Cast(Node::new(Cast { expr: Box::new(expr), ty }, span.clone())),
+            span.clone(),
+        )
+    };
+    let literal =
+        |literal| Node::new(Expr::Literal(Node::new(literal, span.clone())), span.clone());
+    let Some((min, max)) = numeric.range() else {
+        let float = number.parse::<f64>().expect("lexed an invalid number");
+        return Ok(cast(literal(Literal::Float(float)), numeric));
+    };
+    if number.contains('.') {
+        return Err(Simple::custom(
+            span,
+            format!("float literal `{number}` has integer suffix `{suffix}`"),
+        ));
+    }
+    let int =
+        number.parse::<i128>().ok().filter(|int| (min..=max).contains(int)).ok_or_else(|| {
+            Simple::custom(
+                span.clone(),
+                format!("literal `{number}` is out of range for `{suffix}`"),
+            )
+        })?;
+    Ok(match i64::try_from(int) {
+        Ok(int) => cast(literal(Literal::Int(int)), numeric),
+        Err(_) => cast(cast(literal(Literal::Int(int as i64)), Numeric::I64), numeric),
+    })
+}
+
+/// Build the parser of a type expression, whose nested types are parsed by
+/// `ty_expr`. Unless `functions` is set, function types must be parenthesised,
+/// so that a function type given as the type of a parameter does not take the
+/// return type of the function declaring it.
+fn type_expr_with<'a, P>(
+    ty_expr: P,
+    functions: bool,
+) -> impl Parser<Token, TypeExpr, Error = Simple<Token>> + Clone + 'a
+where
+    P: Parser<Token, TypeExpr, Error = Simple<Token>> + Clone + 'a,
+{
     let raw_ident = select! {
         Token::Ident(ident) => ident
     };
-    let ident = raw_ident.map_with_span(Node::new);
 
-    // literals
-    let literal = select! {
-       Token::LiteralInt(int) => Literal::Int(int),
-       Token::LiteralFloat(float) => Literal::Float(f64::from_be_bytes(float)),
-       Token::LiteralStr(str) => Literal::String(str),
-       Token::LiteralChar(c) => Literal::Char(c),
-       Token::LiteralBool(bool) => Literal::Bool(bool),
-       Token::LiteralUnit => Literal::Unit
+    let type_literal = select! {
+        Token::LiteralInt(int) => Primitive::IntLiteral(int),
+        Token::LiteralFloat(float) => Primitive::FloatLiteral(f64::from_be_bytes(float)),
+        Token::LiteralStr(str) => Primitive::StringLiteral(unquote(&str)),
+        Token::LiteralChar(c) => Primitive::CharLiteral(c),
+        Token::LiteralBool(bool) => match bool {
+            true => Primitive::True,
+            false => Primitive::False
+        },
+        Token::Ident(ident) => match ident.as_str() {
+            "int" => Primitive::Int,
+            "float" => Primitive::Float,
+            "string" => Primitive::String,
+            "char" => Primitive::Char,
+            "bool" => Primitive::Bool,
+            "unit" => Primitive::Unit,
+            "any" => Primitive::Any,
+            "never" => Primitive::Never,
+            _ => match Numeric::from_name(&ident) {
+                Some(numeric) => Primitive::Numeric(numeric),
+                None => Primitive::Ref(ident),
+            },
+        },
+        Token::LiteralUnit => Primitive::Unit
     }
-    .map_with_span(Node::new)
-    .labelled("literal");
+    .map(TypeExpr::Primitive)
+    .labelled("primitive type");
+
+    // a parenthesised type, or a tuple type if it holds more than one type
+    // or a trailing comma
+    let parenthesised = ty_expr
+        .clone()
+        .separated_by(just(Token::TokenComma))
+        .at_least(1)
+        .then(just(Token::TokenComma).or_not())
+        .delimited_by(just(Token::TokenParenthesisLeft), just(Token::TokenParenthesisRight))
+        .map(|(mut types, trailing)| match (types.len(), trailing) {
+            (1, None) => types.remove(0),
+            _ => TypeExpr::Primitive(Primitive::Tuple(types)),
+        });
 
-    let type_expr = recursive::<_, TypeExpr, _, _, Simple<Token>>(|ty_expr| {
-        let type_literal = select! {
-            Token::LiteralInt(int) => Primitive::IntLiteral(int),
-            Token::LiteralFloat(float) => Primitive::FloatLiteral(f64::from_be_bytes(float)),
-            Token::LiteralStr(str) => Primitive::StringLiteral(str),
-            Token::LiteralChar(c) => Primitive::CharLiteral(c),
-            Token::LiteralBool(bool) => match bool {
-                true => Primitive::True,
-                false => Primitive::False
-            },
-            Token::Ident(ident) => Primitive::Ref(ident),
-            Token::LiteralUnit => Primitive::Unit
-        }
-        .map(TypeExpr::Primitive)
-        .labelled("primitive type");
+    // a generic type applied to its type arguments
+    let generic = raw_ident
+        .then(
+            ty_expr
+                .clone()
+                .separated_by(just(Token::TokenComma))
+                .at_least(1)
+                .delimited_by(just(Token::TokenLt), just(Token::TokenGt)),
+        )
+        .map(|(ident, args)| TypeExpr::Generic(ident, args));
+
+    // a type captured by the `extends` clause of a conditional type
+    let capture = just(Token::KeywordInfer).ignore_then(raw_ident).map(TypeExpr::Capture);
+
+    // a function type, whose parameter types are always parenthesised
+    let function = ty_expr
+        .clone()
+        .separated_by(just(Token::TokenComma))
+        .allow_trailing()
+        .delimited_by(just(Token::TokenParenthesisLeft), just(Token::TokenParenthesisRight))
+        .or(just(Token::LiteralUnit).to(vec![]))
+        .then_ignore(just(Token::TokenArrow))
+        .then(ty_expr.clone())
+        .map(|(params, ret)| TypeExpr::Function(params, Box::new(ret)));
+
+    let atom = generic.or(capture).or(type_literal).or(parenthesised);
+    let atom = match functions {
+        true => function.or(atom).boxed(),
+        false => atom.boxed(),
+    };
 
-        let atom = type_literal.or(ty_expr
-            .delimited_by(just(Token::TokenParenthesisLeft), just(Token::TokenParenthesisRight)));
+    // optional types, which are shorthand for a union of the type with unit
+    let optional = atom
+        .clone()
+        .then(just(Token::TokenQuestion).or(just(Token::TokenCoalesce)).repeated())
+        .map(|(ty, optional)| match optional.is_empty() {
+            true => ty,
+            false => TypeExpr::Operation(Operation::Union(Union {
+                lhs: Box::new(ty),
+                rhs: Box::new(TypeExpr::Primitive(Primitive::Unit)),
+            })),
+        });
 
-        let intersection = atom
-            .clone()
-            .then(just(Token::TokenAnd).then(atom.clone()).repeated())
-            .foldl(|lhs, (_, rhs)| {
+    let operators = |atom: BoxedParser<'a, Token, TypeExpr, Simple<Token>>| {
+        let keyof = just(Token::KeywordKeyof)
+            .repeated()
+            .then(atom)
+            .foldr(|_, ty| TypeExpr::Operation(Operation::KeyOf(Box::new(ty))));
+
+        let intersection = keyof.clone().then(just(Token::TokenAnd).then(keyof).repeated()).foldl(
+            |lhs, (_, rhs)| {
                 TypeExpr::Operation(Operation::Intersection(Intersection {
                     lhs: Box::new(lhs),
                     rhs: Box::new(rhs),
                 }))
-            });
+            },
+        );
 
-        let union = intersection
-            .clone()
-            .then(just(Token::TokenOr).then(intersection.clone()).repeated())
-            .foldl(|lhs, (_, rhs)| {
+        intersection.clone().then(just(Token::TokenOr).then(intersection).repeated()).foldl(
+            |lhs, (_, rhs)| {
                 TypeExpr::Operation(Operation::Union(Union {
                     lhs: Box::new(lhs),
                     rhs: Box::new(rhs),
                 }))
-            });
+            },
+        )
+    };
 
-        let op = union;
+    // the `?` following the `extends` type of a conditional type begins its
+    // branches, so that type cannot be made optional without parentheses
+    let union = operators(optional.boxed());
+    let extends = operators(atom);
+
+    // conditional types bind loosest, and fall through to a single atom
+    union
+        .clone()
+        .then(
+            just(Token::KeywordExtends)
+                .ignore_then(extends)
+                .then_ignore(just(Token::TokenQuestion))
+                .then(ty_expr.clone())
+                .then_ignore(just(Token::TokenColon))
+                .then(ty_expr)
+                .or_not(),
+        )
+        .map(|(check, conditional)| match conditional {
+            Some(((extends, then), otherwise)) => TypeExpr::Operation(Operation::Conditional(
+                ConditionalType::of(check, extends, then, otherwise),
+            )),
+            None => check,
+        })
+}
 
-        type_literal.or(op)
-    })
-    .labelled("type expression");
+/// Build the parser of a module, given the offsets of the line breaks in its
+/// source.
+fn parser(breaks: Rc<[usize]>) -> impl Parser<Token, AST, Error = Simple<Token>> {
+    // whether no line break falls between the given offsets
+    let same_line = move |from: usize, to: usize| !breaks.iter().any(|&at| from <= at && at < to);
+
+    // ident parser
+    let raw_ident = select! {
+        Token::Ident(ident) => ident
+    };
+    let ident = raw_ident.map_with_span(Node::new);
+
+    // literals
+    let literal = select! {
+       Token::LiteralInt(int) => Literal::Int(int),
+       Token::LiteralFloat(float) => Literal::Float(f64::from_be_bytes(float)),
+       Token::LiteralStr(str) => Literal::String(unquote(&str)),
+       Token::LiteralChar(c) => Literal::Char(c),
+       Token::LiteralBool(bool) => Literal::Bool(bool),
+       Token::LiteralUnit => Literal::Unit
+    }
+    .map_with_span(Node::new)
+    .labelled("literal");
+
+    let type_expr =
+        recursive::<_, TypeExpr, _, _, Simple<Token>>(|ty_expr| type_expr_with(ty_expr, true))
+            .labelled("type expression");
+
+    // the type parameters of a generic declaration, which default to none
+    let generic_params = ident
+        .then(
+            just(Token::KeywordExtends)
+                .ignore_then(type_expr.clone().map_with_span(Node::new))
+                .or_not(),
+        )
+        .map(|(ident, bound)| GenericParam { ident, bound })
+        .map_with_span(Node::new)
+        .separated_by(just(Token::TokenComma))
+        .at_least(1)
+        .delimited_by(just(Token::TokenLt), just(Token::TokenGt))
+        .or_not()
+        .map(Option::unwrap_or_default)
+        .labelled("type parameters");
 
     // recursive stmt declaration
     let stmt = recursive::<_, Node<Stmt>, _, _, _>(|stmt| {
         let type_decl = just(Token::KeywordType)
             .ignore_then(ident)
+            .then(generic_params.clone())
             .then_ignore(just(Token::TokenAssign))
             .then(type_expr.clone().map_with_span(Node::new))
-            .map(|(ident, value)| TypeDeclaration { ident, value })
+            .map(|((ident, generics), value)| TypeDeclaration { ident, generics, value })
             .map_with_span(Node::new)
             .labelled("type declaration");
 
+        let struct_field = ident
+            .then_ignore(just(Token::TokenColon))
+            .then(type_expr.clone().map_with_span(Node::new))
+            .map(|(ident, ty)| StructField { ident, ty })
+            .map_with_span(Node::new)
+            .labelled("struct field");
+
+        let struct_decl = just(Token::KeywordExtern)
+            .or_not()
+            .then_ignore(just(Token::KeywordStruct))
+            .then(ident)
+            .then(generic_params.clone())
+            .then(
+                struct_field
+                    .clone()
+                    .separated_by(just(Token::TokenComma))
+                    .allow_trailing()
+                    .delimited_by(just(Token::TokenBraceLeft), just(Token::TokenBraceRight)),
+            )
+            .map(|(((external, ident), generics), fields)| StructDecl {
+                ident,
+                generics,
+                fields,
+                external: external.is_some(),
+            })
+            .map_with_span(Node::new)
+            .labelled("struct declaration");
+
         let block = stmt
             .repeated()
             .delimited_by(just(Token::TokenBraceLeft), just(Token::TokenBraceRight))
@@ -89,17 +306,48 @@ fn parser() -> impl Parser<Token, AST, Error = Simple<Token>> {
             .map_with_span(Node::new)
             .labelled("block");
 
+        // function and closure parameters, where parameters without a type are
+        // inferred, and parameters are immutable unless their type is prefixed with
+        // `mut`. function types must be parenthesised in the type of the last
+        // parameter of a function, as they would otherwise take its return type
+        let param_ty = type_expr
+            .clone()
+            .then_ignore(just(Token::TokenComma).or(just(Token::TokenParenthesisRight)).rewind())
+            .or(type_expr_with(type_expr.clone(), false));
+
+        let func_decl_param = ident
+            .then(
+                just(Token::TokenColon)
+                    .ignore_then(just(Token::KeywordMut).or_not())
+                    .then(param_ty.map_with_span(Node::new))
+                    .or_not(),
+            )
+            .map(|(ident, ty)| {
+                let (mutability, ty) = match ty {
+                    Some((Some(_), ty)) => (Mutability::Mutable, ty),
+                    Some((None, ty)) => (Mutability::Immutable, ty),
+                    None => (Mutability::Immutable, Node::new(TypeExpr::Infer, ident.span.clone())),
+                };
+                FuncParam { ident, ty, mutability }
+            })
+            .labelled("parameter");
+
         let expr = recursive::<_, Node<Expr>, _, _, _>(|expr| {
+            // the conditions of conditionals and loops, and the values matched
+            let mut condition = Recursive::declare();
+
             // conditionals
             let if_stmt = just(Token::KeywordIf)
-                .ignore_then(expr.clone())
+                .ignore_then(condition.clone())
                 .then(block.clone())
                 .map(|(condition, block)| IfStmt { block, condition: Box::new(condition) })
                 .map_with_span(Node::new)
                 .labelled("if statement");
 
-            let else_if_stmt = just(Token::KeywordElse).ignore_then(if_stmt.clone()).labelled("else-if statement");
-            let else_stmt = just(Token::KeywordElse).ignore_then(block.clone()).labelled("else statement");
+            let else_if_stmt =
+                just(Token::KeywordElse).ignore_then(if_stmt.clone()).labelled("else-if statement");
+            let else_stmt =
+                just(Token::KeywordElse).ignore_then(block.clone()).labelled("else statement");
 
             let conditional = if_stmt
                 .then(else_if_stmt.repeated())
@@ -118,81 +366,439 @@ fn parser() -> impl Parser<Token, AST, Error = Simple<Token>> {
                 .map_with_span(Node::new)
                 .labelled("loop");
 
-            // binary expr
-            let atom =
-                ident.map(Expr::Ident).or(literal.map(Expr::Literal)).map_with_span(Node::new).or(
-                    expr.clone().delimited_by(
+            let while_expr = just(Token::KeywordWhile)
+                .ignore_then(condition.clone())
+                .then(block.clone())
+                .map(|(condition, block)| While { condition: Box::new(condition), block })
+                .map_with_span(Node::new)
+                .labelled("while loop");
+
+            let match_branch = expr
+                .clone()
+                .then_ignore(just(Token::TokenFatArrow))
+                .then(expr.clone())
+                .map(|(pattern, value)| MatchBranch { pattern, value })
+                .map_with_span(Node::new)
+                .labelled("match branch");
+
+            // a trailing `else` branch matches any value
+            let else_branch = just(Token::KeywordElse)
+                .ignore_then(just(Token::TokenFatArrow))
+                .ignore_then(expr.clone())
+                .then_ignore(just(Token::TokenComma).or_not());
+
+            let match_expr = just(Token::KeywordMatch)
+                .ignore_then(condition.clone())
+                .then(
+                    match_branch
+                        .separated_by(just(Token::TokenComma))
+                        .allow_trailing()
+                        .then(else_branch.or_not())
+                        .delimited_by(just(Token::TokenBraceLeft), just(Token::TokenBraceRight)),
+                )
+                .map(|(expr, (branches, else_value))| Match {
+                    expr: Box::new(expr),
+                    branches,
+                    else_value: else_value.map(Box::new),
+                })
+                .map_with_span(Node::new)
+                .labelled("match");
+
+            // struct literals, whose fields are checked against the declaration of
+            // the struct once it is resolved
+            let struct_expr_field = ident
+                .then_ignore(just(Token::TokenColon))
+                .then(expr.clone())
+                .map(|(ident, value)| StructExprField { ident, value })
+                .map_with_span(Node::new)
+                .labelled("struct field initializer");
+
+            let struct_expr = ident
+                .then(
+                    struct_expr_field
+                        .separated_by(just(Token::TokenComma))
+                        .allow_trailing()
+                        .delimited_by(just(Token::TokenBraceLeft), just(Token::TokenBraceRight)),
+                )
+                .map(|(ident, fields)| StructExpr { ident, fields })
+                .map_with_span(Node::new)
+                .labelled("struct literal");
+
+            // anonymous functions, whose return type is inferred if omitted
+            let closure = func_decl_param
+                .clone()
+                .map_with_span(Node::new)
+                .separated_by(just(Token::TokenComma))
+                .delimited_by(just(Token::TokenParenthesisLeft), just(Token::TokenParenthesisRight))
+                .or(just(Token::LiteralUnit).to(vec![]))
+                .then_ignore(just(Token::TokenArrow))
+                .then(type_expr.clone().map_with_span(Node::new).or_not())
+                .then(block.clone())
+                .map(|((params, ret_ty), body)| {
+                    let ret_ty = ret_ty.unwrap_or_else(|| {
+                        Node::new(TypeExpr::Infer, body.span.start..body.span.start)
+                    });
+                    Closure { params, ret_ty, body }
+                })
+                .map_with_span(Node::new)
+                .labelled("closure");
+
+            // expressions controlling the flow of execution
+            let control = choice((
+                block.clone().map(Expr::Block),
+                loop_expr.map(Expr::Loop),
+                while_expr.map(Expr::While),
+                match_expr.map(Expr::Match),
+                conditional.map(Expr::Conditional),
+            ));
+
+            // operations on atoms, from field access to assignment. empty struct
+            // literals are only operands outside of conditions, so that `if x {}`
+            // is not mistaken for one
+            let operations = |structs: bool| {
+                let same_line = same_line.clone();
+                let atom = closure
+                    .clone()
+                    .map(Expr::Closure)
+                    .or(struct_expr.clone().try_map(move |struct_expr, span| {
+                        match structs || !struct_expr.value.fields.is_empty() {
+                            true => Ok(Expr::StructExpr(struct_expr)),
+                            false => Err(Simple::custom(
+                                span,
+                                "empty struct literals must be parenthesised in conditions",
+                            )),
+                        }
+                    }))
+                    .or(ident.map(Expr::Ident))
+                    .or(literal.map(Expr::Literal))
+                    .map_with_span(Node::new)
+                    .or(select! { Token::LiteralSized(number) => number }.try_map(sized_literal))
+                    .or(expr.clone().delimited_by(
                         just(Token::TokenParenthesisLeft),
                         just(Token::TokenParenthesisRight),
-                    ),
+                    ));
+
+                // field access, which evaluates to unit when optional and the
+                // accessed value is unit
+                let access =
+                    just(Token::TokenDot).to(false).or(just(Token::TokenQuestionDot).to(true));
+                let atom = atom
+                    .then(access.then(ident).repeated())
+                    .foldl(|expr, (optional, field)| {
+                        let span = expr.span.start..field.span.end;
+                        Node::new(
+                            Expr::FieldAccess(Node::new(
+                                FieldAccess { expr: Box::new(expr), field, optional },
+                                span.clone(),
+                            )),
+                            span,
+                        )
+                    })
+                    .labelled("field access");
+
+                // the arguments of a call, where `()` calls a function without any. the
+                // arguments must begin on the line the callee ends on, so that an
+                // expression is not called with the start of the next statement
+                let args = just(Token::LiteralUnit)
+                    .to(vec![])
+                    .or(atom.clone().separated_by(just(Token::TokenComma)).at_least(1))
+                    .map_with_span(|args, span: Range<usize>| (args, span.start));
+                let callee_and_args =
+                    atom.clone().then(args).try_map(move |(callee, (args, start)), span| {
+                        match same_line(callee.span.end, start) {
+                            true => Ok((callee, args)),
+                            false => {
+                                Err(Simple::custom(span, "expected arguments on the same line"))
+                            }
+                        }
+                    });
+
+                // variables, fields and the results of calls may be called, such as a
+                // variable holding a closure, or a parenthesised call returning one
+                let func_call = callee_and_args
+                    .clone()
+                    .try_map(|(callee, args), span| match callee.value {
+                        Expr::Ident(_)
+                        | Expr::FieldAccess(_)
+                        | Expr::FuncCall(_)
+                        | Expr::MethodCall(_)
+                        | Expr::Closure(_) => Ok((callee, args)),
+                        _ => Err(Simple::custom(span, "expected a function")),
+                    })
+                    .map(|(callee, args)| FuncCall { callee: Box::new(callee), args })
+                    .map_with_span(Node::new)
+                    .labelled("function call");
+
+                // method calls are non-optional field accesses followed by arguments
+                let method_call = callee_and_args
+                    .try_map(|(callee, args), span| match callee.value {
+                        Expr::FieldAccess(access) if !access.value.optional => Ok(MethodCall {
+                            receiver: access.value.expr,
+                            method: access.value.field,
+                            args,
+                        }),
+                        _ => Err(Simple::custom(span, "expected a method")),
+                    })
+                    .map_with_span(Node::new)
+                    .labelled("method call");
+
+                // calls bind tighter than any operator, so are operands of unary,
+                // cast and binary operations, and errors are propagated from the
+                // result of a call rather than from its last argument
+                let call = method_call
+                    .map(Expr::MethodCall)
+                    .or(func_call.map(Expr::FuncCall))
+                    .map_with_span(Node::new);
+
+                // postfix increment, decrement and error propagation
+                let op = select! {
+                    Token::TokenIncrement => UnaryOp::Increment,
+                    Token::TokenDecrement => UnaryOp::Decrement,
+                    Token::TokenQuestion => UnaryOp::Propagate,
+                };
+
+                let postfix = call
+                    .or(atom)
+                    .then(op.map_with_span(|kind, span| (kind, span)).repeated())
+                    .foldl(|expr, (kind, span): (UnaryOp, Range<usize>)| {
+                        let span = expr.span.start..span.end;
+                        Node::new(
+                            Expr::UnaryExpr(Node::new(
+                                UnaryExpr { kind, expr: Box::new(expr) },
+                                span.clone(),
+                            )),
+                            span,
+                        )
+                    });
+
+                // references, which are mutable if the `&` is followed by `mut`
+                let op = just(Token::TokenAnd).ignore_then(just(Token::KeywordMut).or_not()).map(
+                    |mutable| match mutable {
+                        Some(_) => UnaryOp::MutReference,
+                        None => UnaryOp::Reference,
+                    },
                 );
 
-            // sum operations
-            let op = select! {
-                Token::TokenPlus => BinaryOp::Plus,
-                Token::TokenMinus => BinaryOp::Minus,
-            };
-
-            let sum =
-                atom.clone().then(op.then(atom.clone()).repeated()).foldl(|lhs, (kind, rhs)| {
-                    let span = lhs.span.start..rhs.span.end;
-                    Node::new(
-                        Expr::BinaryExpr(Node::new(
-                            BinaryExpr { lhs: Box::new(lhs), rhs: Box::new(rhs), kind },
-                            span.clone(),
-                        )),
-                        span,
+                let unary = op
+                    .map_with_span(|kind, span| (kind, span))
+                    .repeated()
+                    .then(postfix)
+                    .foldr(|(kind, span): (UnaryOp, Range<usize>), expr| {
+                        let span = span.start..expr.span.end;
+                        Node::new(
+                            Expr::UnaryExpr(Node::new(
+                                UnaryExpr { kind, expr: Box::new(expr) },
+                                span.clone(),
+                            )),
+                            span,
+                        )
+                    })
+                    .labelled("unary operation");
+
+                // casts, which bind tighter than any binary operation
+                let cast = unary
+                    .then(
+                        just(Token::KeywordAs)
+                            .ignore_then(type_expr.clone().map_with_span(Node::new))
+                            .repeated(),
                     )
-                });
+                    .foldl(|expr, ty| {
+                        let span = expr.span.start..ty.span.end;
+                        Node::new(
+                            Expr::Cast(Node::new(Cast { expr: Box::new(expr), ty }, span.clone())),
+                            span,
+                        )
+                    })
+                    .labelled("cast")
+                    .boxed();
+
+                // product operations
+                let op = select! {
+                    Token::TokenStar => BinaryOp::Mul,
+                    Token::TokenSlash => BinaryOp::Div,
+                    Token::TokenPercent => BinaryOp::Mod,
+                };
+
+                let product = cast.clone().then(op.then(cast.clone()).repeated()).foldl(
+                    |lhs, (kind, rhs)| {
+                        let span = lhs.span.start..rhs.span.end;
+                        Node::new(
+                            Expr::BinaryExpr(Node::new(
+                                BinaryExpr { lhs: Box::new(lhs), rhs: Box::new(rhs), kind },
+                                span.clone(),
+                            )),
+                            span,
+                        )
+                    },
+                );
 
-            // assignment
-            let op = select! {
-                Token::TokenAssign => BinaryOp::Assign,
-                Token::TokenPlusEq => BinaryOp::PlusEq,
-                Token::TokenMinusEq => BinaryOp::MinusEq,
-                Token::TokenMulEq => BinaryOp::MulEq,
-                Token::TokenDivEq => BinaryOp::DivEq
-            };
-            let assign =
-                sum.clone().then(op.then(sum.clone()).repeated()).foldl(|lhs, (kind, rhs)| {
-                    let span = lhs.span.start..rhs.span.end;
-                    Node::new(
-                        Expr::BinaryExpr(Node::new(
-                            BinaryExpr { lhs: Box::new(lhs), rhs: Box::new(rhs), kind },
-                            span.clone(),
-                        )),
-                        span,
+                // sum operations
+                let op = select! {
+                    Token::TokenPlus => BinaryOp::Plus,
+                    Token::TokenMinus => BinaryOp::Minus,
+                };
+
+                let sum = product.clone().then(op.then(product.clone()).repeated()).foldl(
+                    |lhs, (kind, rhs)| {
+                        let span = lhs.span.start..rhs.span.end;
+                        Node::new(
+                            Expr::BinaryExpr(Node::new(
+                                BinaryExpr { lhs: Box::new(lhs), rhs: Box::new(rhs), kind },
+                                span.clone(),
+                            )),
+                            span,
+                        )
+                    },
+                );
+
+                // comparison operations
+                let op = select! {
+                    Token::TokenLt => BinaryOp::Lt,
+                    Token::TokenGt => BinaryOp::Gt,
+                    Token::TokenLe => BinaryOp::Le,
+                    Token::TokenGe => BinaryOp::Ge,
+                };
+
+                let comparison =
+                    sum.clone().then(op.then(sum.clone()).repeated()).foldl(|lhs, (kind, rhs)| {
+                        let span = lhs.span.start..rhs.span.end;
+                        Node::new(
+                            Expr::BinaryExpr(Node::new(
+                                BinaryExpr { lhs: Box::new(lhs), rhs: Box::new(rhs), kind },
+                                span.clone(),
+                            )),
+                            span,
+                        )
+                    });
+
+                // equality operations
+                let op = select! {
+                    Token::TokenEq => BinaryOp::Eq,
+                    Token::TokenNe => BinaryOp::Ne,
+                };
+
+                let equality = comparison
+                    .clone()
+                    .then(op.then(comparison.clone()).repeated())
+                    .foldl(|lhs, (kind, rhs)| {
+                        let span = lhs.span.start..rhs.span.end;
+                        Node::new(
+                            Expr::BinaryExpr(Node::new(
+                                BinaryExpr { lhs: Box::new(lhs), rhs: Box::new(rhs), kind },
+                                span.clone(),
+                            )),
+                            span,
+                        )
+                    });
+
+                // logical and, which binds tighter than logical or
+                let op = just(Token::TokenLogicalAnd).to(BinaryOp::LogicalAnd);
+
+                let conjunction = equality
+                    .clone()
+                    .then(op.then(equality.clone()).repeated())
+                    .foldl(|lhs, (kind, rhs)| {
+                        let span = lhs.span.start..rhs.span.end;
+                        Node::new(
+                            Expr::BinaryExpr(Node::new(
+                                BinaryExpr { lhs: Box::new(lhs), rhs: Box::new(rhs), kind },
+                                span.clone(),
+                            )),
+                            span,
+                        )
+                    });
+
+                // logical or
+                let op = just(Token::TokenLogicalOr).to(BinaryOp::LogicalOr);
+
+                let logical = conjunction
+                    .clone()
+                    .then(op.then(conjunction.clone()).repeated())
+                    .foldl(|lhs, (kind, rhs)| {
+                        let span = lhs.span.start..rhs.span.end;
+                        Node::new(
+                            Expr::BinaryExpr(Node::new(
+                                BinaryExpr { lhs: Box::new(lhs), rhs: Box::new(rhs), kind },
+                                span.clone(),
+                            )),
+                            span,
+                        )
+                    });
+
+                // coalescing operations, which bind looser than logical operations
+                let coalesce = logical
+                    .clone()
+                    .then(
+                        just(Token::TokenCoalesce)
+                            .to(BinaryOp::Coalesce)
+                            .then(logical.clone())
+                            .repeated(),
                     )
-                });
+                    .foldl(|lhs, (kind, rhs)| {
+                        let span = lhs.span.start..rhs.span.end;
+                        Node::new(
+                            Expr::BinaryExpr(Node::new(
+                                BinaryExpr { lhs: Box::new(lhs), rhs: Box::new(rhs), kind },
+                                span.clone(),
+                            )),
+                            span,
+                        )
+                    })
+                    // boxed to keep the frames of the nested operator parsers from
+                    // overflowing the stack
+                    .boxed();
+
+                // assignment
+                let op = select! {
+                    Token::TokenAssign => BinaryOp::Assign,
+                    Token::TokenPlusEq => BinaryOp::PlusEq,
+                    Token::TokenMinusEq => BinaryOp::MinusEq,
+                    Token::TokenMulEq => BinaryOp::MulEq,
+                    Token::TokenDivEq => BinaryOp::DivEq
+                };
+                let assign = coalesce.clone().then(op.then(coalesce.clone()).repeated()).foldl(
+                    |lhs, (kind, rhs)| {
+                        let span = lhs.span.start..rhs.span.end;
+                        Node::new(
+                            Expr::BinaryExpr(Node::new(
+                                BinaryExpr { lhs: Box::new(lhs), rhs: Box::new(rhs), kind },
+                                span.clone(),
+                            )),
+                            span,
+                        )
+                    },
+                );
 
-            let bin_op = assign.labelled("binary operation");
+                assign.labelled("binary operation").boxed()
+            };
 
-            let func_call = ident
-                .then(atom.separated_by(just(Token::TokenComma)))
-                .map(|(ident, args)| FuncCall { ident, args })
-                .map_with_span(Node::new).labelled("function call");
+            condition.define(control.clone().map_with_span(Node::new).or(operations(false)));
+            let bin_op = operations(true);
 
-            choice((
-                func_call.map(Expr::FuncCall),
-                literal.map(Expr::Literal),
-                block.map(Expr::Block),
-                loop_expr.map(Expr::Loop),
-                conditional.map(Expr::Conditional),
-            ))
-            .map_with_span(Node::new)
-            .or(bin_op)
+            control.map_with_span(Node::new).or(bin_op)
         });
 
         let declaration_idents = ident.then_ignore(just(Token::TokenComma)).repeated().chain(ident);
 
-        let declaration = just(Token::KeywordLet)
-            .ignore_then(declaration_idents)
+        let declaration_mutability = select! {
+            Token::KeywordLet => Mutability::Immutable,
+            Token::KeywordMut => Mutability::Mutable,
+            Token::KeywordConst => Mutability::Constant,
+        };
+
+        let declaration = declaration_mutability
+            .then(declaration_idents)
+            .then(
+                just(Token::TokenColon)
+                    .ignore_then(type_expr.clone().map_with_span(Node::new))
+                    .or_not(),
+            )
             .then_ignore(just(Token::TokenAssign))
             .then(expr.clone())
-            .map(|(idents, value)| Declaration {
-                explicit_ty: None,
+            .map(|(((mutability, idents), explicit_ty), value)| Declaration {
+                explicit_ty,
                 ident: idents.into_iter().next().unwrap(),
-                mutability: Mutability::Immutable,
+                mutability,
                 value,
             })
             .map_with_span(Node::new)
@@ -203,19 +809,12 @@ fn parser() -> impl Parser<Token, AST, Error = Simple<Token>> {
             .map(Stmt::Return)
             .labelled("return statement");
 
-        // function declarations
-        let func_decl_param = ident
-            .then_ignore(just(Token::TokenColon))
-            .then(type_expr.clone().map_with_span(Node::new))
-            .map(|(ident, ty)| FuncParam { ident, ty })
-            .labelled("parameter");
-
         let func_decl_params = (func_decl_param.clone().map_with_span(Node::new))
             .separated_by(just(Token::TokenComma));
 
         let extern_func_decl = just(Token::KeywordExtern)
             .ignore_then(ident)
-            .then(func_decl_params.or_not())
+            .then(func_decl_params.clone().or_not())
             .then_ignore(just(Token::TokenArrow))
             .then(type_expr.clone().map_with_span(Node::new).or_not())
             .map(|((ident, params), ret_ty)| FuncDecl::External {
@@ -225,11 +824,150 @@ fn parser() -> impl Parser<Token, AST, Error = Simple<Token>> {
             })
             .labelled("external function declaration");
 
-        let func_decl = (extern_func_decl).map_with_span(Node::new);
+        // the return type of local functions defaults to unit if omitted
+        let func_ret_ty_and_body = just(Token::TokenArrow)
+            .ignore_then(type_expr.clone().map_with_span(Node::new).or_not())
+            .then(block.clone())
+            .map(|(ret_ty, body)| {
+                let ret_ty = ret_ty.unwrap_or_else(|| {
+                    Node::new(
+                        TypeExpr::Primitive(Primitive::Unit),
+                        body.span.start..body.span.start,
+                    )
+                });
+                (ret_ty, body)
+            });
+
+        let local_func_decl = just(Token::KeywordConst)
+            .or_not()
+            .then(ident)
+            .then(generic_params.clone())
+            .then(func_decl_params.clone())
+            .then(func_ret_ty_and_body.clone())
+            .map(|((((constant, ident), generics), params), (ret_ty, body))| FuncDecl::Local {
+                ident,
+                generics,
+                params,
+                body,
+                ret_ty,
+                constant: constant.is_some(),
+            })
+            .labelled("function declaration");
+
+        let func_decl = extern_func_decl.or(local_func_decl).map_with_span(Node::new);
+
+        // class declarations
+        let receiver = just(Token::KeywordMut)
+            .or_not()
+            .then_ignore(just(Token::Ident("self".into())))
+            .map(|mutability| match mutability {
+                Some(_) => Mutability::Mutable,
+                None => Mutability::Immutable,
+            })
+            .map_with_span(Node::new)
+            .labelled("receiver");
+
+        let method_params = receiver
+            .then(
+                just(Token::TokenComma)
+                    .ignore_then(func_decl_params.clone())
+                    .or_not()
+                    .map(Option::unwrap_or_default),
+            )
+            .map(|(receiver, params)| (Some(receiver), params))
+            .or(func_decl_params.clone().map(|params| (None, params)));
+
+        let method_decl = ident
+            .then(method_params.clone())
+            .then(func_ret_ty_and_body)
+            .map(|((ident, (receiver, params)), (ret_ty, body))| MethodDecl {
+                ident,
+                receiver,
+                params,
+                body,
+                ret_ty,
+            })
+            .map_with_span(Node::new)
+            .labelled("method declaration");
+
+        let class_member = method_decl
+            .clone()
+            .map(ClassMember::Method)
+            .or(struct_field.then_ignore(just(Token::TokenComma).or_not()).map(ClassMember::Field));
+
+        let class_decl = just(Token::KeywordClass)
+            .ignore_then(ident)
+            .then(generic_params.clone())
+            .then(
+                class_member
+                    .repeated()
+                    .delimited_by(just(Token::TokenBraceLeft), just(Token::TokenBraceRight)),
+            )
+            .map(|((ident, generics), members)| {
+                let mut class = ClassDecl { ident, generics, fields: vec![], methods: vec![] };
+                for member in members {
+                    match member {
+                        ClassMember::Field(field) => class.fields.push(field),
+                        ClassMember::Method(method) => class.methods.push(method),
+                    }
+                }
+                class
+            })
+            .map_with_span(Node::new)
+            .labelled("class declaration");
+
+        // interface declarations - abstract methods must declare a return type, so
+        // that the name of the following method is not parsed as one
+        let interface_method = ident
+            .then(method_params)
+            .then_ignore(just(Token::TokenArrow))
+            .then(type_expr.clone().map_with_span(Node::new).then(block.clone().or_not()).or(
+                block.clone().map(|body| {
+                    let span = body.span.start..body.span.start;
+                    (Node::new(TypeExpr::Primitive(Primitive::Unit), span), Some(body))
+                }),
+            ))
+            .map(|((ident, (receiver, params)), (ret_ty, body))| InterfaceMethod {
+                ident,
+                receiver,
+                params,
+                ret_ty,
+                body,
+            })
+            .map_with_span(Node::new)
+            .labelled("interface method");
+
+        let interface_decl = just(Token::KeywordInterface)
+            .ignore_then(ident)
+            .then(
+                interface_method
+                    .repeated()
+                    .delimited_by(just(Token::TokenBraceLeft), just(Token::TokenBraceRight)),
+            )
+            .map(|(ident, methods)| InterfaceDecl { ident, methods })
+            .map_with_span(Node::new)
+            .labelled("interface declaration");
+
+        let impl_decl = just(Token::KeywordImpl)
+            .ignore_then(ident)
+            .then_ignore(just(Token::KeywordFor))
+            .then(ident)
+            .then(
+                method_decl
+                    .repeated()
+                    .delimited_by(just(Token::TokenBraceLeft), just(Token::TokenBraceRight)),
+            )
+            .map(|((interface, ty), methods)| ImplDecl { interface, ty, methods })
+            .map_with_span(Node::new)
+            .labelled("impl declaration");
 
         choice::<_, Simple<Token>>((
             declaration.map(Stmt::Declaration),
             type_decl.map(Stmt::TypeDeclaration),
+            struct_decl.map(Stmt::StructDecl),
+            class_decl.map(Stmt::ClassDecl),
+            interface_decl.map(Stmt::InterfaceDecl),
+            impl_decl.map(Stmt::ImplDecl),
             func_decl.map(Stmt::FuncDecl),
             return_stmt,
             expr.map(Stmt::Expr),
@@ -237,11 +975,37 @@ fn parser() -> impl Parser<Token, AST, Error = Simple<Token>> {
         .map_with_span(Node::new)
     });
 
-    stmt.repeated().then_ignore(end()).map(|stmts| AST { stmts })
+    // imports, which may only appear at the top level of a module
+    let module_symbol = ident
+        .then(just(Token::KeywordAs).ignore_then(ident).or_not())
+        .map(|(name, alias)| ModuleSymbol { name, alias })
+        .map_with_span(Node::new);
+
+    let import = just(Token::KeywordImport)
+        .ignore_then(
+            module_symbol
+                .separated_by(just(Token::TokenComma))
+                .at_least(1)
+                .delimited_by(just(Token::TokenBraceLeft), just(Token::TokenBraceRight)),
+        )
+        .then_ignore(just(Token::KeywordFrom))
+        .then(select! { Token::LiteralStr(path) => unquote(&path) })
+        .map(|(symbols, path)| Import { symbols, path })
+        .map_with_span(Node::new)
+        .labelled("import")
+        .map(Stmt::Import)
+        .map_with_span(Node::new);
+
+    import.or(stmt).repeated().then_ignore(end()).map(|stmts| AST { stmts })
 }
 
 #[tracing::instrument]
 pub fn parse(input: TokenStream) -> Result<AST, Vec<Simple<Token>>> {
+    // line breaks are only used to end calls, so are kept out of the stream
+    let (breaks, input): (Vec<_>, Vec<_>) =
+        input.into_iter().partition(|(token, _)| *token == Token::Newline);
+    let breaks = breaks.into_iter().map(|(_, span)| span.start).collect();
+
     // empty tokens
     if input.is_empty() {
         return Ok(AST { stmts: vec![] });
@@ -252,5 +1016,5 @@ pub fn parse(input: TokenStream) -> Result<AST, Vec<Simple<Token>>> {
     let end = input.last().expect("empty stream").end();
     let eoi = start..end;
 
-    parser().parse(Stream::from_iter(eoi, input.into_iter()))
+    parser(breaks).parse(Stream::from_iter(eoi, input.into_iter()))
 }
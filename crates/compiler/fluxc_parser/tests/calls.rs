@@ -0,0 +1,79 @@
+use fluxc_ast::{BinaryOp, Expr, Stmt, AST};
+use fluxc_lexer::lex;
+use fluxc_parser::parse;
+
+fn parse_src(src: &str) -> AST {
+    parse(lex(src).expect("Lexing failed!")).expect("Parsing failed!")
+}
+
+/// Returns true if the given statement is an assignment.
+fn is_assignment(stmt: &Stmt) -> bool {
+    matches!(stmt, Stmt::Expr(expr) if matches!(
+        &expr.value,
+        Expr::BinaryExpr(bin) if bin.value.kind == BinaryOp::Assign
+    ))
+}
+
+#[test]
+fn test_parse_calls_end_at_line_breaks() {
+    // neither the struct literal nor the parenthesised variable is called with
+    // the start of the next statement
+    let ast = parse_src("mut p = P { x: 1 }\np.x = 3");
+    assert_eq!(ast.stmts.len(), 2);
+    assert!(is_assignment(&ast.stmts[1].value));
+    let ast = parse_src("let a = (b)\nc = 3");
+    assert_eq!(ast.stmts.len(), 2);
+    assert!(is_assignment(&ast.stmts[1].value));
+    let ast = parse_src("let a = b\nc = 3");
+    assert_eq!(ast.stmts.len(), 2);
+    assert!(is_assignment(&ast.stmts[1].value));
+}
+
+#[test]
+fn test_parse_call_arguments_across_lines() {
+    // arguments following a comma may continue on the next line
+    let ast = parse_src("let x = add 1,\n    2");
+    assert_eq!(ast.stmts.len(), 1);
+    let Stmt::Declaration(decl) = &ast.stmts[0].value else { panic!("expected declaration") };
+    let Expr::FuncCall(call) = &decl.value.value.value else { panic!("expected call") };
+    assert_eq!(call.value.args.len(), 2);
+}
+
+#[test]
+fn test_parse_callees() {
+    // literals and struct literals cannot be called, so are followed by
+    // another statement
+    assert_eq!(parse_src("let x = 1 2").stmts.len(), 2);
+    assert_eq!(parse_src("let x = P { x: 1 } 2").stmts.len(), 2);
+    // the results of calls can be
+    let ast = parse_src("let x = (make 1) 2");
+    let Stmt::Declaration(decl) = &ast.stmts[0].value else { panic!("expected declaration") };
+    let Expr::FuncCall(call) = &decl.value.value.value else { panic!("expected call") };
+    assert!(matches!(call.value.callee.value, Expr::FuncCall(_)));
+}
+
+/// Return the value of the declaration the given source consists of.
+fn declared_value(src: &str) -> Expr {
+    let ast = parse_src(src);
+    assert_eq!(ast.stmts.len(), 1);
+    let Stmt::Declaration(decl) = &ast.stmts[0].value else { panic!("expected declaration") };
+    decl.value.value.value.clone()
+}
+
+#[test]
+fn test_parse_calls_as_operands() {
+    // calls bind tighter than binary operators, on either side of them
+    let Expr::BinaryExpr(bin) = declared_value("let x = 1 + f 2") else {
+        panic!("expected binary expression")
+    };
+    assert_eq!(bin.value.kind, BinaryOp::Plus);
+    assert!(matches!(bin.value.rhs.value, Expr::FuncCall(_)));
+    let Expr::BinaryExpr(bin) = declared_value("let x = a.compare b == 0") else {
+        panic!("expected binary expression")
+    };
+    assert_eq!(bin.value.kind, BinaryOp::Eq);
+    assert!(matches!(bin.value.lhs.value, Expr::MethodCall(_)));
+    // and tighter than casts
+    let Expr::Cast(cast) = declared_value("let x = f 2 as u8") else { panic!("expected cast") };
+    assert!(matches!(cast.value.expr.value, Expr::FuncCall(_)));
+}
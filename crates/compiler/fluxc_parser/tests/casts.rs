@@ -0,0 +1,47 @@
+use fluxc_ast::{BinaryOp, Expr, Stmt, UnaryOp};
+use fluxc_lexer::lex;
+use fluxc_parser::parse;
+
+/// Return the initial value of the declaration statement at the given index.
+fn declared(stmts: &[fluxc_ast::Node<Stmt>], index: usize) -> &Expr {
+    match &stmts[index].value {
+        Stmt::Declaration(decl) => &decl.value.value.value,
+        stmt => panic!("expected declaration, found {:?}", stmt),
+    }
+}
+
+#[test]
+fn test_parse_casts() {
+    let src = include_str!("./casts.flx");
+    let tokens = lex(src).expect("Lexing failed!");
+    let ast = parse(tokens).expect("Parsing failed!");
+    assert_eq!(ast.stmts.len(), 3);
+    // casts bind tighter than binary operations
+    match declared(&ast.stmts, 0) {
+        Expr::BinaryExpr(binary) => {
+            assert_eq!(binary.value.kind, BinaryOp::Div);
+            assert!(
+                matches!(&binary.value.lhs.value, Expr::Cast(cast) if cast.value.ty.value.to_string() == "float")
+            );
+            assert!(matches!(&binary.value.rhs.value, Expr::Cast(_)));
+        }
+        expr => panic!("expected binary expression, found {:?}", expr),
+    }
+    // but looser than unary operations
+    match declared(&ast.stmts, 1) {
+        Expr::Cast(cast) => assert!(
+            matches!(&cast.value.expr.value, Expr::UnaryExpr(unary) if unary.value.kind == UnaryOp::Reference)
+        ),
+        expr => panic!("expected cast, found {:?}", expr),
+    }
+    // and chain from left to right
+    match declared(&ast.stmts, 2) {
+        Expr::Cast(cast) => {
+            assert_eq!(cast.value.ty.value.to_string(), "char");
+            assert!(
+                matches!(&cast.value.expr.value, Expr::Cast(inner) if inner.value.ty.value.to_string() == "int")
+            );
+        }
+        expr => panic!("expected cast, found {:?}", expr),
+    }
+}
@@ -0,0 +1,42 @@
+use fluxc_ast::{Expr, Mutability, Stmt};
+use fluxc_lexer::lex;
+use fluxc_parser::parse;
+
+#[test]
+fn test_parse_classes() {
+    let src = include_str!("./classes.flx");
+    let tokens = lex(src).expect("Lexing failed!");
+    let ast = parse(tokens).expect("Parsing failed!");
+    assert_eq!(ast.stmts.len(), 3);
+    // class Counter { ... }
+    match &ast.stmts[0].value {
+        Stmt::ClassDecl(decl) => {
+            let decl = &decl.value;
+            assert_eq!(decl.ident.value, "Counter");
+            assert_eq!(decl.fields.len(), 1);
+            assert_eq!(decl.methods.len(), 2);
+            // new -> Counter
+            assert_eq!(decl.methods[0].value.ident.value, "new");
+            assert!(decl.methods[0].value.receiver.is_none());
+            // add mut self, n: int
+            assert_eq!(decl.methods[1].value.ident.value, "add");
+            assert_eq!(
+                decl.methods[1].value.receiver.as_ref().map(|r| r.value),
+                Some(Mutability::Mutable)
+            );
+            assert_eq!(decl.methods[1].value.params.len(), 1);
+        }
+        stmt => panic!("expected class declaration, found {:?}", stmt),
+    }
+    // counter.add 1
+    match &ast.stmts[2].value {
+        Stmt::Expr(expr) => match &expr.value {
+            Expr::MethodCall(call) => {
+                assert_eq!(call.value.method.value, "add");
+                assert_eq!(call.value.args.len(), 1);
+            }
+            expr => panic!("expected method call, found {:?}", expr),
+        },
+        stmt => panic!("expected expression, found {:?}", stmt),
+    }
+}
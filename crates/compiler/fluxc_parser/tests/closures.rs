@@ -0,0 +1,87 @@
+use fluxc_ast::{Expr, FuncDecl, Primitive, Stmt, TypeExpr};
+use fluxc_lexer::lex;
+use fluxc_parser::parse;
+
+#[test]
+fn test_parse_closures() {
+    let src = include_str!("./closures.flx");
+    let tokens = lex(src).expect("Lexing failed!");
+    let ast = parse(tokens).expect("Parsing failed!");
+    assert_eq!(ast.stmts.len(), 6);
+    // type Predicate = (int, string) -> bool
+    match &ast.stmts[0].value {
+        Stmt::TypeDeclaration(decl) => {
+            assert_eq!(decl.value.value.value.to_string(), "(int, string) -> bool")
+        }
+        stmt => panic!("expected type declaration, found {:?}", stmt),
+    }
+    // apply f: (int) -> int, x: int -> int
+    match &ast.stmts[1].value {
+        Stmt::FuncDecl(decl) => match &decl.value {
+            FuncDecl::Local { params, ret_ty, .. } => {
+                assert_eq!(params.len(), 2);
+                assert_eq!(params[0].value.ty.value.to_string(), "(int) -> int");
+                assert_eq!(ret_ty.value, TypeExpr::Primitive(Primitive::Int));
+            }
+            decl => panic!("expected local function, found {:?}", decl),
+        },
+        stmt => panic!("expected function declaration, found {:?}", stmt),
+    }
+    // (make_adder 1) 2
+    match &ast.stmts[2].value {
+        Stmt::Expr(expr) => match &expr.value {
+            Expr::FuncCall(call) => {
+                assert!(matches!(call.value.callee.value, Expr::FuncCall(_)));
+                assert_eq!(call.value.args.len(), 1);
+            }
+            expr => panic!("expected function call, found {:?}", expr),
+        },
+        stmt => panic!("expected expression, found {:?}", stmt),
+    }
+    // let add = (x: int) -> int { x + n }
+    match &ast.stmts[4].value {
+        Stmt::Declaration(decl) => match &decl.value.value.value {
+            Expr::Closure(closure) => {
+                assert_eq!(closure.value.params.len(), 1);
+                assert_eq!(closure.value.ret_ty.value, TypeExpr::Primitive(Primitive::Int));
+            }
+            expr => panic!("expected closure, found {:?}", expr),
+        },
+        stmt => panic!("expected declaration, found {:?}", stmt),
+    }
+    // let double = (x) -> { x * 2 }
+    match &ast.stmts[5].value {
+        Stmt::Declaration(decl) => match &decl.value.value.value {
+            Expr::Closure(closure) => {
+                assert!(matches!(closure.value.params[0].value.ty.value, TypeExpr::Infer));
+                assert!(matches!(closure.value.ret_ty.value, TypeExpr::Infer));
+            }
+            expr => panic!("expected closure, found {:?}", expr),
+        },
+        stmt => panic!("expected declaration, found {:?}", stmt),
+    }
+}
+
+#[test]
+fn test_parse_function_typed_params() {
+    // the type of the last parameter must be parenthesised if it is a function
+    // type, but a function type may be returned directly
+    let src = r#"
+compose f: (int) -> int, g: ((int) -> int) -> (int) -> int {
+    return (x) -> { g (f x) }
+}
+"#;
+    let tokens = lex(src).expect("Lexing failed!");
+    let ast = parse(tokens).expect("Parsing failed!");
+    match &ast.stmts[0].value {
+        Stmt::FuncDecl(decl) => match &decl.value {
+            FuncDecl::Local { params, ret_ty, .. } => {
+                assert_eq!(params[0].value.ty.value.to_string(), "(int) -> int");
+                assert_eq!(params[1].value.ty.value.to_string(), "(int) -> int");
+                assert_eq!(ret_ty.value.to_string(), "(int) -> int");
+            }
+            decl => panic!("expected local function, found {:?}", decl),
+        },
+        stmt => panic!("expected function declaration, found {:?}", stmt),
+    }
+}
@@ -0,0 +1,55 @@
+use fluxc_ast::{Operation, Stmt, TypeExpr};
+use fluxc_lexer::lex;
+use fluxc_parser::parse;
+
+/// Return the types declared by the type declarations in the given AST.
+fn declared_types(src: &str) -> Vec<TypeExpr> {
+    let tokens = lex(src).expect("Lexing failed!");
+    let ast = parse(tokens).expect("Parsing failed!");
+    ast.stmts
+        .into_iter()
+        .map(|stmt| match stmt.value {
+            Stmt::TypeDeclaration(decl) => decl.value.value.value,
+            stmt => panic!("expected type declaration, found {:?}", stmt),
+        })
+        .collect()
+}
+
+#[test]
+fn test_parse_conditional_types() {
+    let types = declared_types(include_str!("./conditional_types.flx"));
+    assert_eq!(types.len(), 3);
+    // type NonNull<T> = T extends unit ? never : T
+    match &types[0] {
+        TypeExpr::Operation(Operation::Conditional(conditional)) => {
+            assert_eq!(conditional.check.to_string(), "T");
+            assert_eq!(conditional.extends.to_string(), "unit");
+            assert_eq!(conditional.then.to_string(), "never");
+            assert_eq!(conditional.otherwise.to_string(), "T");
+        }
+        ty => panic!("expected conditional type, found {:?}", ty),
+    }
+    // type First<T> = T extends (infer A, infer B) ? A : never
+    match &types[1] {
+        TypeExpr::Operation(Operation::Conditional(conditional)) => {
+            assert_eq!(conditional.captures(), vec!["A", "B"]);
+        }
+        ty => panic!("expected conditional type, found {:?}", ty),
+    }
+    // type Keys = keyof Point | keyof (int, int)
+    assert_eq!(types[2].to_string(), "0 | 1 | keyof Point");
+}
+
+#[test]
+fn test_parse_nested_conditional_types() {
+    let types = declared_types("type Kind<T> = T extends int ? 1 : T extends string ? 2 : 3");
+    match &types[0] {
+        TypeExpr::Operation(Operation::Conditional(conditional)) => {
+            assert!(matches!(
+                conditional.otherwise.as_ref(),
+                TypeExpr::Operation(Operation::Conditional(_))
+            ));
+        }
+        ty => panic!("expected conditional type, found {:?}", ty),
+    }
+}
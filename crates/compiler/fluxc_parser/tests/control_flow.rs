@@ -0,0 +1,51 @@
+use fluxc_ast::{Expr, Literal, Stmt, AST};
+use fluxc_lexer::lex;
+use fluxc_parser::parse;
+
+fn parse_src(src: &str) -> AST {
+    parse(lex(src).expect("Lexing failed!")).expect("Parsing failed!")
+}
+
+#[test]
+fn test_parse_while() {
+    let ast = parse_src("while x != () {\n    x = ()\n}");
+    let Stmt::Expr(expr) = &ast.stmts[0].value else { panic!("expected expression") };
+    let Expr::While(while_expr) = &expr.value else { panic!("expected while loop") };
+    assert!(matches!(while_expr.value.condition.value, Expr::BinaryExpr(_)));
+    assert_eq!(while_expr.value.block.value.stmts.len(), 1);
+}
+
+#[test]
+fn test_parse_match() {
+    let ast = parse_src("let y = match x {\n    true => 1,\n    false => 2,\n}");
+    let Stmt::Declaration(decl) = &ast.stmts[0].value else { panic!("expected declaration") };
+    let Expr::Match(match_expr) = &decl.value.value.value else { panic!("expected match") };
+    assert!(matches!(&match_expr.value.expr.value, Expr::Ident(x) if x.value == "x"));
+    let branches = match_expr
+        .value
+        .branches
+        .iter()
+        .map(|branch| match (&branch.value.pattern.value, &branch.value.value.value) {
+            (Expr::Literal(pattern), Expr::Literal(value)) => {
+                (pattern.value.clone(), value.value.clone())
+            }
+            _ => panic!("expected literals"),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        branches,
+        vec![(Literal::Bool(true), Literal::Int(1)), (Literal::Bool(false), Literal::Int(2))]
+    );
+}
+
+#[test]
+fn test_parse_match_else() {
+    let ast = parse_src("let y = match x {\n    0 => 1,\n    else => 2,\n}");
+    let Stmt::Declaration(decl) = &ast.stmts[0].value else { panic!("expected declaration") };
+    let Expr::Match(match_expr) = &decl.value.value.value else { panic!("expected match") };
+    assert_eq!(match_expr.value.branches.len(), 1);
+    let Some(else_value) = &match_expr.value.else_value else { panic!("expected else branch") };
+    assert!(matches!(&else_value.value, Expr::Literal(value) if value.value == Literal::Int(2)));
+    // the `else` branch may only come last
+    assert!(parse(lex("let y = match x {\n    else => 2,\n    0 => 1,\n}").unwrap()).is_err());
+}
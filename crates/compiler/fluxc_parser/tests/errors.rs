@@ -0,0 +1,43 @@
+use fluxc_ast::{Expr, FuncDecl, Stmt, UnaryOp};
+use fluxc_lexer::lex;
+use fluxc_parser::parse;
+
+#[test]
+fn test_parse_error_propagation() {
+    let src = include_str!("./errors.flx");
+    let tokens = lex(src).expect("Lexing failed!");
+    let ast = parse(tokens).expect("Parsing failed!");
+    assert_eq!(ast.stmts.len(), 2);
+    let Stmt::FuncDecl(decl) = &ast.stmts[0].value else { panic!("expected a function") };
+    let FuncDecl::Local { ret_ty, body, .. } = &decl.value else { panic!("expected a body") };
+    assert_eq!(ret_ty.value.to_string(), "int | Error");
+    // let port = parse_int config.port?
+    match &body.value.stmts[0].value {
+        Stmt::Declaration(decl) => match &decl.value.value.value {
+            // errors are propagated from the result of the call
+            Expr::UnaryExpr(unary) => {
+                assert_eq!(unary.value.kind, UnaryOp::Propagate);
+                assert!(matches!(unary.value.expr.value, Expr::FuncCall(_)));
+            }
+            expr => panic!("expected unary expression, found {:?}", expr),
+        },
+        stmt => panic!("expected declaration, found {:?}", stmt),
+    }
+    // return port?
+    match &body.value.stmts[1].value {
+        Stmt::Return(expr) => match &expr.value {
+            Expr::UnaryExpr(unary) => assert_eq!(unary.value.kind, UnaryOp::Propagate),
+            expr => panic!("expected unary expression, found {:?}", expr),
+        },
+        stmt => panic!("expected return, found {:?}", stmt),
+    }
+    // `?.` is an optional field access rather than a propagation
+    match &ast.stmts[1].value {
+        Stmt::Declaration(decl) => {
+            assert!(
+                matches!(&decl.value.value.value, Expr::FieldAccess(access) if access.value.optional)
+            )
+        }
+        stmt => panic!("expected declaration, found {:?}", stmt),
+    }
+}
@@ -0,0 +1,50 @@
+use fluxc_ast::{Primitive, Stmt, TypeExpr};
+use fluxc_lexer::lex;
+use fluxc_parser::parse;
+
+#[test]
+fn test_parse_generics() {
+    let src = include_str!("./generics.flx");
+    let tokens = lex(src).expect("Lexing failed!");
+    let ast = parse(tokens).expect("Parsing failed!");
+    assert_eq!(ast.stmts.len(), 4);
+    // type Pair<A, B> = (A, B)
+    match &ast.stmts[0].value {
+        Stmt::TypeDeclaration(decl) => {
+            let params: Vec<_> =
+                decl.value.generics.iter().map(|p| p.value.ident.value.as_str()).collect();
+            assert_eq!(params, vec!["A", "B"]);
+        }
+        stmt => panic!("expected type declaration, found {:?}", stmt),
+    }
+    // struct Box<T> { value: T }
+    match &ast.stmts[1].value {
+        Stmt::StructDecl(decl) => {
+            assert_eq!(decl.value.generics.len(), 1);
+            assert_eq!(decl.value.generics[0].value.ident.value, "T");
+            assert_eq!(decl.value.generics[0].value.bound, None);
+        }
+        stmt => panic!("expected struct declaration, found {:?}", stmt),
+    }
+    // max<T extends int | float> a: T, b: T -> T
+    match &ast.stmts[2].value {
+        Stmt::FuncDecl(decl) => {
+            let generics = decl.value.generics();
+            assert_eq!(generics.len(), 1);
+            let bound = generics[0].value.bound.as_ref().expect("expected a bound");
+            assert_eq!(bound.value.to_string(), "int | float");
+        }
+        stmt => panic!("expected function declaration, found {:?}", stmt),
+    }
+    // let b: Box<int> = Box { value: 1 }
+    match &ast.stmts[3].value {
+        Stmt::Declaration(decl) => {
+            let ty = decl.value.explicit_ty.as_ref().map(|ty| &ty.value);
+            assert_eq!(
+                ty,
+                Some(&TypeExpr::Generic("Box".into(), vec![TypeExpr::Primitive(Primitive::Int)]))
+            );
+        }
+        stmt => panic!("expected declaration, found {:?}", stmt),
+    }
+}
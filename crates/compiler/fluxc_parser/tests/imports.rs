@@ -0,0 +1,31 @@
+use fluxc_ast::{Stmt, AST};
+use fluxc_lexer::lex;
+use fluxc_parser::parse;
+
+fn parse_src(src: &str) -> Result<AST, ()> {
+    parse(lex(src).expect("Lexing failed!")).map_err(|_| ())
+}
+
+#[test]
+fn test_parse_imports() {
+    let ast = parse_src("import { sqrt, pow as power } from \"math\"\nlet x = 1").unwrap();
+    assert_eq!(ast.stmts.len(), 2);
+    let Stmt::Import(import) = &ast.stmts[0].value else { panic!("expected import") };
+    assert_eq!(import.value.path, "math");
+    let symbols = import
+        .value
+        .symbols
+        .iter()
+        .map(|symbol| {
+            let alias = symbol.value.alias.as_ref().map(|alias| alias.value.as_str());
+            (symbol.value.name.value.as_str(), alias)
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(symbols, vec![("sqrt", None), ("pow", Some("power"))]);
+}
+
+#[test]
+fn test_parse_imports_only_at_top_level() {
+    assert!(parse_src("f -> {\n    import { sqrt } from \"math\"\n}").is_err());
+    assert!(parse_src("import {} from \"math\"").is_err());
+}
@@ -0,0 +1,53 @@
+use fluxc_ast::{BinaryOp, Expr, Stmt, AST};
+use fluxc_lexer::lex;
+use fluxc_parser::parse;
+
+fn parse_src(src: &str) -> AST {
+    parse(lex(src).expect("Lexing failed!")).expect("Parsing failed!")
+}
+
+/// Returns the operator and operands of the given binary expression.
+fn binary(expr: &Expr) -> (BinaryOp, &Expr, &Expr) {
+    let Expr::BinaryExpr(bin) = expr else { panic!("expected binary expression") };
+    (bin.value.kind, &bin.value.lhs.value, &bin.value.rhs.value)
+}
+
+#[test]
+fn test_parse_comparison_precedence() {
+    // comparisons bind looser than sums and tighter than equality
+    let ast = parse_src("let x = 1 + 2 < 4 == 5 >= 3");
+    let Stmt::Declaration(decl) = &ast.stmts[0].value else { panic!("expected declaration") };
+    let (op, lhs, rhs) = binary(&decl.value.value.value);
+    assert_eq!(op, BinaryOp::Eq);
+    let (op, sum, _) = binary(lhs);
+    assert_eq!(op, BinaryOp::Lt);
+    assert_eq!(binary(sum).0, BinaryOp::Plus);
+    assert_eq!(binary(rhs).0, BinaryOp::Ge);
+}
+
+#[test]
+fn test_parse_logical_precedence() {
+    // `&&` binds tighter than `||` on either side
+    for (src, and_on_lhs) in [("let x = a || b && c", false), ("let x = a && b || c", true)] {
+        let ast = parse_src(src);
+        let Stmt::Declaration(decl) = &ast.stmts[0].value else { panic!("expected declaration") };
+        let (op, lhs, rhs) = binary(&decl.value.value.value);
+        assert_eq!(op, BinaryOp::LogicalOr);
+        let conjunction = if and_on_lhs { lhs } else { rhs };
+        assert_eq!(binary(conjunction).0, BinaryOp::LogicalAnd);
+    }
+}
+
+#[test]
+fn test_parse_comparison_operators() {
+    for (src, kind) in [
+        ("a < b", BinaryOp::Lt),
+        ("a > b", BinaryOp::Gt),
+        ("a <= b", BinaryOp::Le),
+        ("a >= b", BinaryOp::Ge),
+    ] {
+        let ast = parse_src(src);
+        let Stmt::Expr(expr) = &ast.stmts[0].value else { panic!("expected expression") };
+        assert_eq!(binary(&expr.value).0, kind);
+    }
+}
@@ -0,0 +1,59 @@
+use fluxc_ast::{BinaryOp, Expr, FuncDecl, Stmt};
+use fluxc_lexer::lex;
+use fluxc_parser::parse;
+
+#[test]
+fn test_parse_optionals() {
+    let src = include_str!("./optionals.flx");
+    let tokens = lex(src).expect("Lexing failed!");
+    let ast = parse(tokens).expect("Parsing failed!");
+    assert_eq!(ast.stmts.len(), 5);
+    // type MaybeInt = int?
+    match &ast.stmts[0].value {
+        Stmt::TypeDeclaration(decl) => assert_eq!(decl.value.value.value.to_string(), "unit | int"),
+        stmt => panic!("expected type declaration, found {:?}", stmt),
+    }
+    // type Unwrap<T> = T extends (int?) ? int : T
+    match &ast.stmts[1].value {
+        Stmt::TypeDeclaration(decl) => {
+            assert_eq!(decl.value.value.value.to_string(), "T extends unit | int ? int : T")
+        }
+        stmt => panic!("expected type declaration, found {:?}", stmt),
+    }
+    // find_name id: int? -> string?
+    match &ast.stmts[2].value {
+        Stmt::FuncDecl(decl) => match &decl.value {
+            FuncDecl::Local { params, ret_ty, .. } => {
+                assert_eq!(params[0].value.ty.value.to_string(), "unit | int");
+                assert_eq!(ret_ty.value.to_string(), "unit | string");
+            }
+            decl => panic!("expected local function, found {:?}", decl),
+        },
+        stmt => panic!("expected function declaration, found {:?}", stmt),
+    }
+    // let name = p?.name ?? "anonymous"
+    match &ast.stmts[3].value {
+        Stmt::Declaration(decl) => match &decl.value.value.value {
+            Expr::BinaryExpr(binary) => {
+                assert_eq!(binary.value.kind, BinaryOp::Coalesce);
+                match &binary.value.lhs.value {
+                    Expr::FieldAccess(access) => assert!(access.value.optional),
+                    expr => panic!("expected field access, found {:?}", expr),
+                }
+            }
+            expr => panic!("expected binary expression, found {:?}", expr),
+        },
+        stmt => panic!("expected declaration, found {:?}", stmt),
+    }
+    // let x = a ?? b || c
+    match &ast.stmts[4].value {
+        Stmt::Declaration(decl) => match &decl.value.value.value {
+            Expr::BinaryExpr(binary) => {
+                assert_eq!(binary.value.kind, BinaryOp::Coalesce);
+                assert!(matches!(binary.value.rhs.value, Expr::BinaryExpr(_)));
+            }
+            expr => panic!("expected binary expression, found {:?}", expr),
+        },
+        stmt => panic!("expected declaration, found {:?}", stmt),
+    }
+}
@@ -0,0 +1,58 @@
+use fluxc_ast::{Expr, Literal, Numeric, Primitive, Stmt, TypeExpr};
+use fluxc_lexer::lex;
+use fluxc_parser::parse;
+
+#[test]
+fn test_parse_sized_numbers() {
+    let src = include_str!("./sized.flx");
+    let tokens = lex(src).expect("Lexing failed!");
+    let ast = parse(tokens).expect("Parsing failed!");
+    assert_eq!(ast.stmts.len(), 3);
+    let Stmt::Declaration(flags) = &ast.stmts[0].value else { panic!("expected declaration") };
+    assert_eq!(
+        flags.value.explicit_ty.as_ref().map(|ty| &ty.value),
+        Some(&TypeExpr::Primitive(Primitive::Numeric(Numeric::U8)))
+    );
+    // suffixed literals are casts of the literal to the suffixed type
+    let Stmt::Declaration(mask) = &ast.stmts[1].value else { panic!("expected declaration") };
+    match &mask.value.value.value {
+        Expr::Cast(cast) => {
+            assert_eq!(cast.value.ty.value.to_string(), "u8");
+            assert!(
+                matches!(&cast.value.expr.value, Expr::Literal(literal) if literal.value == Literal::Int(255))
+            );
+        }
+        expr => panic!("expected cast, found {:?}", expr),
+    }
+    let Stmt::Declaration(ratio) = &ast.stmts[2].value else { panic!("expected declaration") };
+    assert!(
+        matches!(&ratio.value.value.value, Expr::Cast(cast) if cast.value.ty.value.to_string() == "f32")
+    );
+}
+
+#[test]
+fn test_parse_sized_literal_out_of_range() {
+    let errors = parse(lex("let mask = 256u8").expect("Lexing failed!")).err().unwrap();
+    assert!(errors.iter().any(|err| format!("{:?}", err).contains("out of range")));
+}
+
+#[test]
+fn test_parse_sized_literal_above_i64() {
+    let ast = parse(lex("let max = 18446744073709551615u64").expect("Lexing failed!"))
+        .expect("Parsing failed!");
+    let Stmt::Declaration(max) = &ast.stmts[0].value else { panic!("expected declaration") };
+    // the value is the `i64` with the same bits, reinterpreted as a `u64`
+    let Expr::Cast(outer) = &max.value.value.value else { panic!("expected cast") };
+    assert_eq!(outer.value.ty.value.to_string(), "u64");
+    let Expr::Cast(inner) = &outer.value.expr.value else { panic!("expected cast") };
+    assert_eq!(inner.value.ty.value.to_string(), "i64");
+    assert!(
+        matches!(&inner.value.expr.value, Expr::Literal(literal) if literal.value == Literal::Int(-1))
+    );
+}
+
+#[test]
+fn test_parse_float_literal_with_integer_suffix() {
+    let errors = parse(lex("let x = 1.5u8").expect("Lexing failed!")).err().unwrap();
+    assert!(errors.iter().any(|err| format!("{:?}", err).contains("integer suffix")));
+}
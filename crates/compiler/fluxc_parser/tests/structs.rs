@@ -0,0 +1,68 @@
+use fluxc_ast::{Expr, Stmt};
+use fluxc_lexer::lex;
+use fluxc_parser::parse;
+
+#[test]
+fn test_parse_structs() {
+    let src = include_str!("./structs.flx");
+    let tokens = lex(src).expect("Lexing failed!");
+    let ast = parse(tokens).expect("Parsing failed!");
+    assert_eq!(ast.stmts.len(), 4);
+    // struct Point { x: int, y: int }
+    match &ast.stmts[0].value {
+        Stmt::StructDecl(decl) => {
+            assert_eq!(decl.value.ident.value, "Point");
+            let fields: Vec<_> =
+                decl.value.fields.iter().map(|f| f.value.ident.value.as_str()).collect();
+            assert_eq!(fields, vec!["x", "y"]);
+            assert!(!decl.value.external);
+        }
+        stmt => panic!("expected struct declaration, found {:?}", stmt),
+    }
+    // let p = Point { x: 1, y: 2 }
+    match &ast.stmts[1].value {
+        Stmt::Declaration(decl) => match &decl.value.value.value {
+            Expr::StructExpr(expr) => {
+                assert_eq!(expr.value.ident.value, "Point");
+                assert_eq!(expr.value.fields.len(), 2);
+            }
+            expr => panic!("expected struct literal, found {:?}", expr),
+        },
+        stmt => panic!("expected declaration, found {:?}", stmt),
+    }
+    // let x = p.x
+    match &ast.stmts[2].value {
+        Stmt::Declaration(decl) => match &decl.value.value.value {
+            Expr::FieldAccess(access) => assert_eq!(access.value.field.value, "x"),
+            expr => panic!("expected field access, found {:?}", expr),
+        },
+        stmt => panic!("expected declaration, found {:?}", stmt),
+    }
+    // extern struct Pixel { r: u8, value: int, g: u8 }
+    match &ast.stmts[3].value {
+        Stmt::StructDecl(decl) => {
+            assert_eq!(decl.value.ident.value, "Pixel");
+            assert_eq!(decl.value.fields.len(), 3);
+            assert!(decl.value.external);
+        }
+        stmt => panic!("expected struct declaration, found {:?}", stmt),
+    }
+}
+
+#[test]
+fn test_parse_empty_struct_literals() {
+    let ast = parse(lex("let e = Empty {}").unwrap()).expect("Parsing failed!");
+    let Stmt::Declaration(decl) = &ast.stmts[0].value else { panic!("expected declaration") };
+    let Expr::StructExpr(expr) = &decl.value.value.value else { panic!("expected struct literal") };
+    assert_eq!(expr.value.ident.value, "Empty");
+    assert!(expr.value.fields.is_empty());
+    // the braces following a condition are its block, not a struct literal
+    for src in ["if x {}", "while x {}", "if x == y {}\nlet z = 1"] {
+        let ast = parse(lex(src).unwrap()).expect("Parsing failed!");
+        let Stmt::Expr(expr) = &ast.stmts[0].value else { panic!("expected expression") };
+        assert!(matches!(expr.value, Expr::Conditional(_) | Expr::While(_)));
+    }
+    // unless the struct literal is parenthesised
+    let ast = parse(lex("if x == (Empty {}) {}").unwrap()).expect("Parsing failed!");
+    assert_eq!(ast.stmts.len(), 1);
+}
@@ -6,9 +6,12 @@ mod tests {
 
     #[test]
     fn test_basic_assign() -> Result<(), Box<dyn Error>> {
-        let input = r#"mut x = 0
-x = 1"#;
-        compile_to_mem(input.into())?;
+        let input = r#"main -> int {
+    mut x = 0
+    x = 1
+    return x
+}"#;
+        compile_to_mem(input.into(), "test.flx")?;
         Ok(())
     }
 }
@@ -0,0 +1,292 @@
+//! Defines the environment of type aliases, declared using `type Name = ...`,
+//! and the resolution of references to them.
+//!
+//! Aliases may be recursive, such as `type List = () | (int, List)`. When an
+//! alias is resolved, a reference back to an alias that is already being
+//! expanded is represented as `TypeExpr::Circular(Ref(name))`, so that the
+//! resolved type is always finite.
+//!
+//! Aliases may also be generic over type parameters, such as
+//! `type Pair<A, B> = (A, B)`. References to a generic alias, such as
+//! `Pair<int, string>`, are expanded by substituting the type arguments for
+//! its parameters. Conditional types in the expanded alias are evaluated once
+//! the resolved type is simplified.
+
+use std::collections::HashMap;
+
+use fluxc_ast::{ConditionalType, Intersection, Operation, Primitive, TypeExpr, Union};
+
+/// An error raised by an ill-formed type alias.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AliasError {
+    /// The alias refers to itself without passing through a tuple or array,
+    /// such as `type A = A | int`.
+    Cyclic(String),
+    /// The alias is recursive, but none of its values are finite, such as
+    /// `type A = (int, A)`.
+    InfiniteSize(String),
+}
+
+/// A declared type alias.
+#[derive(Debug, Clone)]
+struct Alias {
+    /// The names of the type parameters of the alias.
+    params: Vec<String>,
+    /// The type the alias refers to.
+    ty: TypeExpr,
+}
+
+/// The environment of declared type aliases.
+#[derive(Debug, Clone, Default)]
+pub struct Aliases {
+    aliases: HashMap<String, Alias>,
+}
+
+impl Aliases {
+    /// Create an empty alias environment.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declare an alias, replacing any previous alias of the same name.
+    pub fn declare<S: ToString>(&mut self, name: S, ty: TypeExpr) {
+        self.declare_generic(name, vec![], ty);
+    }
+
+    /// Declare an alias generic over the type parameters with the given
+    /// names, replacing any previous alias of the same name.
+    pub fn declare_generic<S: ToString>(&mut self, name: S, params: Vec<String>, ty: TypeExpr) {
+        self.aliases.insert(name.to_string(), Alias { params, ty });
+    }
+
+    /// Return the type the alias with the given name was declared as.
+    pub fn get(&self, name: &str) -> Option<&TypeExpr> {
+        self.aliases.get(name).map(|alias| &alias.ty)
+    }
+
+    /// Return the names of the type parameters of the alias with the given
+    /// name.
+    pub fn params(&self, name: &str) -> Option<&[String]> {
+        self.aliases.get(name).map(|alias| alias.params.as_slice())
+    }
+
+    /// Returns `true` if an alias with the given name has been declared.
+    pub fn contains(&self, name: &str) -> bool {
+        self.aliases.contains_key(name)
+    }
+
+    /// Replace every reference to an alias in the given type with the type it
+    /// refers to. References to types that are not aliases are left as-is.
+    pub fn resolve(&self, ty: &TypeExpr) -> TypeExpr {
+        self.expand(ty, &mut vec![])
+    }
+
+    /// Unfold one level of a recursive type, replacing a circular reference
+    /// with the alias it refers to.
+    pub fn unfold(&self, ty: &TypeExpr) -> TypeExpr {
+        match ty {
+            TypeExpr::Circular(inner) => self.resolve(inner),
+            ty => ty.clone(),
+        }
+    }
+
+    /// Expand the given type, where `stack` holds the aliases currently being
+    /// expanded.
+    fn expand(&self, ty: &TypeExpr, stack: &mut Vec<String>) -> TypeExpr {
+        match ty {
+            TypeExpr::Primitive(Primitive::Ref(name)) if stack.contains(name) => {
+                TypeExpr::Circular(ty.clone().into())
+            }
+            TypeExpr::Primitive(Primitive::Ref(name)) => match self.aliases.get(name) {
+                Some(alias) => {
+                    stack.push(name.clone());
+                    let ty = self.expand(&alias.ty, stack);
+                    stack.pop();
+                    ty
+                }
+                None => ty.clone(),
+            },
+            TypeExpr::Generic(name, _) if stack.contains(name) => {
+                TypeExpr::Circular(ty.clone().into())
+            }
+            TypeExpr::Generic(name, args) => {
+                let args: Vec<_> = args.iter().map(|ty| self.expand(ty, stack)).collect();
+                match self.aliases.get(name) {
+                    Some(alias) => {
+                        let args = alias.params.iter().cloned().zip(args).collect();
+                        stack.push(name.clone());
+                        let ty = self.expand(&alias.ty.substitute(&args), stack);
+                        stack.pop();
+                        ty
+                    }
+                    None => TypeExpr::Generic(name.clone(), args),
+                }
+            }
+            TypeExpr::Primitive(Primitive::Tuple(types)) => TypeExpr::Primitive(Primitive::Tuple(
+                types.iter().map(|ty| self.expand(ty, stack)).collect(),
+            )),
+            TypeExpr::Operation(Operation::Union(union)) => TypeExpr::Operation(Operation::Union(
+                Union::of(self.expand(&union.lhs, stack), self.expand(&union.rhs, stack)),
+            )),
+            TypeExpr::Operation(Operation::Intersection(intersection)) => {
+                TypeExpr::Operation(Operation::Intersection(Intersection::of(
+                    self.expand(&intersection.lhs, stack),
+                    self.expand(&intersection.rhs, stack),
+                )))
+            }
+            TypeExpr::Operation(Operation::Array(inner, size)) => {
+                TypeExpr::Operation(Operation::Array(self.expand(inner, stack).into(), *size))
+            }
+            TypeExpr::Operation(Operation::Conditional(conditional)) => {
+                // references to captured types shadow aliases of the same name,
+                // so are hidden from expansion
+                let captures = conditional.captures();
+                let then = conditional.then.replace(&|ty| match ty {
+                    TypeExpr::Primitive(Primitive::Ref(name)) if captures.contains(name) => {
+                        Some(TypeExpr::Capture(name.clone()))
+                    }
+                    _ => None,
+                });
+                let then = self.expand(&then, stack).replace(&|ty| match ty {
+                    TypeExpr::Capture(name) if captures.contains(name) => {
+                        Some(TypeExpr::Primitive(Primitive::Ref(name.clone())))
+                    }
+                    _ => None,
+                });
+                TypeExpr::Operation(Operation::Conditional(ConditionalType::of(
+                    self.expand(&conditional.check, stack),
+                    self.expand(&conditional.extends, stack),
+                    then,
+                    self.expand(&conditional.otherwise, stack),
+                )))
+            }
+            TypeExpr::Operation(Operation::KeyOf(inner)) => {
+                TypeExpr::Operation(Operation::KeyOf(self.expand(inner, stack).into()))
+            }
+            ty => ty.clone(),
+        }
+    }
+
+    /// Returns `true` if the alias with the given name refers to itself,
+    /// directly or through other aliases.
+    pub fn is_recursive(&self, name: &str) -> bool {
+        self.reaches(name, |ty| refs(ty, true))
+    }
+
+    /// Check that the alias with the given name is well-founded. Aliases may
+    /// only refer to themselves from within a tuple or an array, and recursive
+    /// aliases must have at least one finite value.
+    pub fn check(&self, name: &str) -> Result<(), AliasError> {
+        if self.reaches(name, |ty| refs(ty, false)) {
+            return Err(AliasError::Cyclic(name.to_string()));
+        }
+        if self.is_recursive(name) && !self.is_finite(&self.aliases[name].ty, &mut vec![name]) {
+            return Err(AliasError::InfiniteSize(name.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if the alias with the given name can be reached from its
+    /// own declaration, following the references returned by `edges`.
+    /// References to the type parameters of an alias are not followed.
+    fn reaches<'a>(&'a self, name: &str, edges: impl Fn(&'a TypeExpr) -> Vec<&'a String>) -> bool {
+        let edges = |alias: &'a Alias| {
+            edges(&alias.ty).into_iter().filter(|name| !alias.params.contains(name))
+        };
+        let Some(alias) = self.aliases.get(name) else {
+            return false;
+        };
+        let mut visited: Vec<&String> = vec![];
+        let mut queue: Vec<_> = edges(alias).collect();
+        while let Some(next) = queue.pop() {
+            if next == name {
+                return true;
+            }
+            if visited.contains(&next) {
+                continue;
+            }
+            visited.push(next);
+            if let Some(alias) = self.aliases.get(next) {
+                queue.extend(edges(alias));
+            }
+        }
+        false
+    }
+
+    /// Returns `true` if the given type has a finite value, assuming that the
+    /// aliases in `stack` do not.
+    fn is_finite<'a>(&'a self, ty: &'a TypeExpr, stack: &mut Vec<&'a str>) -> bool {
+        match ty {
+            TypeExpr::Primitive(Primitive::Ref(name)) | TypeExpr::Generic(name, _) => {
+                if stack.contains(&name.as_str()) {
+                    return false;
+                }
+                match self.aliases.get(name) {
+                    Some(alias) => {
+                        stack.push(name);
+                        let finite = self.is_finite(&alias.ty, stack);
+                        stack.pop();
+                        finite
+                    }
+                    None => true,
+                }
+            }
+            TypeExpr::Primitive(Primitive::Never) => false,
+            TypeExpr::Primitive(Primitive::Tuple(types)) => {
+                types.iter().all(|ty| self.is_finite(ty, stack))
+            }
+            TypeExpr::Operation(Operation::Union(union)) => {
+                self.is_finite(&union.lhs, stack) || self.is_finite(&union.rhs, stack)
+            }
+            TypeExpr::Operation(Operation::Intersection(intersection)) => {
+                self.is_finite(&intersection.lhs, stack) && self.is_finite(&intersection.rhs, stack)
+            }
+            // arrays of unknown or zero length may be empty
+            TypeExpr::Operation(Operation::Array(inner, Some(size))) if *size > 0 => {
+                self.is_finite(inner, stack)
+            }
+            TypeExpr::Operation(Operation::Conditional(conditional)) => {
+                self.is_finite(&conditional.then, stack)
+                    || self.is_finite(&conditional.otherwise, stack)
+            }
+            TypeExpr::Circular(inner) => self.is_finite(inner, stack),
+            _ => true,
+        }
+    }
+}
+
+/// Collect the names referred to by the given type. Unless `nested` is set,
+/// references inside tuples, arrays and type arguments are skipped.
+fn refs(ty: &TypeExpr, nested: bool) -> Vec<&String> {
+    match ty {
+        TypeExpr::Primitive(Primitive::Ref(name)) => vec![name],
+        TypeExpr::Generic(name, args) => {
+            let mut names = vec![name];
+            if nested {
+                names.extend(args.iter().flat_map(|ty| refs(ty, nested)));
+            }
+            names
+        }
+        TypeExpr::Primitive(Primitive::Tuple(types)) if nested => {
+            types.iter().flat_map(|ty| refs(ty, nested)).collect()
+        }
+        TypeExpr::Operation(Operation::Union(Union { lhs, rhs }))
+        | TypeExpr::Operation(Operation::Intersection(Intersection { lhs, rhs })) => {
+            let mut names = refs(lhs, nested);
+            names.extend(refs(rhs, nested));
+            names
+        }
+        // conditional types cannot be evaluated until their references are
+        // resolved, so refer to every type they are made of
+        TypeExpr::Operation(Operation::Conditional(conditional)) => {
+            [&conditional.check, &conditional.extends, &conditional.then, &conditional.otherwise]
+                .into_iter()
+                .flat_map(|ty| refs(ty, nested))
+                .collect()
+        }
+        TypeExpr::Operation(Operation::KeyOf(inner)) => refs(inner, nested),
+        TypeExpr::Operation(Operation::Array(inner, _)) if nested => refs(inner, nested),
+        TypeExpr::Circular(inner) => refs(inner, nested),
+        _ => vec![],
+    }
+}
@@ -1,46 +1,112 @@
-use crate::{KeyedTableEntry, TableEntry, TypeField, TypeTable};
+use crate::{
+    layout::{layout_struct, layout_tagged, Repr},
+    KeyedTableEntry, TableEntry, TypeField, TypeTable,
+};
 
 /// Utility type for creating struct types.
 #[derive(Debug)]
 pub struct StructBuilder {
     name: String,
     fields: Vec<(String, usize)>,
+    repr: Repr,
 }
 
 impl StructBuilder {
     /// Create a new struct builder.
     pub fn new<S: ToString>(name: S) -> Self {
-        StructBuilder { name: name.to_string(), fields: vec![] }
+        StructBuilder { name: name.to_string(), fields: vec![], repr: Repr::default() }
     }
     /// Add a field to this type.
     pub fn field<S: ToString>(mut self, name: S, ty: &KeyedTableEntry) -> Self {
         self.fields.push((name.to_string(), ty.id));
         self
     }
+    /// Set the representation used to lay out the fields of this type.
+    pub fn repr(mut self, repr: Repr) -> Self {
+        self.repr = repr;
+        self
+    }
     /// Build the output struct.
     pub fn build(self, table: &mut TypeTable) -> &KeyedTableEntry {
-        let mut index = 0;
+        // the struct is only sized if all of its fields are sized
+        let layout = self
+            .fields
+            .iter()
+            .map(|(_, ty)| table.find(*ty).expect("failed to find type").layout())
+            .collect::<Option<Vec<_>>>()
+            .map(|fields| layout_struct(&fields, self.repr));
         table.append(TableEntry {
             name: self.name,
             fields: Some(
                 self.fields
-                    .iter()
-                    .map(|(name, ty)| {
-                        let field = TypeField { index, name: name.clone(), ty: *ty };
-                        index += 1;
-                        field
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, (name, ty))| TypeField {
+                        index,
+                        name,
+                        ty,
+                        offset: layout.as_ref().map(|layout| layout.offsets[index]),
                     })
                     .collect(),
             ),
-            size: self
-                .fields
-                .iter()
-                .map(|(_, ty)| table.find(*ty).expect("failed to find type").size)
-                .reduce(|size, out| match (size, out) {
-                    (Some(a), Some(b)) => Some(a + b),
-                    _ => None,
-                })
-                .and_then(|v| v),
+            size: layout.as_ref().map(|layout| layout.layout.size),
+            align: layout.map_or(1, |layout| layout.layout.align),
+        })
+    }
+}
+
+/// Utility type for creating tagged union types.
+///
+/// A tagged union holds a `tag`, identifying which of its members it holds by
+/// the member's position, followed by a payload large enough to hold any of
+/// them. Each member is a field at the offset of the payload.
+#[derive(Debug)]
+pub struct UnionBuilder {
+    name: String,
+    members: Vec<(String, usize)>,
+}
+
+impl UnionBuilder {
+    /// Create a new union builder.
+    pub fn new<S: ToString>(name: S) -> Self {
+        UnionBuilder { name: name.to_string(), members: vec![] }
+    }
+    /// Add a member to this type.
+    pub fn member<S: ToString>(mut self, name: S, ty: &KeyedTableEntry) -> Self {
+        self.members.push((name.to_string(), ty.id));
+        self
+    }
+    /// Build the output union.
+    pub fn build(self, table: &mut TypeTable) -> &KeyedTableEntry {
+        let tag = table.find_by_name("int").expect("failed to find type");
+        let (tag_id, tag_layout) = (tag.id, tag.layout());
+        // the union is only sized if all of its members are sized
+        let layout = self
+            .members
+            .iter()
+            .map(|(_, ty)| table.find(*ty).expect("failed to find type").layout())
+            .collect::<Option<Vec<_>>>()
+            .zip(tag_layout)
+            .map(|(members, tag)| layout_tagged(tag, &members));
+        let payload = layout.as_ref().map(|layout| layout.offsets[1]);
+        let members = self.members.into_iter().enumerate().map(|(index, (name, ty))| TypeField {
+            index: index + 1,
+            name,
+            ty,
+            offset: payload,
+        });
+        let tag = TypeField {
+            index: 0,
+            name: "tag".to_string(),
+            ty: tag_id,
+            offset: layout.as_ref().map(|layout| layout.offsets[0]),
+        };
+        let fields = std::iter::once(tag).chain(members).collect();
+        table.append(TableEntry {
+            name: self.name,
+            fields: Some(fields),
+            size: layout.as_ref().map(|layout| layout.layout.size),
+            align: layout.map_or(1, |layout| layout.layout.align),
         })
     }
 }
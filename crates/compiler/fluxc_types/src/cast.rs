@@ -0,0 +1,85 @@
+//! Classifies the conversions performed by casts.
+//!
+//! - Values assignable to the target type are coerced to it, as if they were
+//!   assigned, so `1 as int | string` injects the `int` into the union.
+//! - Integers and floats convert between each other, truncating towards zero
+//!   when converting to an integer.
+//! - Integers, `char` and `bool` convert to integers and `char`, widening or
+//!   narrowing the integer holding them. Narrowing keeps the lowest bits.
+//! - `float` and `f32` convert between each other, rounding to the nearest
+//!   `f32`.
+//! - Tagged unions convert to any of their members, or to a union of some of
+//!   their members, checking at runtime that the value is one of them.
+
+use fluxc_ast::{Extends, Numeric, Primitive, TypeExpr};
+
+use crate::lower::{erase, is_tagged};
+
+/// Enumeration of the conversions performed by casts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Conversion {
+    /// The value is assignable to the target type, so is coerced to it.
+    Coerce,
+    /// An integer is converted to the nearest float.
+    IntToFloat,
+    /// A float is converted to an integer, truncating towards zero.
+    FloatToInt,
+    /// An integer is widened or narrowed to another integer type.
+    Resize,
+    /// A float is widened or narrowed to another float type.
+    FloatResize,
+    /// A member of a tagged union is extracted from it, trapping if the union
+    /// holds some other member.
+    Extract,
+}
+
+/// Find the conversion performed by casting values of type `from` to `to`,
+/// or `None` if there is none.
+pub fn conversion(from: &TypeExpr, to: &TypeExpr) -> Option<Conversion> {
+    if from.extends(to).is_true() {
+        return Some(Conversion::Coerce);
+    }
+    let (from, to) = (erase(from), erase(to));
+    if from.extends(&to).is_true() {
+        return Some(Conversion::Coerce);
+    }
+    let char_or_bool =
+        |ty: &TypeExpr| matches!(ty, TypeExpr::Primitive(Primitive::Char | Primitive::Bool));
+    match (&from, &to) {
+        (from, to) if is_integer(from) && is_float(to) => Some(Conversion::IntToFloat),
+        (from, to) if is_float(from) && is_integer(to) => Some(Conversion::FloatToInt),
+        (from, to) if is_float(from) && is_float(to) => Some(Conversion::FloatResize),
+        (from, to)
+            if (is_integer(from) || char_or_bool(from))
+                && (is_integer(to) || to == &TypeExpr::Primitive(Primitive::Char)) =>
+        {
+            Some(Conversion::Resize)
+        }
+        (from, to) if is_tagged(from) && to.extends(from).is_true() => Some(Conversion::Extract),
+        _ => None,
+    }
+}
+
+/// Returns true if the given erased type is an integer type.
+pub fn is_integer(ty: &TypeExpr) -> bool {
+    match ty {
+        TypeExpr::Primitive(Primitive::Int) => true,
+        TypeExpr::Primitive(Primitive::Numeric(numeric)) => !numeric.is_float(),
+        _ => false,
+    }
+}
+
+/// Returns true if the given erased type is a floating-point type.
+pub fn is_float(ty: &TypeExpr) -> bool {
+    matches!(ty, TypeExpr::Primitive(Primitive::Float | Primitive::Numeric(Numeric::F32)))
+}
+
+/// Returns true if values of the given erased type are held as signed
+/// integers, which are sign-extended when widened.
+pub fn is_signed(ty: &TypeExpr) -> bool {
+    match ty {
+        TypeExpr::Primitive(Primitive::Int) => true,
+        TypeExpr::Primitive(Primitive::Numeric(numeric)) => numeric.is_signed(),
+        _ => false,
+    }
+}
@@ -0,0 +1,1870 @@
+//! Defines a constraint-based type inference engine in the style of
+//! Hindley-Milner.
+//!
+//! Inference happens in two phases. Walking the AST assigns every expression a
+//! type, which may be a type variable standing in for a type that is not yet
+//! known, and emits constraints relating these types to one another. The
+//! constraints are then solved by unification, building a substitution that
+//! maps each type variable to the type it stands for.
+//!
+//! Constraints are solved whenever a function declaration is finished, so that
+//! its type can be generalised over the type variables it leaves unresolved.
+//! This allows functions whose parameters are not annotated to be called with
+//! arguments of different types.
+//!
+//! Unification of known types is aware of unions and intersections: a value
+//! may be assigned to a union if it is assignable to any of its members, and
+//! to an intersection if it is assignable to all of them.
+//!
+//! The type parameters of generic functions are lowered to type variables,
+//! so that they are generalised along with the rest of the function's type.
+//! Each use of a generic function instantiates them afresh, and the types they
+//! are instantiated with are checked against their bounds once solved.
+//!
+//! Comparing a variable against a literal narrows the type of the variable
+//! wherever the comparison is known to hold or not to hold: within the
+//! branches of conditionals and matches, the bodies of while loops, and the
+//! rest of a block following an `if` whose branches all return or break.
+//! Narrowed variables are bound in a scope of their own, and widen back to
+//! their declared type when assigned to.
+
+use std::{
+    collections::{HashMap, HashSet},
+    ops::Range,
+};
+
+use fluxc_ast::{
+    BinaryOp, Block, ClassDecl, Declaration, Exclude, Expr, Extends, FuncDecl, FuncParam,
+    GenericParam, Ident, ImplDecl, InterfaceDecl, InterfaceMethod, Intersect, Literal, MethodCall,
+    MethodDecl, Node, Operation, Primitive, Simplify, Stmt, StructDecl, StructField, TypeExpr,
+    UnaryOp, Union, AST,
+};
+use fluxc_errors::ErrorKind;
+
+use crate::{alias::Aliases, cast::conversion, lower::erase};
+
+/// Type alias for the IDs of type variables.
+pub type TypeVar = usize;
+
+/// The type of an expression, as seen by the inference engine.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Ty {
+    /// A type that has not yet been inferred.
+    Var(TypeVar),
+    /// A known type.
+    Known(TypeExpr),
+    /// An array type, whose elements may not yet be inferred.
+    Array(Box<Ty>, Option<usize>),
+    /// A function type, taking the given parameters and returning a value of
+    /// the given type.
+    Func(Vec<Ty>, Box<Ty>),
+    /// An instance of a generic struct or class, whose type arguments may not
+    /// yet be inferred.
+    Generic(Ident, Vec<Ty>),
+}
+
+impl Ty {
+    /// Create a known primitive type.
+    pub fn primitive(primitive: Primitive) -> Ty {
+        Ty::Known(TypeExpr::Primitive(primitive))
+    }
+
+    /// Returns true if the given type variable occurs in this type.
+    fn occurs(&self, var: TypeVar) -> bool {
+        match self {
+            Ty::Var(v) => *v == var,
+            Ty::Known(_) => false,
+            Ty::Array(elem, _) => elem.occurs(var),
+            Ty::Func(params, ret) => params.iter().any(|p| p.occurs(var)) || ret.occurs(var),
+            Ty::Generic(_, args) => args.iter().any(|arg| arg.occurs(var)),
+        }
+    }
+
+    /// Collect the type variables that occur in this type.
+    fn vars(&self, vars: &mut HashSet<TypeVar>) {
+        match self {
+            Ty::Var(v) => {
+                vars.insert(*v);
+            }
+            Ty::Known(_) => (),
+            Ty::Array(elem, _) => elem.vars(vars),
+            Ty::Func(params, ret) => {
+                params.iter().for_each(|p| p.vars(vars));
+                ret.vars(vars);
+            }
+            Ty::Generic(_, args) => args.iter().for_each(|arg| arg.vars(vars)),
+        }
+    }
+
+    /// Convert this type into a type expression. Unresolved type variables,
+    /// which cannot yet be expressed, become `TypeExpr::Infer`.
+    pub fn to_type_expr(&self) -> TypeExpr {
+        match self {
+            Ty::Known(ty) => ty.clone(),
+            Ty::Array(elem, len) => {
+                TypeExpr::Operation(Operation::Array(elem.to_type_expr().into(), *len))
+            }
+            Ty::Generic(name, args) => {
+                TypeExpr::Generic(name.clone(), args.iter().map(Ty::to_type_expr).collect())
+            }
+            Ty::Func(params, ret) => TypeExpr::Function(
+                params.iter().map(Ty::to_type_expr).collect(),
+                ret.to_type_expr().into(),
+            ),
+            Ty::Var(_) => TypeExpr::Infer,
+        }
+    }
+
+    /// Describe this type for use in diagnostics.
+    fn describe(&self) -> String {
+        match self {
+            Ty::Var(_) => "_".into(),
+            Ty::Known(ty) => ty.to_string(),
+            Ty::Array(elem, Some(len)) => format!("{}[{}]", elem.describe(), len),
+            Ty::Array(elem, None) => format!("{}[]", elem.describe()),
+            Ty::Func(params, ret) => format!(
+                "({}) -> {}",
+                params.iter().map(Ty::describe).collect::<Vec<_>>().join(", "),
+                ret.describe()
+            ),
+            Ty::Generic(name, args) => format!(
+                "{}<{}>",
+                name,
+                args.iter().map(Ty::describe).collect::<Vec<_>>().join(", ")
+            ),
+        }
+    }
+}
+
+/// A type that may be generic over some of its type variables.
+#[derive(Debug, Clone)]
+pub struct Scheme {
+    /// The type variables this scheme is generic over.
+    pub vars: Vec<TypeVar>,
+    /// The type of this scheme.
+    pub ty: Ty,
+}
+
+impl Scheme {
+    /// Create a scheme that is not generic over any type variables.
+    pub fn mono(ty: Ty) -> Self {
+        Scheme { vars: vec![], ty }
+    }
+}
+
+/// A constraint between two types.
+#[derive(Debug, Clone)]
+pub enum Constraint {
+    /// The two types must be equal.
+    Eq(Ty, Ty, Range<usize>),
+    /// Values of the first type must be assignable to the second type.
+    Assignable(Ty, Ty, Range<usize>),
+    /// The type must satisfy the bound of the named type parameter. This is
+    /// only checked once the type is fully known.
+    Bound(Ty, TypeExpr, Ident, Range<usize>),
+}
+
+/// The literals whose type a type variable stands in for. Number literals
+/// take on the numeric type they are used as, so long as they are values of
+/// it, and are otherwise `int` or `float`. Other literals keep their literal
+/// type where a literal type is expected, such as `"x"` for `keyof P`, and
+/// are otherwise widened to their base type.
+#[derive(Debug, Clone)]
+enum Literals {
+    /// Integer literals, between the given smallest and largest values.
+    Int(i64, i64),
+    /// Floating-point literals.
+    Float,
+    /// String, char or boolean literals, of the given literal type or union
+    /// of literal types.
+    Value(TypeExpr),
+}
+
+impl Literals {
+    /// Return the type of the literals when they are not used as any
+    /// particular numeric or literal type.
+    fn default_type(&self) -> TypeExpr {
+        match self {
+            Literals::Int(..) => TypeExpr::Primitive(Primitive::Int),
+            Literals::Float => TypeExpr::Primitive(Primitive::Float),
+            Literals::Value(ty) => erase(ty),
+        }
+    }
+
+    /// Return the type of the literals themselves.
+    fn ty(&self) -> TypeExpr {
+        match self {
+            Literals::Value(ty) => ty.clone(),
+            literal => literal.default_type(),
+        }
+    }
+
+    /// Combine the literals with those of another type variable, if both are
+    /// literals of the same kind.
+    fn merge(self, other: Literals) -> Option<Literals> {
+        match (self, other) {
+            (Literals::Int(a, b), Literals::Int(c, d)) => Some(Literals::Int(a.min(c), b.max(d))),
+            (Literals::Float, Literals::Float) => Some(Literals::Float),
+            (Literals::Value(a), Literals::Value(b)) if erase(&a) == erase(&b) => {
+                Some(Literals::Value(Union::from_members(vec![a, b]).simplify()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns true if the given type is a numeric type of the same kind as
+    /// the literals, regardless of whether they are within its range, or the
+    /// base type of other literals.
+    fn accepts(&self, ty: &TypeExpr) -> bool {
+        match (self, ty) {
+            (Literals::Int(..), TypeExpr::Primitive(Primitive::Int))
+            | (Literals::Float, TypeExpr::Primitive(Primitive::Float)) => true,
+            (Literals::Value(literal), ty) => &erase(literal) == ty,
+            (literal, TypeExpr::Primitive(Primitive::Numeric(numeric))) => {
+                matches!(literal, Literals::Float) == numeric.is_float()
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns true if every literal is a value of the given type. A single
+    /// integer value is also a value of its literal type, such as `1` of
+    /// `1 | 2`.
+    fn fits(&self, ty: &TypeExpr) -> bool {
+        match (self, ty) {
+            (Literals::Int(min, max), ty)
+                if min == max
+                    && TypeExpr::Primitive(Primitive::IntLiteral(*min)).extends(ty).is_true() =>
+            {
+                true
+            }
+            (Literals::Int(min, max), TypeExpr::Primitive(Primitive::Numeric(numeric))) => {
+                numeric.range().is_some_and(|(lo, hi)| lo <= *min as i128 && *max as i128 <= hi)
+            }
+            (Literals::Value(literal), ty) => literal.extends(ty).is_true(),
+            (literal, ty) => literal.accepts(ty),
+        }
+    }
+}
+
+/// A refinement of the type of a variable, known wherever a condition does or
+/// does not hold.
+#[derive(Debug, Clone)]
+enum Refinement {
+    /// The variable is equal to a value of the given literal type.
+    Is(Ident, TypeExpr),
+    /// The variable is not equal to a value of the given literal type.
+    IsNot(Ident, TypeExpr),
+}
+
+/// An error raised during type inference.
+#[derive(Debug, Clone)]
+pub struct TypeError {
+    /// The error kind.
+    pub kind: ErrorKind,
+    /// The span of the source code that caused this error.
+    pub span: Range<usize>,
+}
+
+impl TypeError {
+    /// Create a new type error.
+    pub fn new(span: Range<usize>, kind: ErrorKind) -> Self {
+        TypeError { kind, span }
+    }
+}
+
+/// The type inference engine.
+#[derive(Debug, Default)]
+pub struct Inference {
+    /// The next type variable.
+    next_var: TypeVar,
+    /// The types that type variables have been resolved to.
+    substitution: HashMap<TypeVar, Ty>,
+    /// Constraints that have not yet been solved.
+    constraints: Vec<Constraint>,
+    /// The scopes of values, innermost last.
+    scopes: Vec<HashMap<Ident, Scheme>>,
+    /// The declared type aliases.
+    aliases: Aliases,
+    /// The type variables standing in for the type parameters in scope,
+    /// innermost last.
+    type_params: Vec<HashMap<Ident, Ty>>,
+    /// The names and bounds of the type parameters that type variables stand
+    /// in for.
+    params: HashMap<TypeVar, (Ident, Option<TypeExpr>)>,
+    /// The fields of declared structs and classes.
+    structs: HashMap<Ident, Vec<(Ident, TypeExpr)>>,
+    /// The type parameters of generic structs and classes.
+    generics: HashMap<Ident, Vec<Node<GenericParam>>>,
+    /// The type variables standing in for the type parameters of generic
+    /// classes in the signatures of their methods.
+    class_params: HashMap<Ident, Vec<Ty>>,
+    /// The types of methods, keyed by the type they are declared on.
+    methods: HashMap<(Ident, Ident), Scheme>,
+    /// The methods of classes that take no receiver, which are called on the
+    /// class itself.
+    static_methods: HashSet<(Ident, Ident)>,
+    /// The declared interfaces, from which implementations inherit.
+    interfaces: HashMap<Ident, InterfaceDecl>,
+    /// The interfaces implemented by each type, keyed by the type and the
+    /// interface. Values of a type may be assigned to the interfaces it
+    /// implements.
+    implementations: HashSet<(Ident, Ident)>,
+    /// The return types of the functions being inferred, innermost last.
+    returns: Vec<Ty>,
+    /// The types assigned to each expression.
+    exprs: Vec<(Range<usize>, Ty)>,
+    /// The literals whose types unbound type variables stand in for.
+    literals: HashMap<TypeVar, Literals>,
+    /// The declared types of narrowed variables, keyed by the index of the
+    /// scope binding the narrowed type.
+    declared: HashMap<(usize, Ident), Ty>,
+}
+
+impl Inference {
+    /// Create a new inference engine with an empty global scope, in which
+    /// only the standard error type is declared.
+    pub fn new() -> Self {
+        let mut inference = Inference { scopes: vec![HashMap::new()], ..Default::default() };
+        let error = StructDecl::error();
+        inference.declare_struct(&error.ident.value, &error.generics, &error.fields);
+        inference
+    }
+
+    /// Create a fresh type variable.
+    pub fn fresh(&mut self) -> Ty {
+        self.next_var += 1;
+        Ty::Var(self.next_var - 1)
+    }
+
+    /// Convert a type expression into a type, replacing `TypeExpr::Infer` with
+    /// fresh type variables, references to type parameters with the type
+    /// variables standing in for them, and references to type aliases with
+    /// the types they refer to.
+    pub fn lower(&mut self, ty: &TypeExpr) -> Ty {
+        match ty {
+            TypeExpr::Infer => self.fresh(),
+            TypeExpr::Operation(Operation::Array(elem, len)) => {
+                Ty::Array(self.lower(elem).into(), *len)
+            }
+            TypeExpr::Primitive(Primitive::Ref(name)) if self.type_param(name).is_some() => {
+                self.type_param(name).unwrap()
+            }
+            TypeExpr::Primitive(Primitive::Ref(name)) | TypeExpr::Generic(name, _)
+                if self.aliases.contains(name) =>
+            {
+                let ty = self.aliases.resolve(ty);
+                self.lower(&ty)
+            }
+            TypeExpr::Generic(name, args) => {
+                Ty::Generic(name.clone(), args.iter().map(|arg| self.lower(arg)).collect())
+            }
+            TypeExpr::Function(params, ret) => Ty::Func(
+                params.iter().map(|param| self.lower(param)).collect(),
+                self.lower(ret).into(),
+            ),
+            ty => Ty::Known(self.resolve_keys(&self.aliases.resolve(&self.erase_params(ty)))),
+        }
+    }
+
+    /// Replace the keys of structs and classes in the given type with the
+    /// union of the names of their fields.
+    fn resolve_keys(&self, ty: &TypeExpr) -> TypeExpr {
+        ty.replace(&|ty| match ty {
+            TypeExpr::Operation(Operation::KeyOf(inner)) => match self.resolve_keys(inner) {
+                TypeExpr::Primitive(Primitive::Ref(name)) | TypeExpr::Generic(name, _)
+                    if self.structs.contains_key(&name) =>
+                {
+                    Some(Union::from_members(
+                        self.structs[&name]
+                            .iter()
+                            .map(|(field, _)| Primitive::StringLiteral(field.clone()).into())
+                            .collect(),
+                    ))
+                }
+                inner => Some(TypeExpr::Operation(Operation::KeyOf(inner.into()))),
+            },
+            _ => None,
+        })
+    }
+
+    /// Return the type variable standing in for the type parameter with the
+    /// given name, if one is in scope.
+    fn type_param(&self, name: &str) -> Option<Ty> {
+        self.type_params.iter().rev().find_map(|scope| scope.get(name)).cloned()
+    }
+
+    /// Replace the type parameters nested inside the given type, which known
+    /// types cannot hold type variables for, with what is known of them: the
+    /// type they have been inferred as, or otherwise their bound.
+    fn erase_params(&self, ty: &TypeExpr) -> TypeExpr {
+        let mut args = HashMap::new();
+        for (name, ty) in self.type_params.iter().flatten() {
+            let erased = match self.resolve(ty) {
+                Ty::Known(ty) => ty,
+                Ty::Var(v) => match self.params.get(&v) {
+                    Some((_, Some(bound))) => bound.clone(),
+                    _ => TypeExpr::Primitive(Primitive::Any),
+                },
+                ty => ty.to_type_expr(),
+            };
+            args.insert(name.clone(), erased);
+        }
+        match args.is_empty() {
+            true => ty.clone(),
+            false => ty.substitute(&args),
+        }
+    }
+
+    /// Bring the given type parameters into scope, standing each in for a
+    /// fresh type variable. Returns the type variables, in order.
+    fn enter_generics(&mut self, generics: &[Node<GenericParam>]) -> Vec<Ty> {
+        let vars: Vec<_> = generics.iter().map(|_| self.fresh()).collect();
+        self.type_params.push(
+            generics
+                .iter()
+                .map(|param| param.value.ident.value.clone())
+                .zip(vars.clone())
+                .collect(),
+        );
+        // bounds may refer to the other type parameters
+        for (param, var) in generics.iter().zip(&vars) {
+            if let Ty::Var(v) = var {
+                let bound = param
+                    .value
+                    .bound
+                    .as_ref()
+                    .map(|bound| self.aliases.resolve(&self.erase_params(&bound.value)));
+                self.params.insert(*v, (param.value.ident.value.clone(), bound));
+            }
+        }
+        vars
+    }
+
+    /// Remove the innermost type parameters from scope.
+    fn exit_generics(&mut self) {
+        self.type_params.pop();
+    }
+
+    /// Constrain the types instantiating the given bounded type variables to
+    /// satisfy their bounds.
+    fn check_bounds(&mut self, instances: &[(TypeVar, Ty)], span: &Range<usize>) {
+        for (var, ty) in instances {
+            if let Some((name, Some(bound))) = self.params.get(var).cloned() {
+                self.constraints.push(Constraint::Bound(ty.clone(), bound, name, span.clone()));
+            }
+        }
+    }
+
+    /// Apply the current substitution to the given type.
+    pub fn resolve(&self, ty: &Ty) -> Ty {
+        match ty {
+            Ty::Var(v) => match self.substitution.get(v) {
+                Some(ty) => self.resolve(ty),
+                None => ty.clone(),
+            },
+            Ty::Known(_) => ty.clone(),
+            Ty::Array(elem, len) => Ty::Array(self.resolve(elem).into(), *len),
+            Ty::Func(params, ret) => {
+                Ty::Func(params.iter().map(|p| self.resolve(p)).collect(), self.resolve(ret).into())
+            }
+            Ty::Generic(name, args) => {
+                Ty::Generic(name.clone(), args.iter().map(|arg| self.resolve(arg)).collect())
+            }
+        }
+    }
+
+    /// Return the inferred type of the expression with the given span.
+    pub fn type_of(&self, span: &Range<usize>) -> Option<TypeExpr> {
+        self.exprs.iter().rev().find(|(s, _)| s == span).map(|(_, ty)| self.express(ty))
+    }
+
+    /// Return the span and inferred type of the innermost expression
+    /// containing the given offset into the source.
+    pub fn type_at(&self, offset: usize) -> Option<(Range<usize>, TypeExpr)> {
+        self.exprs
+            .iter()
+            .rev()
+            .filter(|(span, _)| span.contains(&offset))
+            .min_by_key(|(span, _)| span.len())
+            .map(|(span, ty)| (span.clone(), self.express(ty)))
+    }
+
+    /// Convert the given type into a type expression, once resolved. Type
+    /// variables standing in for type parameters that remain unresolved, such
+    /// as within the body of a generic function, are expressed as references
+    /// to the type parameter.
+    fn express(&self, ty: &Ty) -> TypeExpr {
+        let params = self
+            .params
+            .iter()
+            .map(|(v, (name, _))| (*v, Ty::primitive(Primitive::Ref(name.clone()))))
+            .collect();
+        replace(&self.resolve(ty), &params).to_type_expr()
+    }
+
+    /// Return the inferred type of the value with the given name.
+    pub fn lookup(&self, name: &str) -> Option<Ty> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name))
+            .map(|scheme| self.resolve(&scheme.ty))
+    }
+
+    /// Bind a value in the innermost scope.
+    pub fn bind<S: ToString>(&mut self, name: S, scheme: Scheme) {
+        self.scopes.last_mut().expect("no scope").insert(name.to_string(), scheme);
+    }
+
+    /// Enter a new scope.
+    pub fn enter_scope(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    /// Exit the innermost scope.
+    pub fn exit_scope(&mut self) {
+        self.scopes.pop();
+        let depth = self.scopes.len();
+        self.declared.retain(|(index, _), _| *index < depth);
+    }
+
+    /// Enter a scope in which the given refinements hold, binding the
+    /// variables they refer to to their narrowed types. Returns true if any
+    /// variable is narrowed to `never`, in which case the scope can never be
+    /// reached.
+    fn narrow(&mut self, refinements: Vec<Refinement>) -> Result<bool, TypeError> {
+        // the types of the variables must be known to narrow them
+        self.solve()?;
+        self.enter_scope();
+        let mut unreachable = false;
+        for refinement in refinements {
+            let (name, literal) = match &refinement {
+                Refinement::Is(name, literal) | Refinement::IsNot(name, literal) => (name, literal),
+            };
+            let Some(Ty::Known(ty)) = self.lookup(name) else { continue };
+            let narrowed = match refinement {
+                Refinement::Is(..) => narrow_to(&ty, literal),
+                Refinement::IsNot(..) => ty.exclude(literal),
+            };
+            unreachable |= narrowed == TypeExpr::Primitive(Primitive::Never);
+            let declared = self.declared_type(name).unwrap_or(Ty::Known(ty));
+            self.declared.insert((self.scopes.len() - 1, name.clone()), declared);
+            self.bind(name, Scheme::mono(Ty::Known(narrowed)));
+        }
+        Ok(unreachable)
+    }
+
+    /// Return the declared type of the variable with the given name, ignoring
+    /// any narrowing of its type.
+    fn declared_type(&self, name: &str) -> Option<Ty> {
+        let index = self.scopes.iter().rposition(|scope| scope.contains_key(name))?;
+        match self.declared.get(&(index, name.to_string())) {
+            Some(ty) => Some(ty.clone()),
+            None => self.scopes[index].get(name).map(|scheme| scheme.ty.clone()),
+        }
+    }
+
+    /// Widen a narrowed variable back to its declared type, as it may be
+    /// assigned any value of that type. Returns the declared type, if the
+    /// variable was narrowed.
+    fn widen(&mut self, name: &str) -> Option<Ty> {
+        let narrowed = self
+            .declared
+            .keys()
+            .filter(|(_, n)| n == name)
+            .map(|(index, _)| *index)
+            .collect::<Vec<_>>();
+        let mut declared = None;
+        for index in narrowed {
+            let ty = self.declared.remove(&(index, name.to_string()))?;
+            self.scopes[index].insert(name.to_string(), Scheme::mono(ty.clone()));
+            declared = Some(ty);
+        }
+        declared
+    }
+
+    /// Replace the type variables a scheme is generic over with fresh ones.
+    /// Type variables standing in for bounded type parameters are checked
+    /// against their bounds once the types replacing them are known.
+    fn instantiate(&mut self, scheme: &Scheme, span: &Range<usize>) -> Ty {
+        let vars = scheme.vars.iter().map(|v| (*v, self.fresh())).collect::<Vec<_>>();
+        self.check_bounds(&vars, span);
+        replace(&scheme.ty, &vars.into_iter().collect())
+    }
+
+    /// Generalise the given type over the type variables that do not occur
+    /// in any enclosing scope.
+    fn generalize(&self, ty: &Ty) -> Scheme {
+        let ty = self.resolve(ty);
+        let mut env = HashSet::new();
+        for scheme in self.scopes.iter().flat_map(|scope| scope.values()) {
+            let mut vars = HashSet::new();
+            self.resolve(&scheme.ty).vars(&mut vars);
+            env.extend(vars.into_iter().filter(|v| !scheme.vars.contains(v)));
+        }
+        let mut vars = HashSet::new();
+        ty.vars(&mut vars);
+        let mut vars = vars.into_iter().filter(|v| !env.contains(v)).collect::<Vec<_>>();
+        vars.sort_unstable();
+        Scheme { vars, ty }
+    }
+
+    /// Constrain the two types to be equal.
+    fn equate(&mut self, a: Ty, b: Ty, span: &Range<usize>) {
+        self.constraints.push(Constraint::Eq(a, b, span.clone()));
+    }
+
+    /// Constrain the two types to be comparable. Known types are comparable
+    /// if they have values in common, such as `int | string` and `int`.
+    fn compare(&mut self, a: Ty, b: Ty, span: &Range<usize>) -> Result<(), TypeError> {
+        self.solve()?;
+        match (self.resolve(&a), self.resolve(&b)) {
+            (Ty::Known(known), _) => self.settle(&b, &known, span)?,
+            (_, Ty::Known(known)) => self.settle(&a, &known, span)?,
+            _ => (),
+        }
+        match (self.resolve(&a), self.resolve(&b)) {
+            (Ty::Known(x), Ty::Known(y)) => match x.intersect(&y) {
+                TypeExpr::Primitive(Primitive::Never) => {
+                    Err(Self::mismatch(&Ty::Known(x), &Ty::Known(y), span))
+                }
+                _ => Ok(()),
+            },
+            _ => {
+                self.equate(a, b, span);
+                Ok(())
+            }
+        }
+    }
+
+    /// Constrain values of type `actual` to be assignable to `expected`.
+    fn assign(&mut self, actual: Ty, expected: Ty, span: &Range<usize>) {
+        self.constraints.push(Constraint::Assignable(actual, expected, span.clone()));
+    }
+
+    /// Constrain a function of type `callee` to be callable with arguments of
+    /// the given types. Each argument must be assignable to its parameter, so
+    /// may be a member of a union parameter or implement an interface
+    /// parameter. Returns the type of the value the call produces.
+    fn call(
+        &mut self,
+        callee: Ty,
+        args: &[Node<Expr>],
+        span: &Range<usize>,
+    ) -> Result<Ty, TypeError> {
+        let params: Vec<_> = args.iter().map(|_| self.fresh()).collect();
+        let ret = self.fresh();
+        self.equate(callee, Ty::Func(params.clone(), ret.clone().into()), span);
+        for (arg, param) in args.iter().zip(params) {
+            let ty = self.infer_expr(arg)?;
+            self.assign(ty, param, &arg.span);
+        }
+        Ok(ret)
+    }
+
+    /// Solve all outstanding constraints.
+    pub fn solve(&mut self) -> Result<(), TypeError> {
+        let mut deferred = vec![];
+        for constraint in std::mem::take(&mut self.constraints) {
+            match constraint {
+                Constraint::Eq(a, b, span) => self.unify(&a, &b, &span)?,
+                Constraint::Assignable(actual, expected, span) => {
+                    self.unify_assignable(&actual, &expected, &span)?
+                }
+                Constraint::Bound(ty, bound, name, span) => {
+                    let mut vars = HashSet::new();
+                    self.resolve(&ty).vars(&mut vars);
+                    // bounds are checked once the type is known
+                    if !vars.is_empty() {
+                        deferred.push(Constraint::Bound(ty, bound, name, span));
+                        continue;
+                    }
+                    let actual = self.resolve(&ty).to_type_expr();
+                    // interface bounds are satisfied by their implementations
+                    if !actual.extends(&bound).is_true() && !self.implements(&actual, &bound) {
+                        return Err(TypeError::new(
+                            span,
+                            ErrorKind::E0217(actual.to_string(), format!("{name} extends {bound}")),
+                        ));
+                    }
+                }
+            }
+        }
+        self.constraints.extend(deferred);
+        Ok(())
+    }
+
+    /// Bind the given type variable to a type.
+    fn bind_var(&mut self, var: TypeVar, ty: &Ty, span: &Range<usize>) -> Result<(), TypeError> {
+        if ty == &Ty::Var(var) {
+            return Ok(());
+        }
+        if ty.occurs(var) {
+            return Err(TypeError::new(span.clone(), ErrorKind::E0206(ty.describe())));
+        }
+        // literals may only take on types they are values of
+        if let Some(literal) = self.literals.remove(&var) {
+            let literal_ty = Ty::Known(literal.ty());
+            match ty {
+                Ty::Var(other) => {
+                    let merged = match self.literals.get(other).cloned() {
+                        Some(other) => literal
+                            .merge(other)
+                            .ok_or_else(|| Self::mismatch(ty, &literal_ty, span))?,
+                        None => literal,
+                    };
+                    self.literals.insert(*other, merged);
+                }
+                Ty::Known(known) if literal.fits(known) => (),
+                Ty::Known(TypeExpr::Primitive(Primitive::Numeric(numeric)))
+                    if !numeric.is_float() =>
+                {
+                    let Literals::Int(min, max) = literal else {
+                        return Err(Self::mismatch(ty, &literal_ty, span));
+                    };
+                    let value = if numeric.range().is_some_and(|(lo, _)| (min as i128) < lo) {
+                        min
+                    } else {
+                        max
+                    };
+                    return Err(TypeError::new(
+                        span.clone(),
+                        ErrorKind::E0226(value.to_string(), numeric.name().to_string()),
+                    ));
+                }
+                _ => return Err(Self::mismatch(ty, &literal_ty, span)),
+            }
+        }
+        self.substitution.insert(var, ty.clone());
+        Ok(())
+    }
+
+    /// Create a type variable standing in for the type of the given literals.
+    fn literal(&mut self, literal: Literals) -> Ty {
+        let ty = self.fresh();
+        if let Ty::Var(var) = ty {
+            self.literals.insert(var, literal);
+        }
+        ty
+    }
+
+    /// Settle the type of the literals the given type stands in for, if it
+    /// does, where their values are expected to be of type `expected`. The
+    /// literals take on the member of `expected` they are values of, if there
+    /// is exactly one, or else their own type if they are values of several,
+    /// or else the only member of the same kind, which reports number
+    /// literals as out of range. Otherwise they take on their default type.
+    fn settle(
+        &mut self,
+        ty: &Ty,
+        expected: &TypeExpr,
+        span: &Range<usize>,
+    ) -> Result<(), TypeError> {
+        let Ty::Var(var) = self.resolve(ty) else { return Ok(()) };
+        let Some(literal) = self.literals.get(&var).cloned() else { return Ok(()) };
+        let members = members(expected);
+        let fitting = members.iter().filter(|ty| literal.fits(ty)).collect::<Vec<_>>();
+        let accepting = members.iter().filter(|ty| literal.accepts(ty)).collect::<Vec<_>>();
+        let settled = match (&fitting[..], &accepting[..]) {
+            ([ty], _) => (*ty).clone(),
+            ([], _) if literal.fits(expected) => literal.ty(),
+            ([], [ty]) => (*ty).clone(),
+            _ => literal.default_type(),
+        };
+        self.bind_var(var, &Ty::Known(settled), span)
+    }
+
+    /// Settle the types of the literals not used as any particular numeric or
+    /// literal type to their default types.
+    fn default_literals(&mut self) {
+        for (var, literal) in std::mem::take(&mut self.literals) {
+            self.substitution.insert(var, Ty::Known(literal.default_type()));
+        }
+    }
+
+    /// Constrain the operands of an arithmetic operation to be of the same
+    /// type. Numbers are never implicitly converted between numeric types, so
+    /// operands of different numeric types must be cast to a common type.
+    fn arithmetic(&mut self, lhs: Ty, rhs: Ty, span: &Range<usize>) -> Result<(), TypeError> {
+        self.solve()?;
+        match (self.resolve(&lhs), self.resolve(&rhs)) {
+            (Ty::Known(a), Ty::Known(b)) if a != b && is_numeric(&a) && is_numeric(&b) => {
+                Err(TypeError::new(span.clone(), ErrorKind::E0225(a.to_string(), b.to_string())))
+            }
+            _ => {
+                self.equate(lhs, rhs, span);
+                Ok(())
+            }
+        }
+    }
+
+    /// Require values of the given type to be present. Values of optional
+    /// types may be unit, so must be narrowed before they are used.
+    fn present(&self, ty: &Ty, span: &Range<usize>) -> Result<(), TypeError> {
+        match self.resolve(ty) {
+            Ty::Known(ty) if is_optional(&ty) => {
+                Err(TypeError::new(span.clone(), ErrorKind::E0222(ty.to_string())))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Describe the type of the value an expression of the given type
+    /// produces, unless it is unit, never, or not yet inferred.
+    fn produced(&self, ty: &Ty) -> Option<String> {
+        match self.resolve(ty) {
+            Ty::Var(var) => {
+                self.literals.get(&var).map(|literal| literal.default_type().to_string())
+            }
+            Ty::Known(TypeExpr::Primitive(Primitive::Unit | Primitive::Never)) => None,
+            ty => Some(ty.describe()),
+        }
+    }
+
+    /// Create a type mismatch error.
+    fn mismatch(expected: &Ty, actual: &Ty, span: &Range<usize>) -> TypeError {
+        TypeError::new(span.clone(), ErrorKind::E0204(expected.describe(), actual.describe()))
+    }
+
+    /// Unify two types, such that they are equal.
+    fn unify(&mut self, a: &Ty, b: &Ty, span: &Range<usize>) -> Result<(), TypeError> {
+        match (self.resolve(a), self.resolve(b)) {
+            (Ty::Var(v), ty) | (ty, Ty::Var(v)) => self.bind_var(v, &ty, span),
+            (Ty::Array(a, n), Ty::Array(b, m)) => {
+                if matches!((n, m), (Some(n), Some(m)) if n != m) {
+                    return Err(Self::mismatch(&Ty::Array(a, n), &Ty::Array(b, m), span));
+                }
+                self.unify(&a, &b, span)
+            }
+            (Ty::Func(a_params, a_ret), Ty::Func(b_params, b_ret))
+                if a_params.len() == b_params.len() =>
+            {
+                for (a, b) in a_params.iter().zip(b_params.iter()) {
+                    self.unify(a, b, span)?;
+                }
+                self.unify(&a_ret, &b_ret, span)
+            }
+            (Ty::Generic(a, a_args), Ty::Generic(b, b_args))
+                if a == b && a_args.len() == b_args.len() =>
+            {
+                for (a, b) in a_args.iter().zip(b_args.iter()) {
+                    self.unify(a, b, span)?;
+                }
+                Ok(())
+            }
+            (Ty::Known(a), Ty::Known(b)) if a == b => Ok(()),
+            (a, b) => Err(Self::mismatch(&a, &b, span)),
+        }
+    }
+
+    /// Unify two types, such that values of type `actual` are assignable to
+    /// `expected`. Where either type is not yet known, this falls back to
+    /// requiring the types be equal.
+    fn unify_assignable(
+        &mut self,
+        actual: &Ty,
+        expected: &Ty,
+        span: &Range<usize>,
+    ) -> Result<(), TypeError> {
+        if let Ty::Known(expected) = self.resolve(expected) {
+            self.settle(actual, &expected, span)?;
+        }
+        match (self.resolve(actual), self.resolve(expected)) {
+            (Ty::Known(a), Ty::Known(b)) => {
+                match a.extends(&b).is_true() || self.implements(&a, &b) {
+                    true => Ok(()),
+                    false => Err(Self::mismatch(&Ty::Known(b), &Ty::Known(a), span)),
+                }
+            }
+            (Ty::Array(a, n), Ty::Array(b, m)) => {
+                if matches!((n, m), (Some(n), Some(m)) if n != m) {
+                    return Err(Self::mismatch(&Ty::Array(b, m), &Ty::Array(a, n), span));
+                }
+                self.unify_assignable(&a, &b, span)
+            }
+            (actual, expected) => self.unify(&expected, &actual, span),
+        }
+    }
+
+    /// Returns true if values of the type `ty` may be assigned to `interface`
+    /// because the type implements it.
+    fn implements(&self, ty: &TypeExpr, interface: &TypeExpr) -> bool {
+        match (ty, interface) {
+            (
+                TypeExpr::Primitive(Primitive::Ref(ty)),
+                TypeExpr::Primitive(Primitive::Ref(interface)),
+            ) => self.implementations.contains(&(ty.clone(), interface.clone())),
+            _ => false,
+        }
+    }
+
+    /// Record the type of the expression with the given span.
+    fn record(&mut self, span: &Range<usize>, ty: Ty) -> Ty {
+        self.exprs.push((span.clone(), ty.clone()));
+        ty
+    }
+
+    /// Infer the types of every statement in the given AST.
+    pub fn infer_ast(&mut self, ast: &AST) -> Result<(), TypeError> {
+        self.declare_all(&ast.stmts);
+        for stmt in &ast.stmts {
+            self.infer_stmt(stmt)?;
+        }
+        self.solve()?;
+        self.default_literals();
+        Ok(())
+    }
+
+    /// Declare the type aliases, structs, classes and interfaces in the given
+    /// statements, such that they can be referred to before their declaration.
+    fn declare_all(&mut self, stmts: &[Node<Stmt>]) {
+        for stmt in stmts {
+            match &stmt.value {
+                Stmt::TypeDeclaration(decl) => self.aliases.declare_generic(
+                    &decl.value.ident.value,
+                    decl.value
+                        .generics
+                        .iter()
+                        .map(|param| param.value.ident.value.clone())
+                        .collect(),
+                    decl.value.value.value.clone(),
+                ),
+                Stmt::StructDecl(decl) => self.declare_struct(
+                    &decl.value.ident.value,
+                    &decl.value.generics,
+                    &decl.value.fields,
+                ),
+                Stmt::ClassDecl(decl) => self.declare_class(&decl.value),
+                Stmt::InterfaceDecl(decl) => self.declare_interface(&decl.value),
+                _ => (),
+            }
+        }
+        // implementations inherit from interfaces, so are declared last
+        for stmt in stmts {
+            if let Stmt::ImplDecl(decl) = &stmt.value {
+                self.declare_impl(&decl.value);
+            }
+        }
+    }
+
+    /// Declare the fields of a struct, which may refer to its type
+    /// parameters.
+    fn declare_struct(
+        &mut self,
+        name: &str,
+        generics: &[Node<GenericParam>],
+        fields: &[Node<StructField>],
+    ) {
+        let fields = fields
+            .iter()
+            .map(|field| (field.value.ident.value.clone(), field.value.ty.value.clone()))
+            .collect();
+        self.structs.insert(name.to_string(), fields);
+        if !generics.is_empty() {
+            self.generics.insert(name.to_string(), generics.to_vec());
+        }
+    }
+
+    /// Return the type of values of the struct or class with the given name,
+    /// whose type parameters are stood in for by the given types.
+    fn struct_type(name: &str, args: Vec<Ty>) -> Ty {
+        match args.is_empty() {
+            true => Ty::primitive(Primitive::Ref(name.to_string())),
+            false => Ty::Generic(name.to_string(), args),
+        }
+    }
+
+    /// Lower the type of a field of the given struct or class, whose type
+    /// parameters are stood in for by the given types.
+    fn field_type(&mut self, name: &str, args: &[Ty], ty: &TypeExpr) -> Ty {
+        let params = self.generics.get(name).cloned().unwrap_or_default();
+        self.type_params.push(
+            params.iter().map(|param| param.value.ident.value.clone()).zip(args.to_vec()).collect(),
+        );
+        let ty = self.lower(ty);
+        self.exit_generics();
+        ty
+    }
+
+    /// Declare a method on the given type.
+    fn declare_method(&mut self, ty: &str, method: &MethodDecl) {
+        let scheme = Scheme::mono(self.signature(&method.params, &method.ret_ty.value));
+        let key = (ty.to_string(), method.ident.value.clone());
+        if method.receiver.is_none() {
+            self.static_methods.insert(key.clone());
+        }
+        self.methods.insert(key, scheme);
+    }
+
+    /// Declare the fields and methods of a class. The signatures of methods
+    /// of generic classes refer to the class's type parameters through type
+    /// variables, which are replaced by the type arguments of the receiver
+    /// whenever a method is called.
+    fn declare_class(&mut self, decl: &ClassDecl) {
+        self.declare_struct(&decl.ident.value, &decl.generics, &decl.fields);
+        let params = self.enter_generics(&decl.generics);
+        for method in &decl.methods {
+            self.declare_method(&decl.ident.value, &method.value);
+        }
+        self.exit_generics();
+        if !params.is_empty() {
+            self.class_params.insert(decl.ident.value.clone(), params);
+        }
+    }
+
+    /// Declare the methods of an interface, such that they can be called on
+    /// values of the interface type.
+    fn declare_interface(&mut self, decl: &InterfaceDecl) {
+        for method in &decl.methods {
+            let ty = self.interface_signature(&method.value, &decl.ident.value);
+            self.methods.insert((decl.ident.value.clone(), method.value.ident.value.clone()), ty);
+        }
+        self.interfaces.insert(decl.ident.value.clone(), decl.clone());
+    }
+
+    /// Compute the type of an interface method on the given type, with `Self`
+    /// parameters replaced by the type.
+    fn interface_signature(&mut self, method: &InterfaceMethod, ty: &str) -> Scheme {
+        let this = TypeExpr::Primitive(Primitive::Ref(ty.to_string()));
+        let params = method
+            .params
+            .iter()
+            .map(|param| match &param.value.ty.value {
+                TypeExpr::Primitive(Primitive::Ref(name)) if name == "Self" => {
+                    Ty::Known(this.clone())
+                }
+                ty => self.lower(ty),
+            })
+            .collect();
+        let ret = self.lower(&method.ret_ty.value);
+        Scheme::mono(Ty::Func(params, ret.into()))
+    }
+
+    /// Declare the methods of an interface implementation, including the
+    /// default methods it inherits.
+    fn declare_impl(&mut self, decl: &ImplDecl) {
+        for method in &decl.methods {
+            self.declare_method(&decl.ty.value, &method.value);
+        }
+        self.implementations.insert((decl.ty.value.clone(), decl.interface.value.clone()));
+        let Some(interface) = self.interfaces.get(&decl.interface.value).cloned() else {
+            return;
+        };
+        for method in &interface.methods {
+            let key = (decl.ty.value.clone(), method.value.ident.value.clone());
+            if !self.methods.contains_key(&key) {
+                let ty = self.interface_signature(&method.value, &decl.ty.value);
+                self.methods.insert(key, ty);
+            }
+        }
+    }
+
+    /// Compute the type of a function with the given parameters and return
+    /// type.
+    fn signature(&mut self, params: &[Node<FuncParam>], ret_ty: &TypeExpr) -> Ty {
+        let params = params.iter().map(|param| self.lower(&param.value.ty.value)).collect();
+        Ty::Func(params, self.lower(ret_ty).into())
+    }
+
+    /// Infer the type of a variable declaration, binding the variable in the
+    /// innermost scope. Returns the type of the variable.
+    pub fn infer_declaration(&mut self, decl: &Declaration) -> Result<TypeExpr, TypeError> {
+        let value = self.infer_expr(&decl.value)?;
+        let ty = match &decl.explicit_ty {
+            Some(explicit) => {
+                let explicit_ty = self.lower(&explicit.value);
+                self.assign(value, explicit_ty.clone(), &decl.value.span);
+                explicit_ty
+            }
+            None => value,
+        };
+        self.bind(&decl.ident.value, Scheme::mono(ty.clone()));
+        self.solve()?;
+        self.default_literals();
+        Ok(self.resolve(&ty).to_type_expr())
+    }
+
+    /// Infer the type of a function declaration, binding the function in the
+    /// innermost scope. The type of the function is generalised over any type
+    /// variables it leaves unresolved.
+    pub fn infer_function(&mut self, decl: &FuncDecl) -> Result<Scheme, TypeError> {
+        let unit = TypeExpr::Primitive(Primitive::Unit);
+        let (ident, params, body, ret_ty) = match decl {
+            FuncDecl::Local { ident, params, body, ret_ty, .. } => {
+                (ident, params, Some(body), &ret_ty.value)
+            }
+            FuncDecl::Export { ident, params, body, ret_ty, .. } => {
+                (ident, params, Some(body), ret_ty.as_ref().map_or(&TypeExpr::Infer, |t| &t.value))
+            }
+            FuncDecl::External { ident, params, ret_ty } => {
+                (ident, params, None, ret_ty.as_ref().map_or(&unit, |t| &t.value))
+            }
+        };
+        // type parameters are generalised along with any other type
+        // variables the function leaves unresolved
+        self.enter_generics(decl.generics());
+        let ty = self.signature(params, ret_ty);
+        // bind the function before inferring its body to allow recursion
+        self.bind(&ident.value, Scheme::mono(ty.clone()));
+        if let (Some(body), Ty::Func(param_tys, ret)) = (body, &ty) {
+            self.enter_scope();
+            for (param, ty) in params.iter().zip(param_tys) {
+                self.bind(&param.value.ident.value, Scheme::mono(ty.clone()));
+            }
+            self.infer_body(body, ret.as_ref().clone())?;
+            self.exit_scope();
+        }
+        self.exit_generics();
+        self.solve()?;
+        self.default_literals();
+        // generalise outside of the function's own binding
+        self.scopes.last_mut().expect("no scope").remove(&ident.value);
+        let scheme = self.generalize(&ty);
+        self.bind(&ident.value, scheme.clone());
+        Ok(scheme)
+    }
+
+    /// Infer the type of a method declared on the given type.
+    pub fn infer_method(&mut self, ty: &str, decl: &MethodDecl) -> Result<(), TypeError> {
+        let signature = match self.methods.get(&(ty.to_string(), decl.ident.value.clone())) {
+            Some(scheme) => scheme.ty.clone(),
+            None => self.signature(&decl.params, &decl.ret_ty.value),
+        };
+        let Ty::Func(params, ret) = signature else { unreachable!() };
+        let class_params = self.class_params.get(ty).cloned().unwrap_or_default();
+        let names = self.generics.get(ty).into_iter().flatten();
+        let scope = names.map(|param| param.value.ident.value.clone()).zip(class_params.clone());
+        self.type_params.push(scope.collect());
+        self.enter_scope();
+        if decl.receiver.is_some() {
+            self.bind("self", Scheme::mono(Self::struct_type(ty, class_params)));
+        }
+        for (param, ty) in decl.params.iter().zip(params) {
+            self.bind(&param.value.ident.value, Scheme::mono(ty));
+        }
+        self.infer_body(&decl.body, *ret)?;
+        self.exit_scope();
+        self.exit_generics();
+        self.solve()?;
+        self.default_literals();
+        Ok(())
+    }
+
+    /// Return the name of the class the receiver of a method call names, if it
+    /// is the name of a class rather than of a value.
+    fn class_receiver(&self, receiver: &Node<Expr>) -> Option<Ident> {
+        let Expr::Ident(name) = &receiver.value else { return None };
+        let is_value = self.scopes.iter().any(|scope| scope.contains_key(&name.value));
+        match !is_value && self.structs.contains_key(&name.value) {
+            true => Some(name.value.clone()),
+            false => None,
+        }
+    }
+
+    /// Infer the type of a call to a method without a receiver on the given
+    /// class, such as `Counter.new ()`. The name of the class is given the
+    /// type of the class, with fresh type arguments if it is generic.
+    fn infer_static_call(
+        &mut self,
+        class: &str,
+        call: &MethodCall,
+        span: &Range<usize>,
+    ) -> Result<Ty, TypeError> {
+        let method = &call.method;
+        let key = (class.to_string(), method.value.clone());
+        let scheme = self.methods.get(&key).cloned().ok_or_else(|| {
+            TypeError::new(
+                method.span.clone(),
+                ErrorKind::E0208(method.value.clone(), class.into()),
+            )
+        })?;
+        if !self.static_methods.contains(&key) {
+            return Err(TypeError::new(
+                method.span.clone(),
+                ErrorKind::E0228(method.value.clone(), class.into()),
+            ));
+        }
+        let mut callee = self.instantiate(&scheme, span);
+        let params = self.class_params.get(class).cloned().unwrap_or_default();
+        let type_args: Vec<_> = params.iter().map(|_| self.fresh()).collect();
+        let vars = params
+            .iter()
+            .zip(&type_args)
+            .filter_map(|(param, arg)| match param {
+                Ty::Var(v) => Some((*v, arg.clone())),
+                _ => None,
+            })
+            .collect();
+        callee = replace(&callee, &vars);
+        self.record(&call.receiver.span, Self::struct_type(class, type_args));
+        self.call(callee, &call.args, span)
+    }
+
+    /// Infer the body of a function with the given return type.
+    fn infer_body(&mut self, body: &Node<Block>, ret: Ty) -> Result<(), TypeError> {
+        self.returns.push(ret);
+        let result = self.infer_block(body);
+        self.returns.pop();
+        result.map(|_| ())
+    }
+
+    /// Infer the type of a statement. Statements that are not expressions have
+    /// the unit type.
+    pub fn infer_stmt(&mut self, stmt: &Node<Stmt>) -> Result<Ty, TypeError> {
+        let unit = Ty::primitive(Primitive::Unit);
+        match &stmt.value {
+            Stmt::Declaration(decl) => {
+                self.infer_declaration(&decl.value)?;
+            }
+            Stmt::FuncDecl(decl) => {
+                self.infer_function(&decl.value)?;
+            }
+            Stmt::ClassDecl(decl) => {
+                for method in &decl.value.methods {
+                    self.infer_method(&decl.value.ident.value, &method.value)?;
+                }
+            }
+            Stmt::ImplDecl(decl) => {
+                for method in &decl.value.methods {
+                    self.infer_method(&decl.value.ty.value, &method.value)?;
+                }
+            }
+            Stmt::Return(expr) => {
+                let ty = self.infer_expr(expr)?;
+                if let Some(ret) = self.returns.last().cloned() {
+                    self.assign(ty, ret, &expr.span);
+                }
+            }
+            Stmt::Break(expr) => {
+                self.infer_expr(expr)?;
+            }
+            Stmt::Expr(expr) => return self.infer_expr(expr),
+            Stmt::TypeDeclaration(_)
+            | Stmt::StructDecl(_)
+            | Stmt::InterfaceDecl(_)
+            | Stmt::Import(_)
+            | Stmt::Export(_) => (),
+        };
+        Ok(unit)
+    }
+
+    /// Infer the type of a block, which is the type of its trailing
+    /// expression, or unit if it has none.
+    pub fn infer_block(&mut self, block: &Node<Block>) -> Result<Ty, TypeError> {
+        self.enter_scope();
+        self.declare_all(&block.value.stmts);
+        let mut ty = Ty::primitive(Primitive::Unit);
+        let mut narrowed = 0;
+        for stmt in &block.value.stmts {
+            ty = self.infer_stmt(stmt)?;
+            // the rest of the block is only reached if none of the conditions
+            // of an `if` whose branches all return or break hold
+            let Stmt::Expr(Node { value: Expr::Conditional(conditional), .. }) = &stmt.value else {
+                continue;
+            };
+            let conditional = &conditional.value;
+            let if_stmts = std::iter::once(&conditional.if_stmt).chain(&conditional.else_ifs);
+            if conditional.else_stmt.is_none()
+                && if_stmts.clone().all(|if_stmt| diverges(&if_stmt.value.block))
+            {
+                let refinements = if_stmts
+                    .flat_map(|if_stmt| refinements(&if_stmt.value.condition.value, false))
+                    .collect();
+                self.narrow(refinements)?;
+                narrowed += 1;
+            }
+        }
+        for _ in 0..narrowed {
+            self.exit_scope();
+        }
+        self.exit_scope();
+        Ok(self.record(&block.span, ty))
+    }
+
+    /// Infer the type of an expression.
+    pub fn infer_expr(&mut self, expr: &Node<Expr>) -> Result<Ty, TypeError> {
+        let ty = self.infer_expr_at(&expr.value, &expr.span)?;
+        Ok(self.record(&expr.span, ty))
+    }
+
+    /// Infer the type of an expression spanning the given range.
+    fn infer_expr_at(&mut self, expr: &Expr, span: &Range<usize>) -> Result<Ty, TypeError> {
+        let unit = Ty::primitive(Primitive::Unit);
+        let bool = Ty::primitive(Primitive::Bool);
+        Ok(match expr {
+            Expr::Literal(literal) => match &literal.value {
+                Literal::Int(int) => self.literal(Literals::Int(*int, *int)),
+                Literal::Float(_) => self.literal(Literals::Float),
+                Literal::Unit => unit,
+                Literal::Array(elems) => {
+                    let elem = self.fresh();
+                    for value in elems {
+                        let ty = self.infer_expr_at(value, &literal.span)?;
+                        self.equate(elem.clone(), ty, &literal.span);
+                    }
+                    Ty::Array(elem.into(), Some(elems.len()))
+                }
+                _ => {
+                    let ty = literal_type(expr).expect("expected a literal type");
+                    self.literal(Literals::Value(ty))
+                }
+            },
+            Expr::Ident(ident) => {
+                let scheme = self
+                    .scopes
+                    .iter()
+                    .rev()
+                    .find_map(|scope| scope.get(&ident.value))
+                    .cloned()
+                    .ok_or_else(|| {
+                        TypeError::new(ident.span.clone(), ErrorKind::E0205(ident.value.clone()))
+                    })?;
+                self.instantiate(&scheme, span)
+            }
+            Expr::BinaryExpr(binary) => {
+                let lhs = self.infer_expr(&binary.value.lhs)?;
+                let rhs = self.infer_expr(&binary.value.rhs)?;
+                match binary.value.kind {
+                    BinaryOp::LogicalAnd | BinaryOp::LogicalOr => {
+                        self.assign(lhs, bool.clone(), &binary.value.lhs.span);
+                        self.assign(rhs, bool.clone(), &binary.value.rhs.span);
+                        bool
+                    }
+                    // values of overlapping types may be compared for equality
+                    BinaryOp::Eq | BinaryOp::Ne => {
+                        self.compare(lhs, rhs, span)?;
+                        bool
+                    }
+                    BinaryOp::Lt | BinaryOp::Gt | BinaryOp::Le | BinaryOp::Ge => {
+                        self.equate(lhs, rhs, span);
+                        bool
+                    }
+                    // the left hand side is used unless it is unit, so it is
+                    // never unit in the result
+                    BinaryOp::Coalesce => {
+                        self.solve()?;
+                        let unit = TypeExpr::Primitive(Primitive::Unit);
+                        if let Ty::Known(lhs) = self.resolve(&lhs) {
+                            self.settle(&rhs, &lhs.exclude(&unit), span)?;
+                        }
+                        match (self.resolve(&lhs), self.resolve(&rhs)) {
+                            (Ty::Known(lhs), Ty::Known(rhs)) => Ty::Known(
+                                Union::from_members(vec![lhs.exclude(&unit), rhs]).simplify(),
+                            ),
+                            (Ty::Known(lhs), rhs) => {
+                                let lhs = self.lower(&lhs.exclude(&unit));
+                                self.equate(lhs, rhs.clone(), span);
+                                rhs
+                            }
+                            (lhs, rhs) => {
+                                self.equate(lhs.clone(), rhs, span);
+                                lhs
+                            }
+                        }
+                    }
+                    BinaryOp::Assign => {
+                        // assignment widens a narrowed variable back to its
+                        // declared type
+                        let lhs = match &binary.value.lhs.value {
+                            Expr::Ident(ident) => self.widen(&ident.value).unwrap_or(lhs),
+                            _ => lhs,
+                        };
+                        self.assign(rhs, lhs, &binary.value.rhs.span);
+                        unit
+                    }
+                    BinaryOp::LogicalAndEq | BinaryOp::LogicalOrEq => {
+                        self.assign(lhs, bool.clone(), &binary.value.lhs.span);
+                        self.assign(rhs, bool, &binary.value.rhs.span);
+                        unit
+                    }
+                    BinaryOp::PlusEq
+                    | BinaryOp::MinusEq
+                    | BinaryOp::MulEq
+                    | BinaryOp::DivEq
+                    | BinaryOp::ModEq
+                    | BinaryOp::BitwiseAndEq
+                    | BinaryOp::BitwiseOrEq
+                    | BinaryOp::BitwiseXorEq
+                    | BinaryOp::ShlEq
+                    | BinaryOp::ShrEq => {
+                        self.present(&lhs, &binary.value.lhs.span)?;
+                        self.present(&rhs, &binary.value.rhs.span)?;
+                        self.arithmetic(lhs, rhs, span)?;
+                        unit
+                    }
+                    _ => {
+                        self.present(&lhs, &binary.value.lhs.span)?;
+                        self.present(&rhs, &binary.value.rhs.span)?;
+                        self.arithmetic(lhs.clone(), rhs, span)?;
+                        lhs
+                    }
+                }
+            }
+            Expr::UnaryExpr(unary) => {
+                let operand = self.infer_expr(&unary.value.expr)?;
+                match unary.value.kind {
+                    UnaryOp::LogicalNot => {
+                        self.assign(operand, bool.clone(), &unary.value.expr.span);
+                        bool
+                    }
+                    UnaryOp::Index(_) => {
+                        self.present(&operand, &unary.value.expr.span)?;
+                        let elem = self.fresh();
+                        let array = Ty::Array(elem.clone().into(), None);
+                        self.equate(array, operand, &unary.value.expr.span);
+                        elem
+                    }
+                    // the error is returned from the enclosing function, so the
+                    // operand is never an error once propagated
+                    UnaryOp::Propagate => {
+                        self.solve()?;
+                        let error = StructDecl::error_type();
+                        match self.resolve(&operand) {
+                            Ty::Known(ty) if ty != error && members(&ty).contains(&error) => {
+                                if let Some(ret) = self.returns.last().cloned() {
+                                    self.assign(Ty::Known(error.clone()), ret, span);
+                                }
+                                self.lower(&ty.exclude(&error))
+                            }
+                            ty => {
+                                return Err(TypeError::new(
+                                    unary.value.expr.span.clone(),
+                                    ErrorKind::E0223(ty.describe()),
+                                ))
+                            }
+                        }
+                    }
+                    // references are not yet distinguished from the values
+                    // they refer to
+                    _ => operand,
+                }
+            }
+            // casts are checked once both types are known, and otherwise
+            // only permit coercions
+            Expr::Cast(cast) => {
+                let operand = self.infer_expr(&cast.value.expr)?;
+                let target = self.lower(&cast.value.ty.value);
+                self.solve()?;
+                if let Ty::Known(to) = self.resolve(&target) {
+                    self.settle(&operand, &to, span)?;
+                }
+                let (from, to) = (self.resolve(&operand), self.resolve(&target));
+                let mut vars = HashSet::new();
+                from.vars(&mut vars);
+                to.vars(&mut vars);
+                if !vars.is_empty() {
+                    self.assign(operand, target.clone(), span);
+                } else if conversion(&from.to_type_expr(), &to.to_type_expr()).is_none() {
+                    return Err(TypeError::new(
+                        span.clone(),
+                        ErrorKind::E0224(from.describe(), to.describe()),
+                    ));
+                }
+                target
+            }
+            Expr::Block(block) => self.infer_block(block)?,
+            Expr::FuncCall(call) => {
+                let callee = self.infer_expr(&call.value.callee)?;
+                self.present(&callee, &call.value.callee.span)?;
+                self.call(callee, &call.value.args, span)?
+            }
+            Expr::MethodCall(call) => {
+                if let Some(class) = self.class_receiver(&call.value.receiver) {
+                    return self.infer_static_call(&class, &call.value, span);
+                }
+                let receiver = self.infer_expr(&call.value.receiver)?;
+                // the receiver must be known to find the method
+                self.solve()?;
+                self.present(&receiver, &call.value.receiver.span)?;
+                let receiver = self.resolve(&receiver);
+                let method = &call.value.method;
+                match &receiver {
+                    Ty::Known(TypeExpr::Primitive(Primitive::Ref(ty))) | Ty::Generic(ty, _) => {
+                        let scheme = self
+                            .methods
+                            .get(&(ty.clone(), method.value.clone()))
+                            .cloned()
+                            .ok_or_else(|| {
+                                TypeError::new(
+                                    method.span.clone(),
+                                    ErrorKind::E0208(method.value.clone(), receiver.describe()),
+                                )
+                            })?;
+                        let mut callee = self.instantiate(&scheme, span);
+                        // the type parameters of generic classes take on the
+                        // type arguments of the receiver
+                        if let (Ty::Generic(_, args), Some(params)) =
+                            (&receiver, self.class_params.get(ty))
+                        {
+                            let args = params
+                                .iter()
+                                .zip(args)
+                                .filter_map(|(param, arg)| match param {
+                                    Ty::Var(v) => Some((*v, arg.clone())),
+                                    _ => None,
+                                })
+                                .collect();
+                            callee = replace(&callee, &args);
+                        }
+                        self.call(callee, &call.value.args, span)?
+                    }
+                    Ty::Var(_) => {
+                        for arg in &call.value.args {
+                            self.infer_expr(arg)?;
+                        }
+                        self.fresh()
+                    }
+                    _ => {
+                        return Err(TypeError::new(
+                            method.span.clone(),
+                            ErrorKind::E0208(method.value.clone(), receiver.describe()),
+                        ))
+                    }
+                }
+            }
+            Expr::Conditional(conditional) => {
+                let conditional = &conditional.value;
+                let mut branches = vec![];
+                let mut exhausted = false;
+                for if_stmt in std::iter::once(&conditional.if_stmt).chain(&conditional.else_ifs) {
+                    let condition = &if_stmt.value.condition;
+                    let ty = self.infer_expr(condition)?;
+                    self.assign(ty, bool.clone(), &condition.span);
+                    self.narrow(refinements(&condition.value, true))?;
+                    branches.push(self.infer_block(&if_stmt.value.block)?);
+                    self.exit_scope();
+                    // later branches are only reached if this condition does
+                    // not hold
+                    exhausted = self.narrow(refinements(&condition.value, false))?;
+                }
+                let ty = match &conditional.else_stmt {
+                    Some(else_stmt) => Some(self.infer_block(else_stmt)?),
+                    // a chain of conditions that leaves no values of a
+                    // narrowed variable is exhaustive without an else
+                    None if exhausted => branches.pop(),
+                    None => None,
+                };
+                for _ in std::iter::once(&conditional.if_stmt).chain(&conditional.else_ifs) {
+                    self.exit_scope();
+                }
+                match ty {
+                    // every branch must produce a value of the same type
+                    Some(ty) => {
+                        for branch in branches {
+                            self.equate(ty.clone(), branch, span);
+                        }
+                        ty
+                    }
+                    // a non-exhaustive `if` has no value when none of its
+                    // conditions hold, so its branches may not produce one
+                    None => match branches.iter().find_map(|branch| self.produced(branch)) {
+                        Some(ty) => return Err(TypeError::new(span.clone(), ErrorKind::E0229(ty))),
+                        None => unit,
+                    },
+                }
+            }
+            Expr::Loop(loop_expr) => {
+                self.infer_block(&loop_expr.value.block)?;
+                unit
+            }
+            Expr::While(while_expr) => {
+                let condition = &while_expr.value.condition;
+                let ty = self.infer_expr(condition)?;
+                self.assign(ty, bool, &condition.span);
+                self.narrow(refinements(&condition.value, true))?;
+                self.infer_block(&while_expr.value.block)?;
+                self.exit_scope();
+                unit
+            }
+            Expr::Match(match_expr) => {
+                let expr = &match_expr.value.expr;
+                let scrutinee = self.infer_expr(expr)?;
+                let ty = self.fresh();
+                // the values of the scrutinee not yet matched by a literal
+                // pattern. Other patterns may not match any value, so only
+                // literals and an `else` branch make a match exhaustive
+                let mut remaining = match self.resolve(&scrutinee) {
+                    Ty::Known(ty) => Some(ty),
+                    _ => None,
+                };
+                for branch in &match_expr.value.branches {
+                    let pattern = &branch.value.pattern;
+                    let pattern_ty = self.infer_expr(pattern)?;
+                    self.compare(scrutinee.clone(), pattern_ty, &pattern.span)?;
+                    let literal = literal_type(&pattern.value);
+                    let condition = |refinement: fn(Ident, TypeExpr) -> Refinement| match (
+                        &expr.value,
+                        &literal,
+                    ) {
+                        (Expr::Ident(ident), Some(literal)) => {
+                            vec![refinement(ident.value.clone(), literal.clone())]
+                        }
+                        _ => vec![],
+                    };
+                    self.narrow(condition(Refinement::Is))?;
+                    let value = self.infer_expr(&branch.value.value)?;
+                    self.exit_scope();
+                    self.equate(ty.clone(), value, &branch.value.value.span);
+                    // later branches are only reached if this pattern does not
+                    // match
+                    self.narrow(condition(Refinement::IsNot))?;
+                    if let Some(literal) = literal {
+                        remaining = remaining.map(|ty| ty.exclude(&literal));
+                    }
+                }
+                // the `else` branch is reached by every value no pattern
+                // matched
+                if let Some(else_value) = &match_expr.value.else_value {
+                    let value = self.infer_expr(else_value)?;
+                    self.equate(ty.clone(), value, &else_value.span);
+                    remaining = None;
+                }
+                for _ in &match_expr.value.branches {
+                    self.exit_scope();
+                }
+                match remaining {
+                    Some(TypeExpr::Primitive(Primitive::Never)) | None => ty,
+                    Some(remaining) => {
+                        return Err(TypeError::new(
+                            span.clone(),
+                            ErrorKind::E0220(remaining.to_string()),
+                        ))
+                    }
+                }
+            }
+            Expr::StructExpr(struct_expr) => {
+                let name = &struct_expr.value.ident;
+                let fields = self.structs.get(&name.value).cloned().ok_or_else(|| {
+                    TypeError::new(name.span.clone(), ErrorKind::E0205(name.value.clone()))
+                })?;
+                // the type arguments of generic structs are inferred from the
+                // values of their fields
+                let generics = self.generics.get(&name.value).cloned().unwrap_or_default();
+                let args = self.enter_generics(&generics);
+                self.exit_generics();
+                let instances: Vec<_> = args
+                    .iter()
+                    .filter_map(|arg| match arg {
+                        Ty::Var(v) => Some((*v, arg.clone())),
+                        _ => None,
+                    })
+                    .collect();
+                self.check_bounds(&instances, span);
+                for field in &struct_expr.value.fields {
+                    let (_, ty) = fields
+                        .iter()
+                        .find(|(f, _)| f == &field.value.ident.value)
+                        .ok_or_else(|| {
+                            TypeError::new(
+                                field.value.ident.span.clone(),
+                                ErrorKind::E0207(
+                                    field.value.ident.value.clone(),
+                                    name.value.clone(),
+                                ),
+                            )
+                        })?;
+                    let expected = self.field_type(&name.value, &args, ty);
+                    let value = self.infer_expr(&field.value.value)?;
+                    self.assign(value, expected, &field.value.value.span);
+                }
+                // every field must be given a value
+                if let Some((missing, _)) = fields.iter().find(|(f, _)| {
+                    !struct_expr.value.fields.iter().any(|field| &field.value.ident.value == f)
+                }) {
+                    return Err(TypeError::new(
+                        span.clone(),
+                        ErrorKind::E0227(missing.clone(), name.value.clone()),
+                    ));
+                }
+                Self::struct_type(&name.value, args)
+            }
+            Expr::FieldAccess(access) => {
+                let receiver = self.infer_expr(&access.value.expr)?;
+                // the receiver must be known to find the field
+                self.solve()?;
+                let receiver = self.resolve(&receiver);
+                // optional accesses are made on the receiver without unit, and
+                // are unit themselves if the receiver is
+                let optional = match &receiver {
+                    Ty::Known(ty) if access.value.optional && is_optional(ty) => true,
+                    _ => {
+                        self.present(&receiver, &access.value.expr.span)?;
+                        false
+                    }
+                };
+                let receiver = match &receiver {
+                    Ty::Known(ty) if optional => {
+                        self.lower(&ty.exclude(&TypeExpr::Primitive(Primitive::Unit)))
+                    }
+                    _ => receiver,
+                };
+                let field = &access.value.field;
+                let (ty, args) = match &receiver {
+                    Ty::Known(TypeExpr::Primitive(Primitive::Ref(ty))) | Ty::Generic(ty, _) => (
+                        self.structs
+                            .get(ty)
+                            .and_then(|fields| fields.iter().find(|(f, _)| f == &field.value))
+                            .map(|(_, ty)| ty.clone()),
+                        match &receiver {
+                            Ty::Generic(_, args) => args.clone(),
+                            _ => vec![],
+                        },
+                    ),
+                    Ty::Var(_) => (Some(TypeExpr::Infer), vec![]),
+                    _ => (None, vec![]),
+                };
+                let ty = match (ty, &receiver) {
+                    (Some(ty), Ty::Generic(name, _)) => self.field_type(name, &args, &ty),
+                    (Some(ty), _) => self.lower(&ty),
+                    (None, _) => {
+                        return Err(TypeError::new(
+                            field.span.clone(),
+                            ErrorKind::E0207(field.value.clone(), receiver.describe()),
+                        ))
+                    }
+                };
+                match optional {
+                    true => {
+                        let ty = Union::from_members(vec![
+                            self.resolve(&ty).to_type_expr(),
+                            TypeExpr::Primitive(Primitive::Unit),
+                        ]);
+                        self.lower(&ty.simplify())
+                    }
+                    false => ty,
+                }
+            }
+            Expr::Closure(closure) => {
+                let closure = &closure.value;
+                let ty = self.signature(&closure.params, &closure.ret_ty.value);
+                let Ty::Func(params, ret) = &ty else { unreachable!() };
+                // closures refer to the variables of the scopes enclosing them,
+                // and are not generalised, as these may not yet be inferred
+                self.enter_scope();
+                for (param, ty) in closure.params.iter().zip(params) {
+                    self.bind(&param.value.ident.value, Scheme::mono(ty.clone()));
+                }
+                self.returns.push(ret.as_ref().clone());
+                let value = self.infer_block(&closure.body);
+                self.returns.pop();
+                self.exit_scope();
+                let value = value?;
+                // an omitted return type is inferred from the trailing expression
+                if let TypeExpr::Infer = closure.ret_ty.value {
+                    self.assign(value, ret.as_ref().clone(), &closure.body.span);
+                }
+                ty
+            }
+        })
+    }
+}
+
+/// Returns true if the given block always returns or breaks.
+fn diverges(block: &Node<Block>) -> bool {
+    block.value.stmts.iter().any(|stmt| matches!(stmt.value, Stmt::Return(_) | Stmt::Break(_)))
+}
+
+/// Return the type of the given literal expression, if it has a single value.
+fn literal_type(expr: &Expr) -> Option<TypeExpr> {
+    let Expr::Literal(literal) = expr else { return None };
+    Some(TypeExpr::Primitive(match &literal.value {
+        Literal::Int(i) => Primitive::IntLiteral(*i),
+        Literal::Float(f) => Primitive::FloatLiteral(*f),
+        Literal::String(s) => Primitive::StringLiteral(s.clone()),
+        Literal::Char(c) => Primitive::CharLiteral(*c),
+        Literal::Bool(true) => Primitive::True,
+        Literal::Bool(false) => Primitive::False,
+        Literal::Unit => Primitive::Unit,
+        Literal::Array(_) => return None,
+    }))
+}
+
+/// Return the refinements known wherever the given condition holds, or does
+/// not hold.
+fn refinements(condition: &Expr, holds: bool) -> Vec<Refinement> {
+    match condition {
+        Expr::UnaryExpr(unary) if unary.value.kind == UnaryOp::LogicalNot => {
+            refinements(&unary.value.expr.value, !holds)
+        }
+        Expr::BinaryExpr(binary) => {
+            let binary = &binary.value;
+            match (binary.kind, holds) {
+                (BinaryOp::LogicalAnd, true) | (BinaryOp::LogicalOr, false) => {
+                    let mut refinements = refinements(&binary.lhs.value, holds);
+                    refinements.extend(self::refinements(&binary.rhs.value, holds));
+                    refinements
+                }
+                (BinaryOp::Eq | BinaryOp::Ne, _) => {
+                    let (ident, literal) = match (&binary.lhs.value, &binary.rhs.value) {
+                        (Expr::Ident(ident), expr) | (expr, Expr::Ident(ident)) => {
+                            match literal_type(expr) {
+                                Some(literal) => (ident.value.clone(), literal),
+                                None => return vec![],
+                            }
+                        }
+                        _ => return vec![],
+                    };
+                    match holds == (binary.kind == BinaryOp::Eq) {
+                        true => vec![Refinement::Is(ident, literal)],
+                        false => vec![Refinement::IsNot(ident, literal)],
+                    }
+                }
+                _ => vec![],
+            }
+        }
+        _ => vec![],
+    }
+}
+
+/// Return the members of the given type, treating `bool` as `true | false`.
+fn members(ty: &TypeExpr) -> Vec<TypeExpr> {
+    match ty.simplify() {
+        TypeExpr::Operation(Operation::Union(union)) => {
+            union.members().iter().flat_map(members).collect()
+        }
+        TypeExpr::Primitive(Primitive::Bool) => {
+            vec![Primitive::True.into(), Primitive::False.into()]
+        }
+        ty => vec![ty],
+    }
+}
+
+/// Returns true if the given type is a numeric type.
+fn is_numeric(ty: &TypeExpr) -> bool {
+    matches!(ty, TypeExpr::Primitive(Primitive::Int | Primitive::Float | Primitive::Numeric(_)))
+}
+
+/// Returns true if the given type is optional, such that its values are either
+/// unit or values of some other type.
+fn is_optional(ty: &TypeExpr) -> bool {
+    let members = members(ty);
+    members.len() > 1 && members.contains(&TypeExpr::Primitive(Primitive::Unit))
+}
+
+/// Narrow the given type to the members that have values in common with the
+/// given literal type.
+fn narrow_to(ty: &TypeExpr, literal: &TypeExpr) -> TypeExpr {
+    Union::from_members(
+        members(ty)
+            .into_iter()
+            .filter(|ty| ty.intersect(literal) != TypeExpr::Primitive(Primitive::Never))
+            .collect(),
+    )
+    .simplify()
+}
+
+/// Replace the given type variables in a type.
+fn replace(ty: &Ty, vars: &HashMap<TypeVar, Ty>) -> Ty {
+    match ty {
+        Ty::Var(v) => vars.get(v).cloned().unwrap_or(Ty::Var(*v)),
+        Ty::Known(_) => ty.clone(),
+        Ty::Array(elem, len) => Ty::Array(replace(elem, vars).into(), *len),
+        Ty::Func(params, ret) => {
+            Ty::Func(params.iter().map(|p| replace(p, vars)).collect(), replace(ret, vars).into())
+        }
+        Ty::Generic(name, args) => {
+            Ty::Generic(name.clone(), args.iter().map(|arg| replace(arg, vars)).collect())
+        }
+    }
+}
@@ -0,0 +1,84 @@
+//! Defines the memory layout computation for sized types.
+
+use std::cmp::Reverse;
+
+/// The size and alignment of a sized type, in bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Layout {
+    /// The size of the type in bytes. This is always a multiple of `align`.
+    pub size: usize,
+    /// The alignment of the type in bytes. This is always a power of two.
+    pub align: usize,
+}
+
+impl Layout {
+    /// Create a new layout with the given size and alignment.
+    pub const fn new(size: usize, align: usize) -> Self {
+        Layout { size, align }
+    }
+}
+
+/// The representation used when laying out the fields of a struct.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Repr {
+    /// The default Flux representation. Fields may be reordered to minimise
+    /// the amount of padding between them.
+    #[default]
+    Flux,
+    /// The C representation. Fields are laid out in declaration order using
+    /// the same rules as C, making the type safe to pass across FFI
+    /// boundaries.
+    C,
+}
+
+/// The computed layout of a struct.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructLayout {
+    /// The layout of the struct as a whole.
+    pub layout: Layout,
+    /// The byte offset of each field, in declaration order.
+    pub offsets: Vec<usize>,
+}
+
+/// Round `offset` up to the nearest multiple of `align`.
+pub const fn align_to(offset: usize, align: usize) -> usize {
+    offset.div_ceil(align) * align
+}
+
+/// Compute the layout of a struct whose fields have the given layouts, in
+/// declaration order.
+pub fn layout_struct(fields: &[Layout], repr: Repr) -> StructLayout {
+    let mut order: Vec<usize> = (0..fields.len()).collect();
+    // placing the most strictly aligned fields first removes all interior
+    // padding, since every alignment is a power of two
+    if repr == Repr::Flux {
+        order.sort_by_key(|&i| Reverse(fields[i].align));
+    }
+    let mut offsets = vec![0; fields.len()];
+    let mut offset = 0;
+    let mut align = 1;
+    for i in order {
+        offset = align_to(offset, fields[i].align);
+        offsets[i] = offset;
+        offset += fields[i].size;
+        align = align.max(fields[i].align);
+    }
+    // pad the struct such that arrays of it keep every element aligned
+    StructLayout { layout: Layout::new(align_to(offset, align), align), offsets }
+}
+
+/// Compute the layout of a tagged union with a tag of the given layout, whose
+/// members have the given layouts. The payload holding the member follows the
+/// tag, and is large enough to hold any member. The offsets of the tag and the
+/// payload are returned, in that order.
+pub fn layout_tagged(tag: Layout, members: &[Layout]) -> StructLayout {
+    let payload = members.iter().fold(Layout::new(0, 1), |payload, member| {
+        Layout::new(payload.size.max(member.size), payload.align.max(member.align))
+    });
+    let offset = align_to(tag.size, payload.align);
+    let align = tag.align.max(payload.align);
+    StructLayout {
+        layout: Layout::new(align_to(offset + payload.size, align), align),
+        offsets: vec![0, offset],
+    }
+}
@@ -1,6 +1,17 @@
-//! Defines the type table and builders for sized types.
+//! Defines the type table and builders for sized types, the lowering of
+//! structural types to sized types, the environment of type aliases, the
+//! conversions performed by casts, and the type inference engine.
 
+pub mod alias;
 pub mod builder;
+pub mod cast;
+pub mod infer;
+pub mod layout;
+pub mod lower;
+pub mod vtable;
+
+use fluxc_ast::Numeric;
+use layout::Layout;
 
 /// Type alias for the IDs of types.
 pub type TypeId = usize;
@@ -20,17 +31,27 @@ impl TypeTable {
         TypeTable { entries: vec![], next_id: 0 }
     }
     /// This method creates and returns a new `TypeTable` instance with the core
-    /// types declared.
+    /// types declared, laid out for the host.
     pub fn with_core_types() -> Self {
+        Self::with_pointer_size(std::mem::size_of::<usize>())
+    }
+    /// This method creates and returns a new `TypeTable` instance with the core
+    /// types declared, laid out for a target whose pointers are `pointer_size`
+    /// bytes wide.
+    pub fn with_pointer_size(pointer_size: usize) -> Self {
         let mut table = Self::empty();
         // primitives
-        table.append(TableEntry { name: "int".into(), size: Some(8), fields: Some(vec![]) });
-        table.append(TableEntry { name: "float".into(), size: Some(8), fields: Some(vec![]) });
-        table.append(TableEntry { name: "bool".into(), size: Some(1), fields: Some(vec![]) });
-        table.append(TableEntry { name: "char".into(), size: Some(8), fields: Some(vec![]) });
-        table.append(TableEntry { name: "unit".into(), size: Some(0), fields: Some(vec![]) });
+        table.append(TableEntry::primitive("int", 8));
+        table.append(TableEntry::primitive("float", 8));
+        table.append(TableEntry::primitive("bool", 1));
+        table.append(TableEntry::primitive("char", 8));
+        table.append(TableEntry::primitive("unit", 0));
+        // sized numbers
+        for numeric in Numeric::ALL {
+            table.append(TableEntry::primitive(numeric.name(), numeric.size(pointer_size)));
+        }
         // reference
-        table.append(TableEntry { name: "ref".into(), size: Some(8), fields: Some(vec![]) });
+        table.append(TableEntry::primitive("ref", pointer_size));
         table
     }
     /// Find a type with a particular ID.
@@ -44,12 +65,19 @@ impl TypeTable {
     pub fn find_by_name<S: AsRef<str>>(&self, name: S) -> Option<&KeyedTableEntry> {
         self.entries.iter().find(|entry| entry.name == name.as_ref())
     }
+    /// Find the byte offset of a field within the type with the given ID.
+    /// Returns `None` if the type or field does not exist, or if the type is
+    /// unsized.
+    pub fn field_offset<S: AsRef<str>>(&self, id: TypeId, name: S) -> Option<usize> {
+        self.find(id)?.fields.as_ref()?.iter().find(|field| field.name == name.as_ref())?.offset
+    }
     /// Append an entry to the type table. Returns the type ID of the entry.
     pub fn append(&mut self, entry: TableEntry) -> &KeyedTableEntry {
         self.entries.push(KeyedTableEntry {
             id: self.next_id,
             name: entry.name,
             size: entry.size,
+            align: entry.align,
             fields: entry.fields,
         });
         self.next_id += 1;
@@ -66,22 +94,45 @@ pub struct KeyedTableEntry {
     pub name: String,
     /// The size of this type in bytes. If this typed is unsized,
     /// then this value is `None`.
-    pub size: Option<u8>,
+    pub size: Option<usize>,
+    /// The alignment of this type in bytes.
+    pub align: usize,
     /// Fields on this type.
     pub fields: Option<Vec<TypeField>>,
 }
 
+impl KeyedTableEntry {
+    /// Return the layout of this type, or `None` if it is unsized.
+    pub fn layout(&self) -> Option<Layout> {
+        self.size.map(|size| Layout::new(size, self.align))
+    }
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub struct TableEntry {
     /// The name of this type.
     pub name: String,
     /// The size of this type in bytes. If this typed is unsized,
     /// then this value is `None`.
-    pub size: Option<u8>,
+    pub size: Option<usize>,
+    /// The alignment of this type in bytes.
+    pub align: usize,
     /// Fields on this type.
     pub fields: Option<Vec<TypeField>>,
 }
 
+impl TableEntry {
+    /// Create a new primitive table entry that is aligned to its own size.
+    pub fn primitive<S: ToString>(name: S, size: usize) -> Self {
+        TableEntry {
+            name: name.to_string(),
+            size: Some(size),
+            align: size.max(1),
+            fields: Some(vec![]),
+        }
+    }
+}
+
 /// A field on a type.
 #[derive(Debug, PartialEq, Eq)]
 pub struct TypeField {
@@ -91,22 +142,170 @@ pub struct TypeField {
     pub name: String,
     /// The ID of the type of this field.
     pub ty: usize,
+    /// The byte offset of this field from the start of the parent type. If
+    /// the parent type is unsized, then this value is `None`.
+    pub offset: Option<usize>,
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{builder::StructBuilder, TypeField, TypeTable};
+    use fluxc_ast::{Operation, Primitive, TypeExpr, Union};
+
+    use crate::{
+        builder::StructBuilder,
+        layout::{Layout, Repr},
+        vtable::{vtable_name, VTableBuilder},
+        TypeField, TypeTable,
+    };
 
     #[test]
     fn test_create_struct() {
         let mut types = TypeTable::with_core_types();
         let int = types.find(0).unwrap();
         let my_struct = StructBuilder::new("Test").field("inner", int).build(&mut types);
-        assert_eq!(my_struct.id, 6);
+        assert_eq!(my_struct.id, 16);
         assert_eq!(
             my_struct.fields,
-            Some(vec![TypeField { index: 0, name: "inner".to_string(), ty: 0 }]),
+            Some(vec![TypeField { index: 0, name: "inner".to_string(), ty: 0, offset: Some(0) }]),
         );
         assert_eq!(my_struct.size, Some(8));
     }
+
+    #[test]
+    fn test_pointer_sized_types() {
+        let types = TypeTable::with_pointer_size(4);
+        assert_eq!(types.find_by_name("usize").unwrap().size, Some(4));
+        assert_eq!(types.find_by_name("ref").unwrap().size, Some(4));
+        assert_eq!(types.find_by_name("u64").unwrap().size, Some(8));
+    }
+
+    #[test]
+    fn test_struct_field_offset() {
+        let mut types = TypeTable::with_core_types();
+        let int = types.find_by_name("int").unwrap();
+        let my_struct = StructBuilder::new("Point").field("x", int).field("y", int);
+        let id = my_struct.build(&mut types).id;
+        assert_eq!(types.field_offset(id, "x"), Some(0));
+        assert_eq!(types.field_offset(id, "y"), Some(8));
+        assert_eq!(types.field_offset(id, "z"), None);
+    }
+
+    #[test]
+    fn test_struct_padding() {
+        let mut types = TypeTable::with_core_types();
+        let int = types.find_by_name("int").unwrap().id;
+        let bool = types.find_by_name("bool").unwrap().id;
+        // C layout keeps declaration order, padding between fields
+        let c_struct = StructBuilder::new("C")
+            .field("a", types.find(bool).unwrap())
+            .field("b", types.find(int).unwrap())
+            .field("c", types.find(bool).unwrap())
+            .repr(Repr::C)
+            .build(&mut types)
+            .id;
+        assert_eq!(types.field_offset(c_struct, "a"), Some(0));
+        assert_eq!(types.field_offset(c_struct, "b"), Some(8));
+        assert_eq!(types.field_offset(c_struct, "c"), Some(16));
+        assert_eq!(types.find(c_struct).unwrap().layout(), Some(Layout::new(24, 8)));
+        // flux layout reorders fields to remove padding
+        let flux_struct = StructBuilder::new("Flux")
+            .field("a", types.find(bool).unwrap())
+            .field("b", types.find(int).unwrap())
+            .field("c", types.find(bool).unwrap())
+            .build(&mut types)
+            .id;
+        assert_eq!(types.field_offset(flux_struct, "a"), Some(8));
+        assert_eq!(types.field_offset(flux_struct, "b"), Some(0));
+        assert_eq!(types.field_offset(flux_struct, "c"), Some(9));
+        assert_eq!(types.find(flux_struct).unwrap().layout(), Some(Layout::new(16, 8)));
+    }
+
+    #[test]
+    fn test_vtable_layout() {
+        let mut types = TypeTable::with_core_types();
+        let (fat, vtable) =
+            VTableBuilder::new("Shape").method("area").method("perimeter").build(&mut types);
+        assert_eq!(types.find_by_name("Shape").unwrap().id, fat);
+        assert_eq!(types.find_by_name(vtable_name("Shape")).unwrap().id, vtable);
+        // slots are laid out in declaration order
+        assert_eq!(types.field_offset(vtable, "area"), Some(0));
+        assert_eq!(types.field_offset(vtable, "perimeter"), Some(8));
+        // fat pointers hold a data pointer followed by a vtable pointer
+        assert_eq!(types.field_offset(fat, "data"), Some(0));
+        assert_eq!(types.field_offset(fat, "vtable"), Some(8));
+        assert_eq!(types.find(fat).unwrap().layout(), Some(Layout::new(16, 8)));
+    }
+
+    #[test]
+    fn test_large_struct() {
+        let mut types = TypeTable::with_core_types();
+        let builder = (0..64).fold(StructBuilder::new("Large"), |builder, i| {
+            builder.field(format!("field{}", i), types.find_by_name("int").unwrap())
+        });
+        let large = builder.build(&mut types);
+        assert_eq!(large.size, Some(512));
+    }
+
+    #[test]
+    fn test_lower_union() {
+        let mut types = TypeTable::with_core_types();
+        // int | string is tagged, with a payload large enough for either
+        let union = Union::of(Primitive::Int.into(), Primitive::String.into()).into();
+        let id = types.lower(&union).unwrap();
+        let entry = types.find(id).unwrap();
+        assert_eq!(entry.name, "int | string");
+        assert_eq!(entry.layout(), Some(Layout::new(16, 8)));
+        assert_eq!(types.field_offset(id, "tag"), Some(0));
+        assert_eq!(types.field_offset(id, "int"), Some(8));
+        assert_eq!(types.field_offset(id, "string"), Some(8));
+        // unions are only laid out once
+        assert_eq!(types.lower(&union), Some(id));
+        // literals are erased to their base type
+        let literals = Union::of(Primitive::IntLiteral(1).into(), Primitive::IntLiteral(2).into());
+        assert_eq!(types.lower(&literals.into()), types.find_by_name("int").map(|int| int.id));
+        let bool = Union::of(Primitive::True.into(), Primitive::Unit.into()).into();
+        let id = types.lower(&bool).unwrap();
+        assert_eq!(types.find(id).unwrap().layout(), Some(Layout::new(16, 8)));
+        assert_eq!(types.field_offset(id, "bool"), Some(8));
+        // members are tagged in order
+        assert_eq!(types.tags(id, &Primitive::Unit.into()), vec![0]);
+        assert_eq!(types.tags(id, &Primitive::False.into()), vec![1]);
+        assert_eq!(types.tags(id, &bool), vec![0, 1]);
+    }
+
+    #[test]
+    fn test_lower_tuple() {
+        let mut types = TypeTable::with_core_types();
+        let tuple = TypeExpr::Primitive(Primitive::Tuple(vec![
+            Primitive::Bool.into(),
+            Primitive::FloatLiteral(1.5).into(),
+        ]));
+        let id = types.lower(&tuple).unwrap();
+        assert_eq!(types.find(id).unwrap().name, "(bool, float)");
+        assert_eq!(types.field_offset(id, "0"), Some(8));
+        assert_eq!(types.field_offset(id, "1"), Some(0));
+        // arrays keep their elements in order
+        let array = Operation::Array(Box::new(Primitive::Bool.into()), Some(3)).into();
+        let id = types.lower(&array).unwrap();
+        assert_eq!(types.field_offset(id, "2"), Some(2));
+        assert_eq!(types.find(id).unwrap().layout(), Some(Layout::new(3, 1)));
+        // types without a runtime representation are not lowered
+        assert_eq!(types.lower(&Primitive::Any.into()), None);
+        assert_eq!(types.lower(&Primitive::Ref("Point".into()).into()), None);
+    }
+
+    #[test]
+    fn test_lower_closure() {
+        let mut types = TypeTable::with_core_types();
+        let func = TypeExpr::Function(vec![Primitive::Int.into()], Box::new(Primitive::Int.into()));
+        let id = types.lower(&func).unwrap();
+        assert_eq!(types.find(id).unwrap().name, "(int) -> int");
+        assert_eq!(types.field_offset(id, "code"), Some(0));
+        assert_eq!(types.field_offset(id, "env"), Some(8));
+        // environments hold the captured values
+        let captures = [("0".to_string(), Primitive::Bool.into()), ("1".to_string(), func)];
+        let env = types.environment("main.closure", &captures).unwrap();
+        assert_eq!(types.find(env).unwrap().name, "main.closure.env");
+        assert_eq!(types.find(env).unwrap().layout(), Some(Layout::new(24, 8)));
+    }
 }
@@ -0,0 +1,165 @@
+//! Lowers the structural types of the type checker to the sized types of a
+//! type table, which describe how values are represented at runtime.
+//!
+//! - Literal types are erased to their base type, so `1` is held as an `int`.
+//! - Strings, unsized arrays and the recursive parts of recursive types are
+//!   held by reference.
+//! - Tuples become anonymous structs, with a field for each element named
+//!   after its index. Sized arrays are laid out the same way, keeping their
+//!   elements in order.
+//! - Unions become tagged unions, built with a [`UnionBuilder`]. Unions of
+//!   literals of the same type, such as `1 | 2`, are erased to that type, so
+//!   need no tag.
+//! - `never` has no values, so is held as `unit`.
+//! - Functions become closures, made up of a pointer to the code of the
+//!   function and a pointer to its environment, which holds the values of the
+//!   variables the function captured. The code takes the environment as its
+//!   first argument.
+//!
+//! Anonymous types are registered under the name of the type they represent,
+//! such as `(int, string)` or `int | string`, so that each is only laid out
+//! once. Structs, classes, interfaces and the instances of generic types must
+//! be registered before the types referring to them are lowered.
+
+use fluxc_ast::{Operation, Primitive, Simplify, TypeExpr};
+
+use crate::{
+    builder::{StructBuilder, UnionBuilder},
+    layout::Repr,
+    KeyedTableEntry, TypeId, TypeTable,
+};
+
+/// Erase the literal types within the given type to their base types.
+/// Instances of generic types are registered under the type arguments they
+/// were instantiated with, so are left as they are.
+pub fn erase(ty: &TypeExpr) -> TypeExpr {
+    ty.replace(&|ty| match ty {
+        TypeExpr::Primitive(primitive) => match primitive {
+            Primitive::IntLiteral(_) => Some(Primitive::Int.into()),
+            Primitive::FloatLiteral(_) => Some(Primitive::Float.into()),
+            Primitive::StringLiteral(_) => Some(Primitive::String.into()),
+            Primitive::CharLiteral(_) => Some(Primitive::Char.into()),
+            Primitive::True | Primitive::False => Some(Primitive::Bool.into()),
+            _ => None,
+        },
+        TypeExpr::Generic(..) => Some(ty.clone()),
+        _ => None,
+    })
+    .simplify()
+}
+
+/// Returns true if values of the given type are held as tagged unions.
+pub fn is_tagged(ty: &TypeExpr) -> bool {
+    matches!(erase(ty), TypeExpr::Operation(Operation::Union(_)))
+}
+
+/// Return the name of the environment type of the closure whose code is the
+/// given function.
+pub fn env_name(func: &str) -> String {
+    format!("{}.env", func)
+}
+
+/// Return the name of the table entry holding values of the given erased
+/// type.
+fn repr_name(ty: &TypeExpr) -> String {
+    match ty {
+        TypeExpr::Primitive(Primitive::String)
+        | TypeExpr::Operation(Operation::Array(_, None))
+        | TypeExpr::Circular(_) => "ref".into(),
+        TypeExpr::Primitive(Primitive::Never) => "unit".into(),
+        ty => ty.to_string(),
+    }
+}
+
+impl TypeTable {
+    /// Find the entry holding values of the given type, if it has been
+    /// lowered.
+    pub fn resolve(&self, ty: &TypeExpr) -> Option<&KeyedTableEntry> {
+        self.find_by_name(repr_name(&erase(ty)))
+    }
+
+    /// Lower the given type, registering the entries needed to hold its
+    /// values. Returns the ID of the entry holding values of the type, or
+    /// `None` if the type has no runtime representation, such as `any`, or
+    /// refers to a type that has not been registered.
+    pub fn lower(&mut self, ty: &TypeExpr) -> Option<TypeId> {
+        let ty = erase(ty);
+        let name = repr_name(&ty);
+        if let Some(entry) = self.find_by_name(&name) {
+            return Some(entry.id);
+        }
+        match &ty {
+            TypeExpr::Primitive(Primitive::Tuple(types)) => {
+                let types = types.iter().map(|ty| self.lower(ty)).collect::<Option<Vec<_>>>()?;
+                Some(self.build_struct(name, &types, Repr::Flux))
+            }
+            TypeExpr::Operation(Operation::Array(elem, Some(len))) => {
+                let elem = self.lower(elem)?;
+                Some(self.build_struct(name, &vec![elem; *len], Repr::C))
+            }
+            TypeExpr::Function(..) => {
+                let ptr = self.find_by_name("ref").expect("failed to find type").id;
+                let builder = StructBuilder::new(name)
+                    .field("code", self.find(ptr).unwrap())
+                    .field("env", self.find(ptr).unwrap());
+                Some(builder.repr(Repr::C).build(self).id)
+            }
+            TypeExpr::Operation(Operation::Union(union)) => {
+                let members = union.members();
+                let ids = members.iter().map(|ty| self.lower(ty)).collect::<Option<Vec<_>>>()?;
+                let builder =
+                    members.iter().zip(ids).fold(UnionBuilder::new(name), |builder, (ty, id)| {
+                        builder.member(ty, self.find(id).expect("failed to find type"))
+                    });
+                Some(builder.build(self).id)
+            }
+            _ => None,
+        }
+    }
+
+    /// Register the environment of the closure whose code is the function
+    /// `func`, with a field holding each of the given captured values, named
+    /// after the variables they were captured from. Returns `None` if any of
+    /// the captured values has no runtime representation.
+    pub fn environment(&mut self, func: &str, captures: &[(String, TypeExpr)]) -> Option<TypeId> {
+        let ids = captures.iter().map(|(_, ty)| self.lower(ty)).collect::<Option<Vec<_>>>()?;
+        let builder = captures.iter().zip(ids).fold(
+            StructBuilder::new(env_name(func)),
+            |builder, ((name, _), id)| {
+                builder.field(name, self.find(id).expect("failed to find type"))
+            },
+        );
+        Some(builder.build(self).id)
+    }
+
+    /// Return the tags of the members of the tagged union `id` that hold
+    /// values of the given type, which may be a member of the union or a union
+    /// of its members.
+    pub fn tags(&self, id: TypeId, ty: &TypeExpr) -> Vec<usize> {
+        let members = match erase(ty) {
+            TypeExpr::Operation(Operation::Union(union)) => union.members(),
+            ty => vec![ty],
+        };
+        let Some(fields) = self.find(id).and_then(|entry| entry.fields.as_ref()) else {
+            return vec![];
+        };
+        // the tag is the first field, followed by the members in tag order
+        members
+            .iter()
+            .filter_map(|member| {
+                let name = member.to_string();
+                fields.iter().skip(1).find(|field| field.name == name).map(|field| field.index - 1)
+            })
+            .collect()
+    }
+
+    /// Register an anonymous struct with a field for each of the given types,
+    /// named after its index.
+    fn build_struct(&mut self, name: String, types: &[TypeId], repr: Repr) -> TypeId {
+        let builder =
+            types.iter().enumerate().fold(StructBuilder::new(name), |builder, (i, ty)| {
+                builder.field(i, self.find(*ty).expect("failed to find type"))
+            });
+        builder.repr(repr).build(self).id
+    }
+}
@@ -0,0 +1,56 @@
+//! Defines the types used to dynamically dispatch interface methods.
+//!
+//! A value whose type is an interface is represented as a fat pointer, made
+//! up of a pointer to the underlying value and a pointer to the vtable of the
+//! value's implementation of the interface. The vtable holds a function
+//! pointer for every method of the interface, in declaration order.
+
+use crate::{builder::StructBuilder, layout::Repr, TypeId, TypeTable};
+
+/// Return the name of the vtable type of the given interface.
+pub fn vtable_name(interface: &str) -> String {
+    format!("{}.vtable", interface)
+}
+
+/// Utility type for creating the vtable and fat pointer types of an
+/// interface.
+#[derive(Debug)]
+pub struct VTableBuilder {
+    interface: String,
+    methods: Vec<String>,
+}
+
+impl VTableBuilder {
+    /// Create a new vtable builder for the given interface.
+    pub fn new<S: ToString>(interface: S) -> Self {
+        VTableBuilder { interface: interface.to_string(), methods: vec![] }
+    }
+    /// Add a method slot to the vtable.
+    pub fn method<S: ToString>(mut self, name: S) -> Self {
+        self.methods.push(name.to_string());
+        self
+    }
+    /// Build the vtable type, and the fat pointer type that holds values of
+    /// the interface type. The fat pointer type is named after the interface.
+    /// Returns the IDs of the fat pointer and vtable types respectively.
+    pub fn build(self, table: &mut TypeTable) -> (TypeId, TypeId) {
+        let ptr = table.find_by_name("ref").expect("failed to find type").id;
+        // slots must stay in declaration order
+        let vtable = self
+            .methods
+            .iter()
+            .fold(StructBuilder::new(vtable_name(&self.interface)), |builder, method| {
+                builder.field(method, table.find(ptr).unwrap())
+            })
+            .repr(Repr::C)
+            .build(table)
+            .id;
+        let fat = StructBuilder::new(self.interface)
+            .field("data", table.find(ptr).unwrap())
+            .field("vtable", table.find(ptr).unwrap())
+            .repr(Repr::C)
+            .build(table)
+            .id;
+        (fat, vtable)
+    }
+}
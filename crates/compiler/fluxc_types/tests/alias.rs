@@ -0,0 +1,113 @@
+use fluxc_ast::{Operation, Primitive, Stmt, TypeExpr, Union};
+use fluxc_lexer::lex;
+use fluxc_parser::parse;
+use fluxc_types::alias::{AliasError, Aliases};
+use pretty_assertions::assert_eq;
+
+/// Parse the type aliases declared in the given source.
+fn aliases(src: &str) -> Aliases {
+    let ast = parse(lex(src).expect("Lexing failed!")).expect("Parsing failed!");
+    let mut aliases = Aliases::new();
+    for stmt in ast.stmts {
+        if let Stmt::TypeDeclaration(decl) = stmt.value {
+            let params = decl.value.generics.iter().map(|param| param.value.ident.value.clone());
+            aliases.declare_generic(
+                decl.value.ident.value,
+                params.collect(),
+                decl.value.value.value,
+            );
+        }
+    }
+    aliases
+}
+
+fn reference(name: &str) -> TypeExpr {
+    TypeExpr::Primitive(Primitive::Ref(name.into()))
+}
+
+#[test]
+fn test_alias_resolve() {
+    let aliases = aliases("type Num = int | float\ntype Pair = (Num, string)");
+    assert_eq!(
+        aliases.resolve(&reference("Pair")),
+        TypeExpr::Primitive(Primitive::Tuple(vec![
+            TypeExpr::Operation(Operation::Union(Union::of(
+                TypeExpr::Primitive(Primitive::Int),
+                TypeExpr::Primitive(Primitive::Float)
+            ))),
+            TypeExpr::Primitive(Primitive::String),
+        ]))
+    );
+    // references to other types are left as-is
+    assert_eq!(aliases.resolve(&reference("Point")), reference("Point"));
+    assert!(!aliases.is_recursive("Pair"));
+    assert_eq!(aliases.check("Pair"), Ok(()));
+}
+
+#[test]
+fn test_alias_recursive() {
+    let aliases = aliases("type List = () | (int, List)");
+    let list = aliases.resolve(&reference("List"));
+    assert_eq!(
+        list,
+        TypeExpr::Operation(Operation::Union(Union::of(
+            TypeExpr::Primitive(Primitive::Unit),
+            TypeExpr::Primitive(Primitive::Tuple(vec![
+                TypeExpr::Primitive(Primitive::Int),
+                TypeExpr::Circular(reference("List").into()),
+            ]))
+        )))
+    );
+    assert_eq!(list.to_string(), "unit | (int, List)");
+    assert_eq!(aliases.unfold(&TypeExpr::Circular(reference("List").into())), list);
+    assert!(aliases.is_recursive("List"));
+    assert_eq!(aliases.check("List"), Ok(()));
+}
+
+#[test]
+fn test_alias_ill_formed() {
+    let aliases = aliases("type A = A | int\ntype B = C\ntype C = B\ntype D = (int, D)");
+    assert_eq!(aliases.check("A"), Err(AliasError::Cyclic("A".into())));
+    assert_eq!(aliases.check("B"), Err(AliasError::Cyclic("B".into())));
+    assert_eq!(aliases.check("D"), Err(AliasError::InfiniteSize("D".into())));
+    // resolution always terminates
+    assert_eq!(aliases.resolve(&reference("B")), TypeExpr::Circular(reference("B").into()));
+}
+
+#[test]
+fn test_alias_generic() {
+    let pairs = aliases("type Pair<A, B> = (A, B)\ntype Point = Pair<int, int>");
+    let int = TypeExpr::Primitive(Primitive::Int);
+    assert_eq!(pairs.params("Pair"), Some(&["A".to_string(), "B".to_string()][..]));
+    assert_eq!(
+        pairs.resolve(&reference("Point")),
+        TypeExpr::Primitive(Primitive::Tuple(vec![int.clone(), int.clone()]))
+    );
+    // recursive generic aliases are unfolded with the same type arguments
+    let aliases = aliases("type List<T> = () | (T, List<T>)");
+    let list = TypeExpr::Generic("List".into(), vec![int.clone()]);
+    assert!(aliases.is_recursive("List"));
+    assert_eq!(aliases.check("List"), Ok(()));
+    assert_eq!(
+        aliases.resolve(&list),
+        TypeExpr::Operation(Operation::Union(Union::of(
+            TypeExpr::Primitive(Primitive::Unit),
+            TypeExpr::Primitive(Primitive::Tuple(vec![
+                int,
+                TypeExpr::Circular(list.clone().into())
+            ])),
+        )))
+    );
+}
+
+#[test]
+fn test_alias_conditional() {
+    let conditionals = aliases(
+        "type First<T> = T extends (infer A, infer B) ? A : never\ntype Exclude<T, U> = T extends U ? never : T\ntype A = First<(int, string)>\ntype B = Exclude<int | string | float, string>",
+    );
+    assert_eq!(conditionals.resolve(&reference("A")), TypeExpr::Primitive(Primitive::Int));
+    assert_eq!(conditionals.resolve(&reference("B")).to_string(), "int | float");
+
+    let cyclic = aliases("type A = A extends int ? 1 : 2");
+    assert_eq!(cyclic.check("A"), Err(AliasError::Cyclic("A".into())));
+}
@@ -0,0 +1,571 @@
+use fluxc_ast::{Numeric, Operation, Primitive, Stmt, TypeExpr, Union, AST};
+use fluxc_errors::ErrorKind;
+use fluxc_lexer::lex;
+use fluxc_parser::parse;
+use fluxc_types::infer::{Inference, Ty, TypeError};
+use pretty_assertions::assert_eq;
+
+/// Parse and infer the types of the given source.
+fn infer(src: &str) -> Result<(AST, Inference), TypeError> {
+    let ast = parse(lex(src).expect("Lexing failed!")).expect("Parsing failed!");
+    let mut inference = Inference::new();
+    inference.infer_ast(&ast)?;
+    Ok((ast, inference))
+}
+
+/// Return the inferred type of the named value.
+fn type_of(inference: &Inference, name: &str) -> TypeExpr {
+    inference.lookup(name).expect("undeclared value").to_type_expr()
+}
+
+#[test]
+fn test_infer_literal() {
+    let (_, inference) = infer("let x = 1").unwrap();
+    assert_eq!(type_of(&inference, "x"), TypeExpr::Primitive(Primitive::Int));
+}
+
+#[test]
+fn test_infer_expressions() {
+    let (ast, inference) = infer("let x = 1\nlet y = x + 2").unwrap();
+    assert_eq!(type_of(&inference, "y"), TypeExpr::Primitive(Primitive::Int));
+    // every expression is assigned a type
+    let Stmt::Declaration(decl) = &ast.stmts[1].value else { panic!("expected a declaration") };
+    assert_eq!(
+        inference.type_of(&decl.value.value.span),
+        Some(TypeExpr::Primitive(Primitive::Int))
+    );
+}
+
+#[test]
+fn test_infer_unannotated_params() {
+    let (_, inference) = infer(
+        r#"
+add a, b -> int {
+    return a + b
+}
+"#,
+    )
+    .unwrap();
+    let Some(Ty::Func(params, _)) = inference.lookup("add") else {
+        panic!("expected a function type")
+    };
+    for param in params {
+        assert_eq!(param.to_type_expr(), TypeExpr::Primitive(Primitive::Int));
+    }
+}
+
+#[test]
+fn test_infer_generic_function() {
+    // apply is generalised over the type of x, so may be called with
+    // arguments of different types
+    let (_, inference) = infer(
+        r#"
+apply f, x -> int {
+    return f x
+}
+inc n: int -> int {
+    return n + 1
+}
+len s: string -> int {
+    return 0
+}
+let a = apply inc, 1
+let b = apply len, "b"
+"#,
+    )
+    .unwrap();
+    assert_eq!(type_of(&inference, "a"), TypeExpr::Primitive(Primitive::Int));
+    assert_eq!(type_of(&inference, "b"), TypeExpr::Primitive(Primitive::Int));
+}
+
+#[test]
+fn test_infer_union_assignment() {
+    let (_, inference) = infer("let x: int | float = 1").unwrap();
+    assert_eq!(
+        type_of(&inference, "x"),
+        TypeExpr::Operation(Operation::Union(Union::of(
+            TypeExpr::Primitive(Primitive::Int),
+            TypeExpr::Primitive(Primitive::Float)
+        )))
+    );
+}
+
+#[test]
+fn test_infer_mismatch() {
+    let src = "let x: int = \"x\"";
+    let err = infer(src).err().unwrap();
+    assert!(matches!(err.kind, ErrorKind::E0204(_, _)));
+    assert_eq!(err.kind.to_string(), "E0204 - mismatched types: expected `int`, found `string`");
+    assert_eq!(&src[err.span], "\"x\"");
+}
+
+#[test]
+fn test_infer_type_at() {
+    let src = "let x = 1\nlet y = x + 2";
+    let (_, inference) = infer(src).unwrap();
+    let offset = src.rfind('x').unwrap();
+    let (span, ty) = inference.type_at(offset).unwrap();
+    assert_eq!(&src[span], "x");
+    assert_eq!(ty.to_string(), "int");
+    assert_eq!(inference.type_at(src.len() + 1), None);
+}
+
+#[test]
+fn test_infer_undeclared() {
+    let err = infer("let x = y").err().unwrap();
+    assert!(matches!(err.kind, ErrorKind::E0205(name) if name == "y"));
+}
+
+#[test]
+fn test_infer_fields() {
+    let (_, inference) = infer(
+        r#"
+struct Point { x: int, y: int }
+let p = Point { x: 1, y: 2 }
+let x = p.x
+"#,
+    )
+    .unwrap();
+    assert_eq!(type_of(&inference, "p"), TypeExpr::Primitive(Primitive::Ref("Point".into())));
+    assert_eq!(type_of(&inference, "x"), TypeExpr::Primitive(Primitive::Int));
+}
+
+#[test]
+fn test_infer_missing_field() {
+    let err = infer("struct P { x: int, y: int }\nlet p = P { x: 1 }").unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::E0227(field, name) if field == "y" && name == "P"));
+    let err = infer("struct P { x: int, y: int }\nlet p = P {}").unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::E0227(field, name) if field == "x" && name == "P"));
+}
+
+#[test]
+fn test_infer_static_method_call() {
+    let (_, inference) = infer(
+        r#"
+class Box<T> {
+    value: T
+
+    of value: T -> Box<T> {
+        return Box { value: value }
+    }
+}
+let b = Box.of 1
+"#,
+    )
+    .unwrap();
+    assert_eq!(type_of(&inference, "b").to_string(), "Box<int>");
+}
+
+#[test]
+fn test_infer_alias() {
+    let (_, inference) = infer("type Num = int | float\nlet x: Num = 1").unwrap();
+    assert_eq!(type_of(&inference, "x").to_string(), "int | float");
+}
+
+#[test]
+fn test_infer_type_params() {
+    let (_, inference) = infer(
+        r#"
+id<T> x: T -> T {
+    return x
+}
+let a = id 1
+let b = id "b"
+"#,
+    )
+    .unwrap();
+    assert_eq!(type_of(&inference, "a"), TypeExpr::Primitive(Primitive::Int));
+    assert_eq!(type_of(&inference, "b"), TypeExpr::Primitive(Primitive::String));
+}
+
+#[test]
+fn test_infer_generic_struct() {
+    let (_, inference) = infer(
+        r#"
+struct Box<T> { value: T }
+let b = Box { value: 1 }
+let v = b.value
+"#,
+    )
+    .unwrap();
+    assert_eq!(type_of(&inference, "b").to_string(), "Box<int>");
+    assert_eq!(type_of(&inference, "v"), TypeExpr::Primitive(Primitive::Int));
+}
+
+#[test]
+fn test_infer_bound() {
+    let src = r#"
+max<T extends int | float> a: T, b: T -> T {
+    return a
+}
+let x = max 1, 2
+let y = max "a", "b"
+"#;
+    let err = infer(src).err().unwrap();
+    assert_eq!(
+        err.kind.to_string(),
+        "E0217 - type `string` does not satisfy the bound `T extends int | float`"
+    );
+}
+
+#[test]
+fn test_infer_keyof() {
+    let src = r#"
+struct Point { x: int, y: int }
+let x: "x" extends keyof Point ? int : string = 1
+let z: "z" extends keyof Point ? int : string = 1
+"#;
+    let err = infer(src).err().unwrap();
+    assert_eq!(err.kind.to_string(), "E0204 - mismatched types: expected `string`, found `int`");
+    assert_eq!(&src[err.span], "1");
+    let (_, inference) =
+        infer("struct Point { x: int, y: int }\nlet k: keyof Point | int = 1").unwrap();
+    assert_eq!(type_of(&inference, "k").to_string(), "int | \"x\" | \"y\"");
+}
+
+#[test]
+fn test_infer_narrowing() {
+    let src = r#"
+f x: int | () -> int {
+    if x == () {
+        return 0
+    }
+    return x + 1
+}
+g x: int | string -> int {
+    let y = if x == 1 { x } else { 0 }
+    if x != "a" {
+        let z = x
+    }
+    return y
+}
+mut x: int | () = 1
+if x != () {
+    x = ()
+}
+"#;
+    let (_, inference) = infer(src).unwrap();
+    // x is narrowed to int after the early return
+    let offset = src.find("x + 1").unwrap();
+    assert_eq!(inference.type_at(offset).unwrap().1, TypeExpr::Primitive(Primitive::Int));
+    // and within the branches of conditionals
+    let offset = src.find("{ x }").unwrap() + 2;
+    assert_eq!(inference.type_at(offset).unwrap().1, TypeExpr::Primitive(Primitive::Int));
+    let offset = src.find("z = x").unwrap() + 4;
+    assert_eq!(inference.type_at(offset).unwrap().1.to_string(), "int | string");
+    // assignment widens x back to its declared type
+    assert_eq!(type_of(&inference, "x").to_string(), "unit | int");
+    // values of disjoint types cannot be compared
+    let err = infer("let x: int | float = 1\nlet y = x == \"a\"").err().unwrap();
+    assert!(matches!(err.kind, ErrorKind::E0204(_, _)));
+}
+
+#[test]
+fn test_infer_exhaustive() {
+    let (_, inference) =
+        infer("let b = true\nlet n = if b == true { 1 } else if b == false { 2 }").unwrap();
+    assert_eq!(type_of(&inference, "n"), TypeExpr::Primitive(Primitive::Int));
+    // an `if` without an `else` that may not run has no value to produce
+    let src = "let b = true\nlet m = if b == true { 1 }";
+    let err = infer(src).err().unwrap();
+    assert_eq!(
+        err.kind.to_string(),
+        "E0229 - non-exhaustive `if` produces a value of type `int` without an `else` branch"
+    );
+    assert_eq!(&src[err.span], "if b == true { 1 }");
+    // unless its branches produce nothing
+    infer("mut n = 0\nlet b = true\nif b == true {\n    n = 1\n}").unwrap();
+}
+
+#[test]
+fn test_infer_match() {
+    let err =
+        infer("let x: bool | () = true\nlet y = match x { true => 1, false => 2 }").err().unwrap();
+    assert_eq!(
+        err.kind.to_string(),
+        "E0220 - non-exhaustive match: values of type `unit` are not covered"
+    );
+    let (_, inference) =
+        infer("let x: bool | () = true\nlet y = match x { true => 1, false => 2, () => 3 }")
+            .unwrap();
+    assert_eq!(type_of(&inference, "y"), TypeExpr::Primitive(Primitive::Int));
+    // patterns other than literals may not match, so do not cover any values
+    let err = infer("let k = 5\nlet x = 3\nlet y = match x { k => 1 }").err().unwrap();
+    assert_eq!(
+        err.kind.to_string(),
+        "E0220 - non-exhaustive match: values of type `int` are not covered"
+    );
+    // an `else` branch covers the values the patterns do not
+    let (_, inference) =
+        infer("let k = 5\nlet x = 3\nlet y = match x { k => 1, else => 2 }").unwrap();
+    assert_eq!(type_of(&inference, "y"), TypeExpr::Primitive(Primitive::Int));
+}
+
+#[test]
+fn test_infer_narrowing_loops_and_matches() {
+    let src = r#"
+f x: bool | () -> int {
+    return match x {
+        () => 0,
+        true => {
+            let t = x
+            1
+        },
+        false => 2,
+    }
+}
+g x: int | () -> int {
+    mut n = 0
+    while x != () {
+        n = x
+    }
+    return n
+}
+"#;
+    let (_, inference) = infer(src).unwrap();
+    // match branches see the value their pattern matched
+    let offset = src.find("t = x").unwrap() + 4;
+    assert_eq!(inference.type_at(offset).unwrap().1, TypeExpr::Primitive(Primitive::True));
+    // and the body of a loop sees the values its condition holds for
+    let offset = src.find("n = x").unwrap() + 4;
+    assert_eq!(inference.type_at(offset).unwrap().1, TypeExpr::Primitive(Primitive::Int));
+}
+
+#[test]
+fn test_infer_closures() {
+    let (_, inference) = infer(
+        r#"
+apply f: (int) -> int, x: int -> int {
+    return f x
+}
+let n = 1
+let add = (x) -> { x + n }
+let y = add 2
+let z = apply add, 3
+let make_adder = (a: int) -> (int) -> int { (b) -> { a + b } }
+let w = (make_adder 1) 2
+"#,
+    )
+    .unwrap();
+    let int = || TypeExpr::Primitive(Primitive::Int);
+    assert_eq!(type_of(&inference, "add"), TypeExpr::Function(vec![int()], int().into()));
+    assert_eq!(type_of(&inference, "add").to_string(), "(int) -> int");
+    assert_eq!(type_of(&inference, "make_adder").to_string(), "(int) -> (int) -> int");
+    for name in ["y", "z", "w"] {
+        assert_eq!(type_of(&inference, name), int());
+    }
+    // only functions can be called
+    let err = infer("let x = 1\nlet y = x 2").err().unwrap();
+    assert!(matches!(err.kind, ErrorKind::E0204(_, _)));
+}
+
+#[test]
+fn test_infer_optionals() {
+    let src = r#"
+struct User { name: string, age: int }
+f user: User? -> string {
+    let name = user?.name
+    return name ?? "anonymous"
+}
+g x: int? -> int {
+    if x == () {
+        return 0
+    }
+    return x + 1
+}
+let x: int? = 1
+let y = x ?? 0
+"#;
+    let (_, inference) = infer(src).unwrap();
+    // optional field accesses are unit if their receiver is
+    let offset = src.find("name ??").unwrap();
+    assert_eq!(inference.type_at(offset).unwrap().1.to_string(), "unit | string");
+    // coalescing removes unit from the left hand side
+    assert_eq!(type_of(&inference, "y"), TypeExpr::Primitive(Primitive::Int));
+    // optional values must be narrowed before they are used
+    for src in
+        ["let x: int? = 1\nlet y = x + 1", "struct P { x: int }\nf p: P? -> int { return p.x }"]
+    {
+        let err = infer(src).err().unwrap();
+        assert!(matches!(err.kind, ErrorKind::E0222(_)));
+    }
+}
+
+#[test]
+fn test_infer_error_propagation() {
+    let src = r#"
+parse_digit n: int -> int | Error {
+    if n == 0 {
+        return 0
+    }
+    return Error { message: "not a digit" }
+}
+double_digit n: int -> int | Error {
+    let digit = parse_digit n?
+    return digit * 2
+}
+"#;
+    let (_, inference) = infer(src).unwrap();
+    // the error is propagated, so the digit is never an error
+    let offset = src.find("digit * 2").unwrap();
+    assert_eq!(inference.type_at(offset).unwrap().1, TypeExpr::Primitive(Primitive::Int));
+    // errors can only be propagated from values that may be errors
+    let err = infer("f x: int -> int | Error {\n    return x?\n}").err().unwrap();
+    assert!(matches!(err.kind, ErrorKind::E0223(_)));
+    // to functions that may return them
+    let err =
+        infer(&src.replace("double_digit n: int -> int | Error", "double_digit n: int -> int"))
+            .err()
+            .unwrap();
+    assert!(matches!(err.kind, ErrorKind::E0204(_, _)));
+}
+
+#[test]
+fn test_infer_casts() {
+    let src = r#"
+let count = 3
+let ratio = count as float / 2.0
+let truncated = ratio as int
+let flag = true as int
+let value: int | string = 1
+let number = value as int
+"#;
+    let (_, inference) = infer(src).unwrap();
+    assert_eq!(type_of(&inference, "ratio"), TypeExpr::Primitive(Primitive::Float));
+    assert_eq!(type_of(&inference, "truncated"), TypeExpr::Primitive(Primitive::Int));
+    assert_eq!(type_of(&inference, "flag"), TypeExpr::Primitive(Primitive::Int));
+    // members are extracted from unions
+    assert_eq!(type_of(&inference, "number"), TypeExpr::Primitive(Primitive::Int));
+    // types with no conversion between them cannot be cast
+    let err = infer("let x = \"1\" as int").err().unwrap();
+    assert!(matches!(err.kind, ErrorKind::E0224(_, _)));
+    let err = infer("let x: int | string = 1\nlet y = x as float").err().unwrap();
+    assert!(matches!(err.kind, ErrorKind::E0224(_, _)));
+}
+
+#[test]
+fn test_infer_sized_numbers() {
+    let src = r#"
+let flags: u8 = 1
+let next = flags + 2
+let mask = 255u8
+let ratio = 0.5f32
+let total = (mask as i32) + 1000i32
+let default = 3
+"#;
+    let (_, inference) = infer(src).unwrap();
+    let numeric = |numeric| TypeExpr::Primitive(Primitive::Numeric(numeric));
+    // literals take on the numeric type they are used as
+    assert_eq!(type_of(&inference, "next"), numeric(Numeric::U8));
+    assert_eq!(type_of(&inference, "mask"), numeric(Numeric::U8));
+    assert_eq!(type_of(&inference, "ratio"), numeric(Numeric::F32));
+    assert_eq!(type_of(&inference, "total"), numeric(Numeric::I32));
+    // and otherwise default to int
+    assert_eq!(type_of(&inference, "default"), TypeExpr::Primitive(Primitive::Int));
+    // literals must be within the range of their type
+    let err = infer("let x: u8 = 256").err().unwrap();
+    assert!(matches!(&err.kind, ErrorKind::E0226(value, ty) if value == "256" && ty == "u8"));
+    let err = infer("let x: i8 = 1\nlet y = x - 200").err().unwrap();
+    assert!(matches!(err.kind, ErrorKind::E0226(_, _)));
+    // numbers are never implicitly converted between numeric types
+    let err = infer("let x: u8 = 1\nlet y: i32 = 2\nlet z = x + y").err().unwrap();
+    assert!(matches!(err.kind, ErrorKind::E0225(_, _)));
+    let err = infer("let x: f32 = 1").err().unwrap();
+    assert!(matches!(err.kind, ErrorKind::E0204(_, _)));
+}
+
+#[test]
+fn test_infer_assignability_follows_extends() {
+    // `bool` is exactly `true | false`, so is assignable to the union
+    let (_, inference) = infer("let b = 1 == 1\nlet c: true | false = b").unwrap();
+    assert_eq!(type_of(&inference, "c"), TypeExpr::Primitive(Primitive::Bool));
+    // but not to either member alone
+    assert!(infer("let b = 1 == 1\nlet c: true = b").is_err());
+}
+
+#[test]
+fn test_infer_interface_assignability() {
+    let src = r#"
+interface Shape {
+    scale self, n: int -> int
+}
+struct Square { size: int }
+struct Circle { radius: int }
+impl Shape for Square {
+    scale self, n: int -> int {
+        return self.size * n
+    }
+}
+let square = Square { size: 2 }
+let shape: Shape = square
+"#;
+    // values of implementing types may be assigned to the interface
+    let (_, inference) = infer(src).unwrap();
+    assert_eq!(type_of(&inference, "shape"), TypeExpr::Primitive(Primitive::Ref("Shape".into())));
+    // but values of other types may not
+    let err = infer(&format!("{src}let circle: Shape = Circle {{ radius: 1 }}")).err().unwrap();
+    assert!(matches!(err.kind, ErrorKind::E0204(_, _)));
+}
+
+#[test]
+fn test_infer_argument_assignability() {
+    // arguments need only be assignable to their parameters
+    let (_, inference) = infer(
+        r#"
+interface Shape {
+    scale self, n: int -> int
+}
+struct Sq { size: int }
+impl Shape for Sq {
+    scale self, n: int -> int {
+        return self.size * n
+    }
+}
+one a: int | bool -> int {
+    return 1
+}
+area s: Shape -> int {
+    return s.scale 2
+}
+let x = one 1
+let y = one true
+let z = area Sq { size: 2 }
+"#,
+    )
+    .unwrap();
+    assert_eq!(type_of(&inference, "x"), TypeExpr::Primitive(Primitive::Int));
+    assert_eq!(type_of(&inference, "z"), TypeExpr::Primitive(Primitive::Int));
+    let err = infer("one a: int | bool -> int {\n    return 1\n}\nlet x = one \"a\"").unwrap_err();
+    assert_eq!(
+        err.kind.to_string(),
+        "E0204 - mismatched types: expected `bool | int`, found `string`"
+    );
+}
+
+#[test]
+fn test_infer_interface_bound() {
+    let src = r#"
+interface Comparable {
+    compare self, other: Self -> int
+}
+struct N { value: int }
+struct M { value: int }
+impl Comparable for N {
+    compare self, other: N -> int {
+        return self.value - other.value
+    }
+}
+max<T extends Comparable> a: T, b: T -> T {
+    return a
+}
+let x = max N { value: 1 }, N { value: 2 }
+"#;
+    // implementations satisfy interface bounds
+    let (_, inference) = infer(src).unwrap();
+    assert_eq!(type_of(&inference, "x"), TypeExpr::Primitive(Primitive::Ref("N".into())));
+    let err = infer(&format!("{src}let y = max M {{ value: 1 }}, M {{ value: 2 }}")).unwrap_err();
+    assert_eq!(
+        err.kind.to_string(),
+        "E0217 - type `M` does not satisfy the bound `T extends Comparable`"
+    );
+}
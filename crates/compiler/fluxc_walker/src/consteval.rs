@@ -0,0 +1,334 @@
+//! Evaluates the initializers of constants at compile-time.
+//!
+//! Constant initializers may be made up of literals, other constants,
+//! arithmetic, boolean, string and comparison operations, casts between
+//! primitives, conditionals, and calls to functions declared with `const`. The value of a constant is stored
+//! as the literal type of its variable, such as `Primitive::IntLiteral`.
+
+use std::{collections::HashMap, ops::Range};
+
+use fluxc_ast::{
+    BinaryOp, Block, Declaration, Expr, FuncDecl, Literal, Mutability, Node, Numeric, Primitive,
+    Stmt, TypeExpr, UnaryOp,
+};
+use fluxc_errors::{ErrorKind, FatalError};
+use fluxc_types::lower::erase;
+
+use crate::Walker;
+
+/// The maximum depth of nested calls to `const` functions, which bounds the
+/// evaluation of recursive functions.
+const MAX_CALL_DEPTH: usize = 128;
+
+/// Return the literal type holding the given value, if it has one.
+pub fn literal_type(literal: &Literal) -> Option<TypeExpr> {
+    let primitive = match literal {
+        Literal::Int(int) => Primitive::IntLiteral(*int),
+        Literal::Float(float) => Primitive::FloatLiteral(*float),
+        Literal::String(string) => Primitive::StringLiteral(string.clone()),
+        Literal::Char(c) => Primitive::CharLiteral(*c),
+        Literal::Bool(bool) => (*bool).into(),
+        Literal::Unit => Primitive::Unit,
+        Literal::Array(_) => return None,
+    };
+    Some(TypeExpr::Primitive(primitive))
+}
+
+/// Return the value held by the given literal type, if it is one.
+pub fn literal_value(ty: &TypeExpr) -> Option<Literal> {
+    match ty {
+        TypeExpr::Primitive(Primitive::IntLiteral(int)) => Some(Literal::Int(*int)),
+        TypeExpr::Primitive(Primitive::FloatLiteral(float)) => Some(Literal::Float(*float)),
+        TypeExpr::Primitive(Primitive::StringLiteral(string)) => {
+            Some(Literal::String(string.clone()))
+        }
+        TypeExpr::Primitive(Primitive::CharLiteral(c)) => Some(Literal::Char(*c)),
+        TypeExpr::Primitive(Primitive::True) => Some(Literal::Bool(true)),
+        TypeExpr::Primitive(Primitive::False) => Some(Literal::Bool(false)),
+        TypeExpr::Primitive(Primitive::Unit) => Some(Literal::Unit),
+        _ => None,
+    }
+}
+
+/// Enumeration of the ways evaluation can be interrupted.
+enum Interrupt {
+    /// Evaluation failed.
+    Error(FatalError),
+    /// A `return` statement with the given span returned the given value from
+    /// the enclosing `const` function.
+    Return(Literal, Range<usize>),
+}
+
+impl From<FatalError> for Interrupt {
+    fn from(err: FatalError) -> Self {
+        Interrupt::Error(err)
+    }
+}
+
+/// The state of the evaluation of a single constant.
+struct ConstEval<'a> {
+    walker: &'a Walker,
+    /// The name of the constant being evaluated.
+    name: &'a str,
+    /// The variables bound by the `const` function being evaluated.
+    locals: HashMap<String, Literal>,
+    /// The number of nested `const` function calls being evaluated.
+    depth: usize,
+}
+
+impl<'a> ConstEval<'a> {
+    /// Create an error spanning the given range, reporting that it cannot be
+    /// evaluated at compile-time.
+    fn not_const(&self, span: &Range<usize>) -> FatalError {
+        self.walker.error_at(span.clone(), ErrorKind::E0213(self.name.to_string()))
+    }
+
+    /// Create an error spanning the given range, reporting that its
+    /// evaluation failed for the given reason.
+    fn failed(&self, span: &Range<usize>, reason: &str) -> FatalError {
+        self.walker
+            .error_at(span.clone(), ErrorKind::E0214(self.name.to_string(), reason.to_string()))
+    }
+
+    /// Evaluate the given expression.
+    fn eval(&mut self, expr: &Node<Expr>) -> Result<Literal, Interrupt> {
+        match &expr.value {
+            Expr::Literal(literal) => match &literal.value {
+                Literal::Array(_) => Err(self.not_const(&expr.span).into()),
+                value => Ok(value.clone()),
+            },
+            Expr::Ident(ident) => Ok(self.lookup(ident)?),
+            Expr::BinaryExpr(binary) if !binary.value.kind.is_assignment() => {
+                let lhs = self.eval(&binary.value.lhs)?;
+                // logical and coalescing operators short-circuit
+                match (binary.value.kind, &lhs) {
+                    (BinaryOp::LogicalAnd, Literal::Bool(false))
+                    | (BinaryOp::LogicalOr, Literal::Bool(true)) => return Ok(lhs),
+                    (BinaryOp::Coalesce, Literal::Unit) => return self.eval(&binary.value.rhs),
+                    (BinaryOp::Coalesce, _) => return Ok(lhs),
+                    _ => (),
+                }
+                let rhs = self.eval(&binary.value.rhs)?;
+                Ok(self.binary(&expr.span, binary.value.kind, lhs, rhs)?)
+            }
+            Expr::UnaryExpr(unary) => {
+                match (&unary.value.kind, self.eval(&unary.value.expr)?) {
+                    (UnaryOp::LogicalNot, Literal::Bool(bool)) => Ok(Literal::Bool(!bool)),
+                    (UnaryOp::BitwiseNot, Literal::Int(int)) => Ok(Literal::Int(!int)),
+                    (UnaryOp::Negation, Literal::Int(int)) => Ok(int
+                        .checked_neg()
+                        .map(Literal::Int)
+                        .ok_or_else(|| self.failed(&expr.span, "integer overflow"))?),
+                    (UnaryOp::Negation, Literal::Float(float)) => Ok(Literal::Float(-float)),
+                    _ => Err(self.not_const(&expr.span).into()),
+                }
+            }
+            Expr::Cast(cast) => {
+                let TypeExpr::Primitive(target) = &cast.value.ty.value else {
+                    return Err(self.not_const(&expr.span).into());
+                };
+                match (self.eval(&cast.value.expr)?, target) {
+                    (Literal::Int(int), Primitive::Float) => Ok(Literal::Float(int as f64)),
+                    // float to int conversions saturate, as they do at runtime
+                    (Literal::Float(float), Primitive::Int) => Ok(Literal::Int(float as i64)),
+                    (Literal::Char(c), Primitive::Int) => Ok(Literal::Int(c as i64)),
+                    (Literal::Bool(bool), Primitive::Int) => Ok(Literal::Int(bool as i64)),
+                    (Literal::Int(int), Primitive::Char) => Ok(u32::try_from(int)
+                        .ok()
+                        .and_then(char::from_u32)
+                        .map(Literal::Char)
+                        .ok_or_else(|| self.failed(&expr.span, "invalid character"))?),
+                    // sized numbers are converted as they are at runtime,
+                    // keeping the lowest bits of integers
+                    (Literal::Int(int), Primitive::Numeric(Numeric::F32)) => {
+                        Ok(Literal::Float(int as f32 as f64))
+                    }
+                    (Literal::Float(float), Primitive::Numeric(Numeric::F32)) => {
+                        Ok(Literal::Float(float as f32 as f64))
+                    }
+                    (Literal::Float(float), Primitive::Numeric(numeric)) => {
+                        let int =
+                            if numeric.is_signed() { float as i64 } else { float as u64 as i64 };
+                        Ok(Literal::Int(numeric.wrap(int)))
+                    }
+                    (Literal::Int(int), Primitive::Numeric(numeric)) => {
+                        Ok(Literal::Int(numeric.wrap(int)))
+                    }
+                    (Literal::Char(c), Primitive::Numeric(numeric)) => {
+                        Ok(Literal::Int(numeric.wrap(c as i64)))
+                    }
+                    (Literal::Bool(bool), Primitive::Numeric(_)) => Ok(Literal::Int(bool as i64)),
+                    // casts to the type of the value leave it as it is
+                    (value, target)
+                        if literal_type(&value).map(|ty| erase(&ty))
+                            == Some(TypeExpr::Primitive(target.clone())) =>
+                    {
+                        Ok(value)
+                    }
+                    _ => Err(self.not_const(&expr.span).into()),
+                }
+            }
+            Expr::Block(block) => self.eval_block(block),
+            Expr::Conditional(conditional) => {
+                let conditional = &conditional.value;
+                for if_stmt in std::iter::once(&conditional.if_stmt).chain(&conditional.else_ifs) {
+                    match self.eval(&if_stmt.value.condition)? {
+                        Literal::Bool(true) => return self.eval_block(&if_stmt.value.block),
+                        Literal::Bool(false) => (),
+                        _ => return Err(self.not_const(&if_stmt.value.condition.span).into()),
+                    }
+                }
+                match &conditional.else_stmt {
+                    Some(block) => self.eval_block(block),
+                    None => Ok(Literal::Unit),
+                }
+            }
+            Expr::FuncCall(call) => {
+                let args = call
+                    .value
+                    .args
+                    .iter()
+                    .map(|arg| self.eval(arg))
+                    .collect::<Result<Vec<_>, _>>()?;
+                match &call.value.callee.value {
+                    Expr::Ident(ident) => Ok(self.call(&expr.span, ident, args)?),
+                    _ => Err(self.not_const(&expr.span).into()),
+                }
+            }
+            _ => Err(self.not_const(&expr.span).into()),
+        }
+    }
+
+    /// Evaluate a block, producing the value of its trailing expression.
+    fn eval_block(&mut self, block: &Node<Block>) -> Result<Literal, Interrupt> {
+        let mut value = Literal::Unit;
+        for stmt in &block.value.stmts {
+            value = match &stmt.value {
+                Stmt::Declaration(decl) => {
+                    let value = self.eval(&decl.value.value)?;
+                    self.locals.insert(decl.value.ident.value.clone(), value);
+                    Literal::Unit
+                }
+                Stmt::Return(expr) => {
+                    let value = self.eval(expr)?;
+                    return Err(Interrupt::Return(value, stmt.span.clone()));
+                }
+                Stmt::Expr(expr) => self.eval(expr)?,
+                _ => return Err(self.not_const(&stmt.span).into()),
+            };
+        }
+        Ok(value)
+    }
+
+    /// Lookup the value of the given identifier, which must refer to a local
+    /// of the `const` function being evaluated, or to another constant.
+    fn lookup(&self, ident: &Node<String>) -> Result<Literal, FatalError> {
+        if let Some(value) = self.locals.get(&ident.value) {
+            return Ok(value.clone());
+        }
+        match self.walker.lookup_variable(&ident.value) {
+            Some(variable) if variable.mutability == Mutability::Constant => {
+                literal_value(&variable.ty).ok_or_else(|| self.not_const(&ident.span))
+            }
+            _ => Err(self.not_const(&ident.span)),
+        }
+    }
+
+    /// Evaluate a call to the `const` function with the given name.
+    fn call(
+        &mut self,
+        span: &Range<usize>,
+        ident: &Node<String>,
+        args: Vec<Literal>,
+    ) -> Result<Literal, FatalError> {
+        let Some(FuncDecl::Local { params, body, .. }) =
+            self.walker.lookup_const_function(&ident.value)
+        else {
+            return Err(self.not_const(&ident.span));
+        };
+        if self.depth == MAX_CALL_DEPTH {
+            return Err(self.failed(span, "recursion limit reached"));
+        }
+        let locals = params.iter().map(|param| param.value.ident.value.clone()).zip(args).collect();
+        let caller = std::mem::replace(&mut self.locals, locals);
+        self.depth += 1;
+        let result = self.eval_block(body);
+        self.depth -= 1;
+        self.locals = caller;
+        match result {
+            Ok(value) | Err(Interrupt::Return(value, _)) => Ok(value),
+            Err(Interrupt::Error(err)) => Err(err),
+        }
+    }
+
+    /// Evaluate a binary operation on the given operands.
+    fn binary(
+        &self,
+        span: &Range<usize>,
+        kind: BinaryOp,
+        lhs: Literal,
+        rhs: Literal,
+    ) -> Result<Literal, FatalError> {
+        use Literal::*;
+        let overflow = || self.failed(span, "integer overflow");
+        Ok(match (kind, lhs, rhs) {
+            (BinaryOp::Div | BinaryOp::Mod, Int(_), Int(0)) => {
+                return Err(self.failed(span, "division by zero"))
+            }
+            (BinaryOp::Plus, Int(a), Int(b)) => Int(a.checked_add(b).ok_or_else(overflow)?),
+            (BinaryOp::Minus, Int(a), Int(b)) => Int(a.checked_sub(b).ok_or_else(overflow)?),
+            (BinaryOp::Mul, Int(a), Int(b)) => Int(a.checked_mul(b).ok_or_else(overflow)?),
+            (BinaryOp::Div, Int(a), Int(b)) => Int(a.checked_div(b).ok_or_else(overflow)?),
+            (BinaryOp::Mod, Int(a), Int(b)) => Int(a.checked_rem(b).ok_or_else(overflow)?),
+            (BinaryOp::BitwiseAnd, Int(a), Int(b)) => Int(a & b),
+            (BinaryOp::BitwiseOr, Int(a), Int(b)) => Int(a | b),
+            (BinaryOp::BitwiseXor, Int(a), Int(b)) => Int(a ^ b),
+            (BinaryOp::Shl, Int(a), Int(b)) => {
+                Int(u32::try_from(b).ok().and_then(|b| a.checked_shl(b)).ok_or_else(overflow)?)
+            }
+            (BinaryOp::Shr, Int(a), Int(b)) => {
+                Int(u32::try_from(b).ok().and_then(|b| a.checked_shr(b)).ok_or_else(overflow)?)
+            }
+            (BinaryOp::Plus, Float(a), Float(b)) => Float(a + b),
+            (BinaryOp::Minus, Float(a), Float(b)) => Float(a - b),
+            (BinaryOp::Mul, Float(a), Float(b)) => Float(a * b),
+            (BinaryOp::Div, Float(a), Float(b)) => Float(a / b),
+            (BinaryOp::Mod, Float(a), Float(b)) => Float(a % b),
+            (BinaryOp::Plus, String(a), String(b)) => String(a + &b),
+            (BinaryOp::LogicalAnd, Bool(a), Bool(b)) => Bool(a && b),
+            (BinaryOp::LogicalOr, Bool(a), Bool(b)) => Bool(a || b),
+            (BinaryOp::Eq, a, b) => Bool(a == b),
+            (BinaryOp::Ne, a, b) => Bool(a != b),
+            (BinaryOp::Lt | BinaryOp::Gt | BinaryOp::Le | BinaryOp::Ge, a, b) => {
+                let ordering = match (a, b) {
+                    (Int(a), Int(b)) => a.partial_cmp(&b),
+                    (Float(a), Float(b)) => a.partial_cmp(&b),
+                    (Char(a), Char(b)) => a.partial_cmp(&b),
+                    (String(a), String(b)) => a.partial_cmp(&b),
+                    _ => return Err(self.not_const(span)),
+                };
+                Bool(ordering.is_some_and(|ordering| match kind {
+                    BinaryOp::Lt => ordering.is_lt(),
+                    BinaryOp::Gt => ordering.is_gt(),
+                    BinaryOp::Le => ordering.is_le(),
+                    _ => ordering.is_ge(),
+                }))
+            }
+            _ => return Err(self.not_const(span)),
+        })
+    }
+}
+
+impl Walker {
+    /// Evaluate the initializer of the given constant declaration at
+    /// compile-time.
+    pub fn eval_const(&self, decl: &Declaration) -> Result<Literal, FatalError> {
+        let mut eval =
+            ConstEval { walker: self, name: &decl.ident.value, locals: HashMap::new(), depth: 0 };
+        match eval.eval(&decl.value) {
+            Ok(value) => Ok(value),
+            Err(Interrupt::Return(_, span)) => Err(eval.not_const(&span)),
+            Err(Interrupt::Error(err)) => Err(err),
+        }
+    }
+}
@@ -0,0 +1,515 @@
+//! Defines an AST walker for performing semantic analysis.
+//!
+//! The walker resolves every name in the AST to its declaration, checks that
+//! the types it refers to exist, and lowers the AST into HIR, annotating every
+//! expression with the type inferred for it.
+//!
+//! Variables are declared in lexical scopes, which are entered and exited as
+//! the walker descends into modules, functions, blocks and loops. Variables
+//! declared in an inner scope shadow those of the same name in outer scopes,
+//! and are dropped when the scope is exited, at which point any that were
+//! never used are reported as warnings.
+
+mod consteval;
+mod lower;
+mod scope;
+
+pub use consteval::{literal_type, literal_value};
+pub use scope::*;
+
+use std::{ops::Range, rc::Rc};
+
+use fluxc_ast::{
+    ClassDecl, Declaration, FuncDecl, GenericParam, ImplDecl, InterfaceDecl, Mutability, Node,
+    Stmt, StructDecl, TypeDeclaration, TypeExpr,
+};
+use fluxc_errors::{ErrorKind, FatalError, Warning, WarningKind};
+use fluxc_hir::{
+    AsClass, AsFunction, AsInterface, AsMethod, AsStruct, Class, Function, FunctionKind, Impl,
+    Interface, LocalId, Struct, Variable,
+};
+use fluxc_span::{IntoSpan, SpanContext};
+use fluxc_types::{
+    alias::Aliases,
+    infer::{Inference, TypeError},
+};
+
+/// Represents a stack.
+#[derive(Debug)]
+pub struct Stack<T> {
+    /// The contents of the stack.
+    contents: Vec<T>,
+}
+
+impl<T> Stack<T> {
+    /// Creates a new, empty stack.
+    pub fn new() -> Stack<T> {
+        Stack { contents: Vec::new() }
+    }
+
+    /// Return the size of the stack.
+    pub fn size(&self) -> usize {
+        self.contents.len()
+    }
+
+    /// Get an object from the stack.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.contents.get(index)
+    }
+
+    /// Get a mutable reference to an object from the stack.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.contents.get_mut(index)
+    }
+
+    /// Get a mutable reference to the object on top of the stack.
+    pub fn last_mut(&mut self) -> Option<&mut T> {
+        self.contents.last_mut()
+    }
+
+    /// Get an object from the stack without checking if the stack is empty.
+    pub fn get_unchecked(&self, index: usize) -> &T {
+        self.contents.get(index).unwrap()
+    }
+
+    /// Push an item onto the stack.
+    pub fn push(&mut self, item: T) {
+        self.contents.push(item);
+    }
+
+    /// Pop an item off of the stack.
+    pub fn pop(&mut self) -> Option<T> {
+        self.contents.pop()
+    }
+
+    /// Find an item in the stack using the given predicate.
+    pub fn find<F: Fn(&T) -> bool>(&self, predicate: F) -> Option<&T> {
+        self.contents.iter().rev().find(|item| predicate(item))
+    }
+
+    /// Find an item in the stack using the given predicate, returning a mutable
+    /// reference to the item.
+    pub fn find_mut<F: Fn(&T) -> bool>(&mut self, predicate: F) -> Option<&mut T> {
+        self.contents.iter_mut().rev().find(|item| predicate(item))
+    }
+}
+
+impl<T> Default for Stack<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A utility for walking the AST.
+pub struct Walker {
+    context: Rc<SpanContext>,
+    current_function: Option<Function>,
+    scopes: Stack<Scope>,
+    functions: Stack<Function>,
+    const_functions: Vec<FuncDecl>,
+    interfaces: Stack<Interface>,
+    impls: Vec<Impl>,
+    aliases: Aliases,
+    structs: Vec<Struct>,
+    classes: Vec<Class>,
+    type_params: Stack<Vec<String>>,
+    next_local: LocalId,
+    inference: Inference,
+    warnings: Vec<Warning>,
+}
+
+impl Walker {
+    /// Create a new walker for the source file with the given context.
+    pub fn new(context: Rc<SpanContext>) -> Self {
+        Walker {
+            context,
+            current_function: None,
+            scopes: Stack::new(),
+            functions: Stack::new(),
+            const_functions: vec![],
+            interfaces: Stack::new(),
+            impls: vec![],
+            aliases: Aliases::new(),
+            // the standard error type is declared in every module
+            structs: vec![StructDecl::error().as_struct()],
+            classes: vec![],
+            type_params: Stack::new(),
+            next_local: 0,
+            inference: Inference::new(),
+            warnings: vec![],
+        }
+    }
+
+    /// Create a fatal error spanning the given node.
+    fn error<T>(&self, node: &Node<T>, kind: ErrorKind) -> FatalError {
+        self.error_at(node.span.clone(), kind)
+    }
+
+    /// Create a fatal error spanning the given range.
+    fn error_at(&self, span: Range<usize>, kind: ErrorKind) -> FatalError {
+        FatalError::new(span.into_span(self.context.clone()), kind)
+    }
+
+    /// Convert a type error raised during inference into a fatal error.
+    fn type_error(&self, err: TypeError) -> FatalError {
+        self.error_at(err.span, err.kind)
+    }
+
+    /// Return the current function. This clones the stored function.
+    pub fn current_function(&self) -> Option<&Function> {
+        match &self.current_function {
+            Some(s) => Some(s),
+            None => None,
+        }
+    }
+
+    /// Return the warnings raised so far.
+    pub fn warnings(&self) -> &[Warning] {
+        &self.warnings
+    }
+
+    /// Raise a warning spanning the given range.
+    fn warn(&mut self, span: Range<usize>, kind: WarningKind) {
+        self.warnings.push(Warning::new(span.into_span(self.context.clone()), kind));
+    }
+
+    /// Enter a new scope of the given kind.
+    pub fn enter_scope(&mut self, kind: ScopeKind) {
+        self.scopes.push(Scope::new(kind));
+    }
+
+    /// Exit the innermost scope, returning it. Variables declared in the scope
+    /// that were never used are reported, unless their name begins with an
+    /// underscore or they were declared at the top level of a module, where
+    /// they may be exported. Unused imports are always reported.
+    pub fn exit_scope(&mut self) -> Scope {
+        let scope = self.scopes.pop().expect("exited a scope that was never entered");
+        if scope.kind != ScopeKind::Module {
+            for binding in scope.bindings.iter().filter(|b| !b.used) {
+                let name = &binding.variable.name;
+                if !name.starts_with('_') && name != "self" {
+                    self.warn(binding.span.clone(), WarningKind::W0001(name.clone()));
+                }
+            }
+        }
+        for import in scope.imports.iter().filter(|i| !i.used) {
+            self.warn(import.span.clone(), WarningKind::W0002(import.name.clone()));
+        }
+        scope
+    }
+
+    /// Declares all functions and classes in the given statements.
+    pub fn declare_all_in_stmts(&mut self, stmts: &Vec<Node<Stmt>>) -> Result<(), FatalError> {
+        for stmt in stmts {
+            match &stmt.value {
+                Stmt::FuncDecl(func) => self.declare_function(&func.value)?,
+                Stmt::StructDecl(decl) => self.declare_struct(&decl.value)?,
+                Stmt::ClassDecl(decl) => self.declare_class(&decl.value)?,
+                _ => (),
+            }
+        }
+        Ok(())
+    }
+
+    /// Declare a function.
+    pub fn declare_function(&mut self, func: &FuncDecl) -> Result<(), FatalError> {
+        let (FuncDecl::Local { ident, .. }
+        | FuncDecl::Export { ident, .. }
+        | FuncDecl::External { ident, .. }) = func;
+        if self.lookup_function(&ident.value).is_some() {
+            return Err(self.error(ident, ErrorKind::E0210(ident.value.clone())));
+        }
+        self.functions.push(func.as_function());
+        if let FuncDecl::Local { constant: true, .. } = func {
+            self.const_functions.push(func.clone());
+        }
+        Ok(())
+    }
+
+    /// Declare a struct.
+    pub fn declare_struct(&mut self, decl: &StructDecl) -> Result<(), FatalError> {
+        self.check_type_name(&decl.ident)?;
+        self.structs.push(decl.as_struct());
+        Ok(())
+    }
+
+    /// Declare a class.
+    pub fn declare_class(&mut self, decl: &ClassDecl) -> Result<(), FatalError> {
+        self.check_type_name(&decl.ident)?;
+        self.classes.push(decl.as_class());
+        Ok(())
+    }
+
+    /// Declare a type alias.
+    pub fn declare_alias(&mut self, decl: &TypeDeclaration) -> Result<(), FatalError> {
+        self.check_type_name(&decl.ident)?;
+        let params = decl.generics.iter().map(|param| param.value.ident.value.clone()).collect();
+        self.aliases.declare_generic(&decl.ident.value, params, decl.value.value.clone());
+        Ok(())
+    }
+
+    /// Bring the given type parameters into scope, such that types may refer
+    /// to them.
+    pub fn enter_generics(&mut self, generics: &[Node<GenericParam>]) {
+        self.type_params
+            .push(generics.iter().map(|param| param.value.ident.value.clone()).collect());
+    }
+
+    /// Remove the innermost type parameters from scope.
+    pub fn exit_generics(&mut self) {
+        self.type_params.pop();
+    }
+
+    /// Returns true if a type parameter with the given name is in scope.
+    pub fn lookup_type_param(&self, name: &str) -> bool {
+        self.type_params.find(|params| params.iter().any(|param| param == name)).is_some()
+    }
+
+    /// Return the number of type parameters of the declared type with the
+    /// given name.
+    pub fn type_arity(&self, name: &str) -> usize {
+        if let Some(params) = self.aliases.params(name) {
+            return params.len();
+        }
+        self.lookup_struct(name)
+            .map(|s| s.generics.len())
+            .or_else(|| self.lookup_class(name).map(|c| c.generics.len()))
+            .unwrap_or(0)
+    }
+
+    /// Check that no type has already been declared with the given name.
+    fn check_type_name(&self, ident: &Node<String>) -> Result<(), FatalError> {
+        match self.lookup_type(&ident.value) {
+            true => Err(self.error(ident, ErrorKind::E0210(ident.value.clone()))),
+            false => Ok(()),
+        }
+    }
+
+    /// Returns true if a type alias, struct, class or interface with the given
+    /// name has been declared.
+    pub fn lookup_type(&self, name: &str) -> bool {
+        self.aliases.contains(name)
+            || self.structs.iter().any(|s| s.name == name)
+            || self.classes.iter().any(|c| c.name == name)
+            || self.lookup_interface(name).is_some()
+    }
+
+    /// Lookup a struct declared in the current scope.
+    pub fn lookup_struct(&self, name: &str) -> Option<&Struct> {
+        self.structs.iter().find(|s| s.name == name)
+    }
+
+    /// Lookup a class declared in the current scope.
+    pub fn lookup_class(&self, name: &str) -> Option<&Class> {
+        self.classes.iter().find(|c| c.name == name)
+    }
+
+    /// Declare a variable, inferring its type from its value.
+    pub fn declare_variable(&mut self, decl: &Declaration) -> Result<Variable, FatalError> {
+        let ty = match (&decl.explicit_ty, self.inference.type_of(&decl.value.span)) {
+            (Some(ty), _) => ty.value.clone(),
+            // the value has already been inferred as part of its module
+            (None, Some(ty)) => ty,
+            (None, None) => {
+                self.inference.infer_declaration(decl).map_err(|err| self.type_error(err))?
+            }
+        };
+        Ok(self.declare_local(&decl.ident, decl.mutability, ty))
+    }
+
+    /// Declare a local variable of the given type in the innermost scope, such
+    /// as a function parameter, assigning it a fresh ID.
+    pub fn declare_local(
+        &mut self,
+        ident: &Node<String>,
+        mutability: Mutability,
+        ty: TypeExpr,
+    ) -> Variable {
+        let variable = Variable { id: self.next_local, name: ident.value.clone(), mutability, ty };
+        self.next_local += 1;
+        let binding = Binding { variable: variable.clone(), span: ident.span.clone(), used: false };
+        self.scopes
+            .last_mut()
+            .expect("declared a variable outside of any scope")
+            .bindings
+            .push(binding);
+        variable
+    }
+
+    /// Declare a symbol imported into the innermost scope.
+    pub fn declare_import(&mut self, ident: &Node<String>) {
+        let import =
+            ImportBinding { name: ident.value.clone(), span: ident.span.clone(), used: false };
+        self.scopes
+            .last_mut()
+            .expect("imported a symbol outside of any scope")
+            .imports
+            .push(import);
+    }
+
+    /// Return the index of the innermost scope declaring a variable with the
+    /// given name.
+    fn scope_of(&self, name: &str) -> Option<usize> {
+        (0..self.scopes.size()).rev().find(|i| self.scopes.get_unchecked(*i).lookup(name).is_some())
+    }
+
+    /// Lookup the binding of a variable available in the current scope.
+    pub fn lookup_binding(&self, name: &str) -> Option<&Binding> {
+        self.scope_of(name).and_then(|index| self.scopes.get_unchecked(index).lookup(name))
+    }
+
+    /// Lookup a variable available in the current scope.
+    pub fn lookup_variable<S: AsRef<str>>(&self, name: S) -> Option<&Variable> {
+        self.lookup_binding(name.as_ref()).map(|b| &b.variable)
+    }
+
+    /// Lookup a variable available in the current scope by its ID.
+    pub fn lookup_local(&self, id: LocalId) -> Option<&Variable> {
+        (0..self.scopes.size()).rev().find_map(|i| {
+            let scope = self.scopes.get_unchecked(i);
+            scope.bindings.iter().map(|b| &b.variable).find(|variable| variable.id == id)
+        })
+    }
+
+    /// Returns `true` if the variable with the given name is declared outside
+    /// of the function currently being walked, such that the closure being
+    /// walked captures it by value. Variables declared at the top level of a
+    /// module are never captured.
+    pub fn is_captured(&self, name: &str) -> bool {
+        let Some(index) = self.scope_of(name) else { return false };
+        self.scopes.get_unchecked(index).kind != ScopeKind::Module
+            && (index + 1..self.scopes.size())
+                .any(|i| self.scopes.get_unchecked(i).kind == ScopeKind::Function)
+    }
+
+    /// Lookup a variable available in the current scope, returning a mutable
+    /// reference to the variable.
+    pub fn lookup_variable_mut<S: AsRef<str>>(&mut self, name: S) -> Option<&mut Variable> {
+        let index = self.scope_of(name.as_ref())?;
+        let scope = self.scopes.get_mut(index)?;
+        scope.lookup_mut(name.as_ref()).map(|b| &mut b.variable)
+    }
+
+    /// Lookup a variable available in the current scope, marking it as used.
+    /// If the variable is declared outside of the function currently being
+    /// walked, it is recorded as captured by every function scope between its
+    /// declaration and the current scope.
+    pub fn use_variable<S: AsRef<str>>(&mut self, name: S) -> Option<Variable> {
+        let index = self.scope_of(name.as_ref())?;
+        let scope = self.scopes.get_mut(index)?;
+        let captured = scope.kind != ScopeKind::Module;
+        let binding = scope.lookup_mut(name.as_ref())?;
+        binding.used = true;
+        let variable = binding.variable.clone();
+        if captured {
+            for inner in index + 1..self.scopes.size() {
+                let scope = self.scopes.get_mut(inner).unwrap();
+                if scope.kind == ScopeKind::Function {
+                    scope.capture(variable.id);
+                }
+            }
+        }
+        Some(variable)
+    }
+
+    /// Lookup a symbol imported into the current scope, marking it as used.
+    /// Returns `true` if the symbol was found.
+    pub fn use_import(&mut self, name: &str) -> bool {
+        for index in (0..self.scopes.size()).rev() {
+            let scope = self.scopes.get_mut(index).unwrap();
+            if let Some(import) = scope.imports.iter_mut().find(|i| i.name == name) {
+                import.used = true;
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Lookup a funciton available in the current scope.
+    pub fn lookup_function(&self, name: &str) -> Option<&Function> {
+        self.functions.find(|f| f.name == name)
+    }
+
+    /// Lookup the declaration of a `const` function, so that calls to it can
+    /// be evaluated at compile-time.
+    pub fn lookup_const_function(&self, name: &str) -> Option<&FuncDecl> {
+        self.const_functions.iter().find(|func| match func {
+            FuncDecl::Local { ident, .. } => ident.value == name,
+            _ => false,
+        })
+    }
+
+    /// Lookup a funciton available in the current scope.
+    pub fn lookup_function_mut(&mut self, name: &str) -> Option<&mut Function> {
+        self.functions.find_mut(|f| f.name == name)
+    }
+
+    /// Declare an interface.
+    pub fn declare_interface(&mut self, decl: &InterfaceDecl) -> Result<(), FatalError> {
+        self.check_type_name(&decl.ident)?;
+        self.interfaces.push(decl.as_interface());
+        Ok(())
+    }
+
+    /// Lookup an interface available in the current scope.
+    pub fn lookup_interface(&self, name: &str) -> Option<&Interface> {
+        self.interfaces.find(|i| i.name == name)
+    }
+
+    /// Check that the given implementation conforms to its interface, and
+    /// declare it. Default methods that the implementation does not override
+    /// are inherited from the interface.
+    pub fn declare_impl(&mut self, decl: &ImplDecl) -> Result<(), FatalError> {
+        let ty = &decl.ty.value;
+        let interface = self.lookup_interface(&decl.interface.value).ok_or_else(|| {
+            self.error(&decl.interface, ErrorKind::E0200(decl.interface.value.clone()))
+        })?;
+        // every implemented method must match a method of the interface
+        let mut methods = vec![];
+        for method in &decl.methods {
+            let name = &method.value.ident.value;
+            let expected = interface.methods.iter().find(|m| &m.name == name).ok_or_else(|| {
+                self.error(
+                    &method.value.ident,
+                    ErrorKind::E0202(name.clone(), interface.name.clone()),
+                )
+            })?;
+            let actual = method.value.as_method(ty);
+            if !expected.with_self(ty).signature_eq(&actual) {
+                return Err(self.error(
+                    &method.value.ident,
+                    ErrorKind::E0203(name.clone(), interface.name.clone()),
+                ));
+            }
+            methods.push(actual);
+        }
+        // abstract methods must be implemented, while defaults are inherited
+        for expected in &interface.methods {
+            if methods.iter().any(|m| m.name == expected.name) {
+                continue;
+            }
+            if expected.kind == FunctionKind::Abstract {
+                return Err(self.error(
+                    &decl.ty,
+                    ErrorKind::E0201(ty.clone(), expected.name.clone(), interface.name.clone()),
+                ));
+            }
+            methods.push(expected.with_self(ty));
+        }
+        let interface = interface.name.clone();
+        self.impls.push(Impl { interface, ty: ty.clone(), methods });
+        Ok(())
+    }
+
+    /// Lookup the implementation of an interface for the given type.
+    pub fn lookup_impl(&self, interface: &str, ty: &str) -> Option<&Impl> {
+        self.impls.iter().find(|i| i.interface == interface && i.ty == ty)
+    }
+
+    /// Lookup a method implemented for the given type through any of its
+    /// interface implementations. Since the type is known, calls to the
+    /// method are statically dispatched.
+    pub fn lookup_impl_method(&self, ty: &str, name: &str) -> Option<&Function> {
+        self.impls.iter().filter(|i| i.ty == ty).flat_map(|i| &i.methods).find(|m| m.name == name)
+    }
+}
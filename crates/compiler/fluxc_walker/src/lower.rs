@@ -0,0 +1,695 @@
+//! Lowers the AST into HIR.
+
+use std::{collections::HashMap, ops::Range};
+
+use fluxc_ast::{
+    Block, ClassDecl, Closure as ClosureExpr, Expr, FuncDecl, FuncParam, GenericParam, ImplDecl,
+    InterfaceDecl, MethodDecl, Mutability, Node, Operation, Primitive, Stmt, StructDecl,
+    TypeDeclaration, TypeExpr, AST,
+};
+use fluxc_errors::{ErrorKind, FatalError};
+use fluxc_hir::{
+    Argument, AsClass, AsFunction, AsInterface, AsMethod, AsStruct, BinaryExpr, Body, Call, Cast,
+    Class, Closure, Conditional, Declaration, Dispatch, ExprKind, FieldAccess, Function,
+    FunctionKind, Impl, Loop, Match, MethodCall, Module, StructExpr, UnaryExpr, While,
+};
+use fluxc_span::IntoSpan;
+use fluxc_types::{alias::AliasError, infer::Ty};
+
+use crate::{literal_type, ScopeKind, Walker};
+
+impl Walker {
+    /// Analyse the given AST, producing the HIR module it represents.
+    pub fn walk(&mut self, ast: &AST) -> Result<Module, FatalError> {
+        self.inference.infer_ast(ast).map_err(|err| self.type_error(err))?;
+        self.enter_scope(ScopeKind::Module);
+
+        // declare every item, so that items can be referred to before their
+        // declaration
+        let mut interfaces = HashMap::new();
+        for stmt in &ast.stmts {
+            match &stmt.value {
+                Stmt::FuncDecl(decl) => self.declare_function(&decl.value)?,
+                Stmt::StructDecl(decl) => self.declare_struct(&decl.value)?,
+                Stmt::ClassDecl(decl) => self.declare_class(&decl.value)?,
+                Stmt::TypeDeclaration(decl) => self.declare_alias(&decl.value)?,
+                Stmt::InterfaceDecl(decl) => {
+                    self.declare_interface(&decl.value)?;
+                    interfaces.insert(decl.value.ident.value.clone(), &decl.value);
+                }
+                Stmt::Import(import) => {
+                    for symbol in &import.value.symbols {
+                        self.declare_import(
+                            symbol.value.alias.as_ref().unwrap_or(&symbol.value.name),
+                        );
+                    }
+                }
+                _ => (),
+            }
+        }
+        for stmt in &ast.stmts {
+            if let Stmt::ImplDecl(decl) = &stmt.value {
+                self.declare_impl(&decl.value)?;
+            }
+        }
+
+        let mut module =
+            Module { structs: vec![StructDecl::error().as_struct()], ..Default::default() };
+        for stmt in &ast.stmts {
+            match &stmt.value {
+                Stmt::FuncDecl(decl) => module.functions.push(self.lower_function(&decl.value)?),
+                Stmt::StructDecl(decl) => {
+                    self.enter_generics(&decl.value.generics);
+                    let checked = self.check_generics(&decl.value.generics).and_then(|_| {
+                        decl.value
+                            .fields
+                            .iter()
+                            .try_for_each(|field| self.check_type(&field.value.ty, false))
+                    });
+                    self.exit_generics();
+                    checked?;
+                    module.structs.push(decl.value.as_struct());
+                }
+                Stmt::ClassDecl(decl) => module.classes.push(self.lower_class(&decl.value)?),
+                Stmt::InterfaceDecl(decl) => {
+                    module.interfaces.push(self.lower_interface(&decl.value)?)
+                }
+                Stmt::ImplDecl(decl) => {
+                    let interface = interfaces[&decl.value.interface.value];
+                    module.impls.push(self.lower_impl(&decl.value, interface)?);
+                }
+                Stmt::TypeDeclaration(decl) => self.check_alias(&decl.value)?,
+                Stmt::Import(_) | Stmt::Export(_) => (),
+                _ => module.stmts.push(self.lower_stmt(stmt)?),
+            }
+        }
+        self.exit_scope();
+        Ok(module)
+    }
+
+    /// Check that every type the given type expression refers to has been
+    /// declared, or is a type parameter in scope, and that generic types are
+    /// given as many type arguments as they have type parameters. `Self` may
+    /// only be referred to inside interfaces.
+    fn check_type(&self, ty: &Node<TypeExpr>, allow_self: bool) -> Result<(), FatalError> {
+        fn find_error(walker: &Walker, ty: &TypeExpr, allow_self: bool) -> Option<ErrorKind> {
+            match ty {
+                TypeExpr::Primitive(Primitive::Ref(name))
+                    if (allow_self && name == "Self") || walker.lookup_type_param(name) =>
+                {
+                    None
+                }
+                TypeExpr::Primitive(Primitive::Ref(name)) | TypeExpr::Generic(name, _)
+                    if !walker.lookup_type(name) =>
+                {
+                    Some(ErrorKind::E0209(name.clone()))
+                }
+                TypeExpr::Primitive(Primitive::Ref(name)) => match walker.type_arity(name) {
+                    0 => None,
+                    arity => Some(ErrorKind::E0218(name.clone(), arity, 0)),
+                },
+                TypeExpr::Generic(name, args) => match walker.type_arity(name) {
+                    arity if arity != args.len() => {
+                        Some(ErrorKind::E0218(name.clone(), arity, args.len()))
+                    }
+                    _ => args.iter().find_map(|ty| find_error(walker, ty, allow_self)),
+                },
+                TypeExpr::Primitive(Primitive::Tuple(tuple)) => {
+                    tuple.iter().find_map(|ty| find_error(walker, ty, allow_self))
+                }
+                TypeExpr::Operation(Operation::Union(union)) => {
+                    find_error(walker, &union.lhs, allow_self)
+                        .or_else(|| find_error(walker, &union.rhs, allow_self))
+                }
+                TypeExpr::Operation(Operation::Intersection(intersection)) => {
+                    find_error(walker, &intersection.lhs, allow_self)
+                        .or_else(|| find_error(walker, &intersection.rhs, allow_self))
+                }
+                TypeExpr::Operation(Operation::Array(inner, _))
+                | TypeExpr::Operation(Operation::KeyOf(inner))
+                | TypeExpr::Circular(inner) => find_error(walker, inner, allow_self),
+                // types captured by the `extends` clause of a conditional are
+                // in scope in its `then` branch
+                TypeExpr::Operation(Operation::Conditional(conditional)) => {
+                    let any = TypeExpr::Primitive(Primitive::Any);
+                    let captures = conditional
+                        .captures()
+                        .into_iter()
+                        .map(|name| (name, any.clone()))
+                        .collect();
+                    let extends = conditional.extends.replace(&|ty| match ty {
+                        TypeExpr::Capture(_) => Some(any.clone()),
+                        _ => None,
+                    });
+                    find_error(walker, &conditional.check, allow_self)
+                        .or_else(|| find_error(walker, &extends, allow_self))
+                        .or_else(|| {
+                            find_error(walker, &conditional.then.substitute(&captures), allow_self)
+                        })
+                        .or_else(|| find_error(walker, &conditional.otherwise, allow_self))
+                }
+                TypeExpr::Capture(name) => Some(ErrorKind::E0219(name.clone())),
+                TypeExpr::Function(params, ret) => params
+                    .iter()
+                    .chain(std::iter::once(ret.as_ref()))
+                    .find_map(|ty| find_error(walker, ty, allow_self)),
+                _ => None,
+            }
+        }
+        match find_error(self, &ty.value, allow_self) {
+            Some(kind) => Err(self.error(ty, kind)),
+            None => Ok(()),
+        }
+    }
+
+    /// Check the bounds of the given type parameters, which must already be
+    /// in scope.
+    fn check_generics(&self, generics: &[Node<GenericParam>]) -> Result<(), FatalError> {
+        generics
+            .iter()
+            .filter_map(|param| param.value.bound.as_ref())
+            .try_for_each(|bound| self.check_type(bound, false))
+    }
+
+    /// Check that a type alias only refers to declared types and its own
+    /// type parameters, and that it is well-founded if it is recursive.
+    fn check_alias(&mut self, decl: &TypeDeclaration) -> Result<(), FatalError> {
+        self.enter_generics(&decl.generics);
+        let checked =
+            self.check_generics(&decl.generics).and_then(|_| self.check_type(&decl.value, false));
+        self.exit_generics();
+        checked?;
+        self.aliases.check(&decl.ident.value).map_err(|err| {
+            let kind = match err {
+                AliasError::Cyclic(name) => ErrorKind::E0215(name),
+                AliasError::InfiniteSize(name) => ErrorKind::E0216(name),
+            };
+            self.error(&decl.ident, kind)
+        })
+    }
+
+    /// Return the type inferred for the expression with the given span.
+    fn type_of(&self, span: &Range<usize>) -> TypeExpr {
+        self.inference.type_of(span).unwrap_or(TypeExpr::Infer)
+    }
+
+    /// Lower a function declaration, analysing its body with its type
+    /// parameters in scope.
+    fn lower_function(&mut self, decl: &FuncDecl) -> Result<Function, FatalError> {
+        self.enter_generics(decl.generics());
+        let func = self.lower_function_in_scope(decl);
+        self.exit_generics();
+        func
+    }
+
+    /// Lower a function declaration whose type parameters are in scope.
+    fn lower_function_in_scope(&mut self, decl: &FuncDecl) -> Result<Function, FatalError> {
+        self.check_generics(decl.generics())?;
+        let mut func = decl.as_function();
+        let (params, body, ret_ty) = match decl {
+            FuncDecl::Local { params, body, ret_ty, .. } => (params, Some(body), Some(ret_ty)),
+            FuncDecl::Export { params, body, ret_ty, .. } => (params, Some(body), ret_ty.as_ref()),
+            FuncDecl::External { params, ret_ty, .. } => (params, None, ret_ty.as_ref()),
+        };
+        for param in params {
+            self.check_type(&param.value.ty, false)?;
+        }
+        if let Some(ret_ty) = ret_ty {
+            self.check_type(ret_ty, false)?;
+        }
+        // fill in the types inferred for unannotated parameters
+        if let Some(Ty::Func(param_tys, ret)) = self.inference.lookup(&func.name) {
+            for (arg, ty) in func.args.iter_mut().zip(param_tys) {
+                if matches!(arg.ty, TypeExpr::Infer) {
+                    arg.ty = ty.to_type_expr();
+                }
+            }
+            if ret_ty.is_none() {
+                func.return_type = ret.to_type_expr();
+            }
+        }
+        if let Some(body) = body {
+            func.body = Some(self.lower_body(&func, None, params, body)?);
+        }
+        Ok(func)
+    }
+
+    /// Lower the body of the given function in a new function scope, binding
+    /// its arguments. Methods with a receiver bind it with the mutability it is
+    /// declared with.
+    fn lower_body(
+        &mut self,
+        func: &Function,
+        receiver: Option<&Node<Mutability>>,
+        params: &[Node<FuncParam>],
+        body: &Node<Block>,
+    ) -> Result<Body, FatalError> {
+        let previous = self.current_function.replace(func.clone());
+        self.enter_scope(ScopeKind::Function);
+        let bindings = receiver
+            .map(|receiver| (Node::new("self".to_string(), receiver.span.clone()), receiver.value))
+            .into_iter()
+            .chain(params.iter().map(|param| (param.value.ident.clone(), param.value.mutability)));
+        let params = bindings
+            .zip(&func.args)
+            .map(|((ident, mutability), arg)| {
+                self.declare_local(&ident, mutability, arg.ty.clone())
+            })
+            .collect();
+        let block = self.lower_block(body, ScopeKind::Block);
+        let scope = self.exit_scope();
+        self.current_function = previous;
+        Ok(Body { params, block: block?, captures: scope.captures })
+    }
+
+    /// Lower an anonymous function to the function it is lifted to, analysing
+    /// its body in a new function scope. The arguments and return type of the
+    /// function are those inferred for the closure.
+    fn lower_closure(&mut self, closure: &Node<ClosureExpr>) -> Result<Closure, FatalError> {
+        let decl = &closure.value;
+        for param in &decl.params {
+            self.check_type(&param.value.ty, false)?;
+        }
+        self.check_type(&decl.ret_ty, false)?;
+        let (arg_tys, return_type) = match self.type_of(&closure.span) {
+            TypeExpr::Function(params, ret) => (params, *ret),
+            _ => (
+                decl.params.iter().map(|param| param.value.ty.value.clone()).collect(),
+                decl.ret_ty.value.clone(),
+            ),
+        };
+        let mut func = Function {
+            name: format!("closure.{}", closure.span.start),
+            generics: vec![],
+            kind: FunctionKind::Closure,
+            args: decl
+                .params
+                .iter()
+                .zip(arg_tys)
+                .map(|(param, ty)| Argument { name: param.value.ident.value.clone(), ty })
+                .collect(),
+            return_type,
+            body: None,
+        };
+        let body = self.lower_body(&func, None, &decl.params, &decl.body)?;
+        let captures = body
+            .captures
+            .iter()
+            .map(|id| self.lookup_local(*id).cloned().expect("captured an undeclared variable"))
+            .collect();
+        func.body = Some(body);
+        Ok(Closure { function: func, captures })
+    }
+
+    /// Lower a method of the given type, analysing its body.
+    fn lower_method(&mut self, ty: &str, decl: &MethodDecl) -> Result<Function, FatalError> {
+        for param in &decl.params {
+            self.check_type(&param.value.ty, false)?;
+        }
+        self.check_type(&decl.ret_ty, false)?;
+        let mut method = decl.as_method(ty);
+        method.body =
+            Some(self.lower_body(&method, decl.receiver.as_ref(), &decl.params, &decl.body)?);
+        Ok(method)
+    }
+
+    /// Lower a class declaration, analysing its methods with the class's type
+    /// parameters in scope.
+    fn lower_class(&mut self, decl: &ClassDecl) -> Result<Class, FatalError> {
+        self.enter_generics(&decl.generics);
+        let class = self.lower_class_in_scope(decl);
+        self.exit_generics();
+        class
+    }
+
+    /// Lower a class declaration whose type parameters are in scope.
+    fn lower_class_in_scope(&mut self, decl: &ClassDecl) -> Result<Class, FatalError> {
+        self.check_generics(&decl.generics)?;
+        for field in &decl.fields {
+            self.check_type(&field.value.ty, false)?;
+        }
+        let mut class = decl.as_class();
+        class.methods = decl
+            .methods
+            .iter()
+            .map(|method| self.lower_method(&decl.ident.value, &method.value))
+            .collect::<Result<_, _>>()?;
+        Ok(class)
+    }
+
+    /// Lower an interface declaration. The bodies of default methods are
+    /// analysed separately for every implementation that inherits them.
+    fn lower_interface(
+        &mut self,
+        decl: &InterfaceDecl,
+    ) -> Result<fluxc_hir::Interface, FatalError> {
+        for method in &decl.methods {
+            for param in &method.value.params {
+                self.check_type(&param.value.ty, true)?;
+            }
+            self.check_type(&method.value.ret_ty, true)?;
+        }
+        Ok(decl.as_interface())
+    }
+
+    /// Lower an interface implementation, analysing its methods and the
+    /// default methods it inherits.
+    fn lower_impl(
+        &mut self,
+        decl: &ImplDecl,
+        interface: &InterfaceDecl,
+    ) -> Result<Impl, FatalError> {
+        let ty = &decl.ty.value;
+        if !self.lookup_type(ty) {
+            return Err(self.error(&decl.ty, ErrorKind::E0209(ty.clone())));
+        }
+        let mut methods = decl
+            .methods
+            .iter()
+            .map(|method| self.lower_method(ty, &method.value))
+            .collect::<Result<Vec<_>, _>>()?;
+        for method in &interface.methods {
+            if methods.iter().any(|m| m.name == method.value.ident.value) {
+                continue;
+            }
+            if let Some(default) = method.value.default_impl(ty) {
+                // infer the default for this type, so its body is typed
+                self.inference.infer_method(ty, &default).map_err(|err| self.type_error(err))?;
+                let mut lowered = self.lower_method(ty, &default)?;
+                lowered.kind = fluxc_hir::FunctionKind::Default;
+                methods.push(lowered);
+            }
+        }
+        Ok(Impl { interface: interface.ident.value.clone(), ty: ty.clone(), methods })
+    }
+
+    /// Lower a block of statements in a new scope of the given kind.
+    fn lower_block(
+        &mut self,
+        block: &Node<Block>,
+        kind: ScopeKind,
+    ) -> Result<fluxc_hir::Block, FatalError> {
+        self.enter_scope(kind);
+        let stmts: Result<_, _> =
+            block.value.stmts.iter().map(|stmt| self.lower_stmt(stmt)).collect();
+        self.exit_scope();
+        let stmts = stmts?;
+        let ty =
+            self.inference.type_of(&block.span).unwrap_or(TypeExpr::Primitive(Primitive::Unit));
+        Ok(fluxc_hir::Block { stmts, ty })
+    }
+
+    /// Lower a statement.
+    fn lower_stmt(&mut self, stmt: &Node<Stmt>) -> Result<fluxc_hir::Stmt, FatalError> {
+        Ok(match &stmt.value {
+            Stmt::Declaration(decl) => {
+                if let Some(ty) = &decl.value.explicit_ty {
+                    self.check_type(ty, false)?;
+                }
+                // the value is lowered first, as it cannot refer to the
+                // variable being declared
+                let mut value = self.lower_expr(&decl.value.value)?;
+                let variable = match decl.value.mutability {
+                    // constants are folded into their value, which is held by
+                    // the literal type of the constant
+                    Mutability::Constant => {
+                        let literal = self.eval_const(&decl.value)?;
+                        let ty = literal_type(&literal).expect("constant evaluated to an array");
+                        value.kind = ExprKind::Literal(literal);
+                        value.ty = ty.clone();
+                        self.declare_local(&decl.value.ident, Mutability::Constant, ty)
+                    }
+                    _ => self.declare_variable(&decl.value)?,
+                };
+                fluxc_hir::Stmt::Declaration(Declaration { variable, value })
+            }
+            Stmt::Return(expr) => fluxc_hir::Stmt::Return(self.lower_expr(expr)?),
+            Stmt::Break(expr) => fluxc_hir::Stmt::Break(self.lower_expr(expr)?),
+            Stmt::Expr(expr) => fluxc_hir::Stmt::Expr(self.lower_expr(expr)?),
+            _ => return Err(self.error(stmt, ErrorKind::E0001("nested item declarations".into()))),
+        })
+    }
+
+    /// Resolve an identifier to the local or function it refers to.
+    fn resolve(&mut self, ident: &Node<String>) -> Result<ExprKind, FatalError> {
+        if let Some(variable) = self.use_variable(&ident.value) {
+            return Ok(ExprKind::Local(variable.id));
+        }
+        if let Some(func) = self.lookup_function(&ident.value) {
+            return Ok(ExprKind::Function(func.name.clone()));
+        }
+        match self.use_import(&ident.value) {
+            true => Err(self.error(ident, ErrorKind::E0001("imported values".into()))),
+            false => Err(self.error(ident, ErrorKind::E0205(ident.value.clone()))),
+        }
+    }
+
+    /// Whether the receiver of a method call names a struct or class rather
+    /// than a value, making the call static, as in `Counter.new ()`.
+    fn is_type_receiver(&self, receiver: &Node<Expr>) -> bool {
+        let Expr::Ident(ident) = &receiver.value else {
+            return false;
+        };
+        self.lookup_variable(&ident.value).is_none()
+            && self.lookup_function(&ident.value).is_none()
+            && (self.lookup_struct(&ident.value).is_some()
+                || self.lookup_class(&ident.value).is_some())
+    }
+
+    /// Check that the given place, mutated by the given expression, refers to
+    /// a mutable variable. Fields are only mutable if the variable holding them
+    /// is. Errors span the mutating expression, and relate to the declaration
+    /// of the variable.
+    fn check_mutable(&self, expr: &Node<Expr>, place: &Node<Expr>) -> Result<(), FatalError> {
+        fn root(place: &Expr) -> Option<&Node<String>> {
+            match place {
+                Expr::Ident(ident) => Some(ident),
+                Expr::FieldAccess(access) => root(&access.value.expr.value),
+                _ => None,
+            }
+        }
+        let Some(binding) = root(&place.value).and_then(|ident| self.lookup_binding(&ident.value))
+        else {
+            return Ok(());
+        };
+        let name = binding.variable.name.clone();
+        let kind = match binding.variable.mutability {
+            Mutability::Mutable if self.is_captured(&name) => ErrorKind::E0221(name),
+            Mutability::Mutable => return Ok(()),
+            Mutability::Immutable => ErrorKind::E0211(name),
+            Mutability::Constant => ErrorKind::E0212(name),
+        };
+        Err(self
+            .error(expr, kind)
+            .with_related(binding.span.clone().into_span(self.context.clone())))
+    }
+
+    /// Lower an expression, annotating it with its inferred type.
+    fn lower_expr(&mut self, expr: &Node<Expr>) -> Result<fluxc_hir::Expr, FatalError> {
+        let kind = match &expr.value {
+            Expr::Literal(literal) => ExprKind::Literal(literal.value.clone()),
+            Expr::Ident(ident) => {
+                let kind = self.resolve(ident)?;
+                // references to constants take on the literal type of their
+                // value, so that they can be emitted as immediates
+                if let Some(constant) = self
+                    .lookup_variable(&ident.value)
+                    .filter(|variable| variable.mutability == Mutability::Constant)
+                {
+                    let ty = constant.ty.clone();
+                    return Ok(fluxc_hir::Expr { kind, ty, span: expr.span.clone() });
+                }
+                kind
+            }
+            Expr::BinaryExpr(binary) if binary.value.kind.is_assignment() => {
+                self.check_mutable(expr, &binary.value.lhs)?;
+                ExprKind::BinaryExpr(BinaryExpr {
+                    kind: binary.value.kind,
+                    lhs: self.lower_expr(&binary.value.lhs)?.into(),
+                    rhs: self.lower_expr(&binary.value.rhs)?.into(),
+                })
+            }
+            Expr::BinaryExpr(binary) => ExprKind::BinaryExpr(BinaryExpr {
+                kind: binary.value.kind,
+                lhs: self.lower_expr(&binary.value.lhs)?.into(),
+                rhs: self.lower_expr(&binary.value.rhs)?.into(),
+            }),
+            Expr::UnaryExpr(unary) if unary.value.kind.is_mutation() => {
+                self.check_mutable(expr, &unary.value.expr)?;
+                ExprKind::UnaryExpr(UnaryExpr {
+                    kind: unary.value.kind.clone(),
+                    expr: self.lower_expr(&unary.value.expr)?.into(),
+                })
+            }
+            Expr::UnaryExpr(unary) => ExprKind::UnaryExpr(UnaryExpr {
+                kind: unary.value.kind.clone(),
+                expr: self.lower_expr(&unary.value.expr)?.into(),
+            }),
+            Expr::Cast(cast) => {
+                self.check_type(&cast.value.ty, false)?;
+                ExprKind::Cast(Cast { expr: self.lower_expr(&cast.value.expr)?.into() })
+            }
+            Expr::Block(block) => ExprKind::Block(self.lower_block(block, ScopeKind::Block)?),
+            Expr::FuncCall(call) => {
+                let callee = self.lower_expr(&call.value.callee)?;
+                let args = self.lower_exprs(&call.value.args)?;
+                let type_args = match &callee.kind {
+                    ExprKind::Function(name) => self
+                        .lookup_function(name)
+                        .map(|func| type_args(func, &args, &self.type_of(&expr.span)))
+                        .unwrap_or_default(),
+                    _ => vec![],
+                };
+                ExprKind::Call(Call { callee: callee.into(), args, type_args })
+            }
+            Expr::MethodCall(call) if self.is_type_receiver(&call.value.receiver) => {
+                let args = self.lower_exprs(&call.value.args)?;
+                let ty = TypeExpr::Function(
+                    args.iter().map(|arg| arg.ty.clone()).collect(),
+                    self.type_of(&expr.span).into(),
+                );
+                let callee = fluxc_hir::Expr {
+                    kind: ExprKind::Method(
+                        self.type_of(&call.value.receiver.span),
+                        call.value.method.value.clone(),
+                    ),
+                    ty,
+                    span: call.value.receiver.span.start..call.value.method.span.end,
+                };
+                ExprKind::Call(Call { callee: callee.into(), args, type_args: vec![] })
+            }
+            Expr::MethodCall(call) => {
+                let receiver = self.lower_expr(&call.value.receiver)?;
+                let method = &call.value.method;
+                let dispatch = match &receiver.ty {
+                    TypeExpr::Primitive(Primitive::Ref(ty)) => {
+                        match self.lookup_interface(ty).is_some() {
+                            true => Dispatch::Dynamic(ty.clone()),
+                            false => Dispatch::Static(ty.clone()),
+                        }
+                    }
+                    ty @ TypeExpr::Generic(..) => Dispatch::Static(ty.to_string()),
+                    ty => {
+                        return Err(self
+                            .error(method, ErrorKind::E0208(method.value.clone(), ty.to_string())))
+                    }
+                };
+                ExprKind::MethodCall(MethodCall {
+                    receiver: receiver.into(),
+                    method: method.value.clone(),
+                    args: self.lower_exprs(&call.value.args)?,
+                    dispatch,
+                })
+            }
+            Expr::Conditional(conditional) => {
+                let conditional = &conditional.value;
+                let branches = std::iter::once(&conditional.if_stmt)
+                    .chain(&conditional.else_ifs)
+                    .map(|if_stmt| {
+                        Ok((
+                            self.lower_expr(&if_stmt.value.condition)?,
+                            self.lower_block(&if_stmt.value.block, ScopeKind::Block)?,
+                        ))
+                    })
+                    .collect::<Result<_, FatalError>>()?;
+                let else_block = match &conditional.else_stmt {
+                    Some(block) => Some(self.lower_block(block, ScopeKind::Block)?),
+                    None => None,
+                };
+                ExprKind::Conditional(Conditional { branches, else_block })
+            }
+            Expr::Loop(loop_expr) => ExprKind::Loop(Loop {
+                name: loop_expr.value.name.clone(),
+                block: self.lower_block(&loop_expr.value.block, ScopeKind::Loop)?,
+            }),
+            Expr::While(while_expr) => ExprKind::While(While {
+                condition: self.lower_expr(&while_expr.value.condition)?.into(),
+                block: self.lower_block(&while_expr.value.block, ScopeKind::Loop)?,
+            }),
+            Expr::Match(match_expr) => ExprKind::Match(Match {
+                expr: self.lower_expr(&match_expr.value.expr)?.into(),
+                branches: match_expr
+                    .value
+                    .branches
+                    .iter()
+                    .map(|branch| {
+                        Ok((
+                            self.lower_expr(&branch.value.pattern)?,
+                            self.lower_expr(&branch.value.value)?,
+                        ))
+                    })
+                    .collect::<Result<_, FatalError>>()?,
+                else_value: match &match_expr.value.else_value {
+                    Some(value) => Some(self.lower_expr(value)?.into()),
+                    None => None,
+                },
+            }),
+            Expr::StructExpr(struct_expr) => ExprKind::StructExpr(StructExpr {
+                name: struct_expr.value.ident.value.clone(),
+                fields: struct_expr
+                    .value
+                    .fields
+                    .iter()
+                    .map(|field| {
+                        Ok((field.value.ident.value.clone(), self.lower_expr(&field.value.value)?))
+                    })
+                    .collect::<Result<_, FatalError>>()?,
+            }),
+            Expr::Closure(closure) => ExprKind::Closure(self.lower_closure(closure)?),
+            Expr::FieldAccess(access) => ExprKind::FieldAccess(FieldAccess {
+                expr: self.lower_expr(&access.value.expr)?.into(),
+                field: access.value.field.value.clone(),
+                optional: access.value.optional,
+            }),
+        };
+        Ok(fluxc_hir::Expr { kind, ty: self.type_of(&expr.span), span: expr.span.clone() })
+    }
+
+    /// Lower a list of expressions.
+    fn lower_exprs(&mut self, exprs: &[Node<Expr>]) -> Result<Vec<fluxc_hir::Expr>, FatalError> {
+        exprs.iter().map(|expr| self.lower_expr(expr)).collect()
+    }
+}
+
+/// Compute the type arguments a call to the given function is instantiated
+/// with, by matching the declared types of its arguments and return value
+/// against the types inferred for the call. Type parameters that cannot be
+/// matched are left to be inferred.
+fn type_args(func: &Function, args: &[fluxc_hir::Expr], ret: &TypeExpr) -> Vec<TypeExpr> {
+    let mut bindings = HashMap::new();
+    for (param, arg) in func.args.iter().zip(args) {
+        bind_type_params(&func.generics, &param.ty, &arg.ty, &mut bindings);
+    }
+    bind_type_params(&func.generics, &func.return_type, ret, &mut bindings);
+    func.generics.iter().map(|name| bindings.remove(name).unwrap_or(TypeExpr::Infer)).collect()
+}
+
+/// Bind the type parameters referred to by the declared type `param` to the
+/// corresponding parts of the concrete type `actual`.
+fn bind_type_params(
+    generics: &[String],
+    param: &TypeExpr,
+    actual: &TypeExpr,
+    bindings: &mut HashMap<String, TypeExpr>,
+) {
+    match (param, actual) {
+        (_, TypeExpr::Infer) => (),
+        (TypeExpr::Primitive(Primitive::Ref(name)), ty) if generics.contains(name) => {
+            bindings.entry(name.clone()).or_insert_with(|| ty.clone());
+        }
+        (
+            TypeExpr::Operation(Operation::Array(param, _)),
+            TypeExpr::Operation(Operation::Array(actual, _)),
+        ) => bind_type_params(generics, param, actual, bindings),
+        (
+            TypeExpr::Primitive(Primitive::Tuple(params)),
+            TypeExpr::Primitive(Primitive::Tuple(actuals)),
+        )
+        | (TypeExpr::Generic(_, params), TypeExpr::Generic(_, actuals)) => {
+            for (param, actual) in params.iter().zip(actuals) {
+                bind_type_params(generics, param, actual, bindings);
+            }
+        }
+        _ => (),
+    }
+}
@@ -0,0 +1,81 @@
+//! Defines the lexical scopes tracked while walking the AST.
+
+use std::ops::Range;
+
+use fluxc_hir::{LocalId, Variable};
+
+/// Enumeration of scope kinds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScopeKind {
+    /// The top-level scope of a module.
+    Module,
+    /// The scope holding the parameters of a function.
+    Function,
+    /// The scope of a block of statements.
+    Block,
+    /// The scope of the body of a loop.
+    Loop,
+}
+
+/// A variable bound in a scope.
+#[derive(Debug, Clone)]
+pub struct Binding {
+    /// The bound variable.
+    pub variable: Variable,
+    /// The span of the identifier the variable was declared with.
+    pub span: Range<usize>,
+    /// Whether the variable has been referred to since it was declared.
+    pub used: bool,
+}
+
+/// A symbol imported into a module.
+#[derive(Debug, Clone)]
+pub struct ImportBinding {
+    /// The name the symbol is imported as.
+    pub name: String,
+    /// The span of the imported symbol.
+    pub span: Range<usize>,
+    /// Whether the symbol has been referred to since it was imported.
+    pub used: bool,
+}
+
+/// A lexical scope, holding the variables declared in it.
+#[derive(Debug)]
+pub struct Scope {
+    /// The kind of this scope.
+    pub kind: ScopeKind,
+    /// The variables declared in this scope, in order of declaration. Later
+    /// bindings shadow earlier bindings of the same name.
+    pub bindings: Vec<Binding>,
+    /// The symbols imported into this scope.
+    pub imports: Vec<ImportBinding>,
+    /// The variables declared outside of this scope that it refers to. Only
+    /// function scopes capture variables, and variables declared at the top
+    /// level of a module are never captured.
+    pub captures: Vec<LocalId>,
+}
+
+impl Scope {
+    /// Creates a new, empty scope of the given kind.
+    pub fn new(kind: ScopeKind) -> Scope {
+        Scope { kind, bindings: vec![], imports: vec![], captures: vec![] }
+    }
+
+    /// Lookup the innermost binding of the given name in this scope.
+    pub fn lookup(&self, name: &str) -> Option<&Binding> {
+        self.bindings.iter().rev().find(|b| b.variable.name == name)
+    }
+
+    /// Lookup the innermost binding of the given name in this scope,
+    /// returning a mutable reference to the binding.
+    pub fn lookup_mut(&mut self, name: &str) -> Option<&mut Binding> {
+        self.bindings.iter_mut().rev().find(|b| b.variable.name == name)
+    }
+
+    /// Record that this scope captures the variable with the given ID.
+    pub fn capture(&mut self, id: LocalId) {
+        if !self.captures.contains(&id) {
+            self.captures.push(id);
+        }
+    }
+}
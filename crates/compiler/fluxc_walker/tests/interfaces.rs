@@ -0,0 +1,160 @@
+use std::rc::Rc;
+
+use fluxc_ast::{Primitive, Stmt, TypeExpr, AST};
+use fluxc_errors::{ErrorKind, FatalError};
+use fluxc_hir::{FunctionKind, Module, Stmt as HirStmt};
+use fluxc_lexer::lex;
+use fluxc_parser::parse;
+use fluxc_span::SpanContext;
+use fluxc_walker::Walker;
+
+const COMPARABLE: &str = r#"
+interface Comparable {
+    compare self, other: Self -> int
+
+    equals self, other: Self -> bool {
+        return false
+    }
+}
+"#;
+
+/// Parse the given source, and declare all of its interfaces and
+/// implementations.
+fn walk(src: &str) -> Result<Walker, FatalError> {
+    let src = format!("{}{}", COMPARABLE, src);
+    let ast: AST = parse(lex(&src).expect("Lexing failed!")).expect("Parsing failed!");
+    let mut walker = Walker::new(Rc::new(SpanContext { source: src, path: "test.flx".into() }));
+    for stmt in &ast.stmts {
+        match &stmt.value {
+            Stmt::InterfaceDecl(decl) => walker.declare_interface(&decl.value)?,
+            Stmt::ImplDecl(decl) => walker.declare_impl(&decl.value)?,
+            _ => (),
+        }
+    }
+    Ok(walker)
+}
+
+#[test]
+fn test_impl_inherits_defaults() {
+    let walker = walk(
+        r#"
+impl Comparable for Point {
+    compare self, other: Point -> int {
+        return 0
+    }
+}
+"#,
+    )
+    .unwrap();
+    let compare = walker.lookup_impl_method("Point", "compare").unwrap();
+    assert_eq!(compare.kind, FunctionKind::Method);
+    let equals = walker.lookup_impl_method("Point", "equals").unwrap();
+    assert_eq!(equals.kind, FunctionKind::Default);
+    assert!(walker.lookup_impl("Comparable", "Point").is_some());
+}
+
+#[test]
+fn test_impl_missing_method() {
+    let err = walk("impl Comparable for Point {}").err().unwrap();
+    assert!(
+        matches!(err.kind, ErrorKind::E0201(ty, method, _) if ty == "Point" && method == "compare")
+    );
+}
+
+#[test]
+fn test_impl_incompatible_method() {
+    let err = walk(
+        r#"
+impl Comparable for Point {
+    compare self, other: int -> int {
+        return 0
+    }
+}
+"#,
+    )
+    .err()
+    .unwrap();
+    assert!(matches!(err.kind, ErrorKind::E0203(method, _) if method == "compare"));
+    assert_eq!(err.span.as_str(), "compare");
+}
+
+#[test]
+fn test_impl_unknown_interface() {
+    let err = walk("impl Hashable for Point {}").err().unwrap();
+    assert!(matches!(err.kind, ErrorKind::E0200(name) if name == "Hashable"));
+}
+
+/// Parse the given source and lower it into HIR.
+fn lower(src: &str) -> Result<Module, FatalError> {
+    let ast = parse(lex(src).expect("Lexing failed!")).expect("Parsing failed!");
+    let mut walker =
+        Walker::new(Rc::new(SpanContext { source: src.into(), path: "test.flx".into() }));
+    walker.walk(&ast)
+}
+
+#[test]
+fn test_walk_inherited_self_default() {
+    let module = lower(
+        r#"
+interface Sized {
+    size self -> int
+
+    total self, other: Self -> int {
+        let size = self.size ()
+        let other_size = other.size ()
+        return size + other_size
+    }
+}
+struct Square { side: int }
+impl Sized for Square {
+    size self -> int {
+        return self.side * self.side
+    }
+}
+let a = Square { side: 2 }
+let b = Square { side: 3 }
+let c = a.total b
+"#,
+    )
+    .unwrap();
+    // the inherited default takes the implementing type in place of `Self`
+    let total = module.impls[0].methods.iter().find(|m| m.name == "total").unwrap();
+    assert_eq!(total.kind, FunctionKind::Default);
+    assert_eq!(total.args[1].ty, TypeExpr::Primitive(Primitive::Ref("Square".into())));
+}
+
+#[test]
+fn test_walk_comparable_example() {
+    // the `Comparable` example from the language documentation
+    let module = lower(
+        r#"
+struct Point { x: int, y: int }
+
+interface Comparable {
+    compare self, other: Self -> int
+
+    equals self, other: Self -> bool {
+        return self.compare other == 0
+    }
+}
+
+impl Comparable for Point {
+    compare self, other: Point -> int {
+        return self.x - other.x
+    }
+}
+
+let a = Point { x: 1, y: 2 }
+let b = Point { x: 1, y: 3 }
+let same = a.equals b
+"#,
+    )
+    .unwrap();
+    let methods: Vec<_> =
+        module.impls[0].methods.iter().map(|m| (m.name.as_str(), m.kind.clone())).collect();
+    assert_eq!(methods, vec![("compare", FunctionKind::Method), ("equals", FunctionKind::Default)]);
+    let Some(HirStmt::Declaration(same)) = module.stmts.last() else {
+        panic!("expected a declaration")
+    };
+    assert_eq!(same.variable.ty, TypeExpr::Primitive(Primitive::Bool));
+}
@@ -0,0 +1,608 @@
+use std::rc::Rc;
+
+use fluxc_ast::{Literal, Primitive, TypeExpr, UnaryOp};
+use fluxc_errors::{ErrorKind, FatalError, WarningKind};
+use fluxc_hir::{Dispatch, ExprKind, Module, Stmt};
+use fluxc_lexer::lex;
+use fluxc_parser::parse;
+use fluxc_span::SpanContext;
+use fluxc_walker::Walker;
+
+/// Parse the given source and lower it into HIR.
+fn walk(src: &str) -> Result<Module, FatalError> {
+    let ast = parse(lex(src).expect("Lexing failed!")).expect("Parsing failed!");
+    let mut walker =
+        Walker::new(Rc::new(SpanContext { source: src.into(), path: "test.flx".into() }));
+    walker.walk(&ast)
+}
+
+/// Parse the given source and lower it into HIR, returning the warnings raised.
+fn warnings(src: &str) -> Vec<WarningKind> {
+    let ast = parse(lex(src).expect("Lexing failed!")).expect("Parsing failed!");
+    let mut walker =
+        Walker::new(Rc::new(SpanContext { source: src.into(), path: "test.flx".into() }));
+    walker.walk(&ast).unwrap();
+    walker.warnings().iter().map(|warning| warning.kind.clone()).collect()
+}
+
+#[test]
+fn test_walk_resolves_locals() {
+    let module = walk("let x = 1\nlet y = x").unwrap();
+    let Stmt::Declaration(x) = &module.stmts[0] else { panic!("expected a declaration") };
+    let Stmt::Declaration(y) = &module.stmts[1] else { panic!("expected a declaration") };
+    assert_eq!(x.variable.ty, TypeExpr::Primitive(Primitive::Int));
+    assert_eq!(y.value.kind, ExprKind::Local(x.variable.id));
+    assert_eq!(y.value.ty, TypeExpr::Primitive(Primitive::Int));
+}
+
+#[test]
+fn test_walk_shadowed_locals() {
+    let module = walk("let x = 1\nlet x = \"x\"\nlet y = x").unwrap();
+    let Stmt::Declaration(x) = &module.stmts[1] else { panic!("expected a declaration") };
+    let Stmt::Declaration(y) = &module.stmts[2] else { panic!("expected a declaration") };
+    assert_eq!(y.value.kind, ExprKind::Local(x.variable.id));
+    assert_eq!(y.variable.ty, TypeExpr::Primitive(Primitive::String));
+}
+
+#[test]
+fn test_walk_functions() {
+    let module = walk(
+        r#"
+add a, b -> int {
+    return a + b
+}
+let x = add 1, 2
+"#,
+    )
+    .unwrap();
+    let add = &module.functions[0];
+    // unannotated parameters take on their inferred types
+    assert!(add.args.iter().all(|arg| arg.ty == TypeExpr::Primitive(Primitive::Int)));
+    let body = add.body.as_ref().unwrap();
+    assert_eq!(body.params.len(), 2);
+    let Stmt::Declaration(x) = &module.stmts[0] else { panic!("expected a declaration") };
+    let ExprKind::Call(call) = &x.value.kind else { panic!("expected a call") };
+    assert_eq!(call.callee.kind, ExprKind::Function("add".into()));
+}
+
+#[test]
+fn test_walk_nullary_calls() {
+    let module = walk(
+        r#"
+class Counter {
+    count: int
+
+    get self -> int {
+        return self.count
+    }
+}
+answer -> int {
+    return 42
+}
+let x = answer ()
+let c = Counter { count: 1 }
+let y = c.get ()
+"#,
+    );
+    // `()` calls a function without any arguments
+    let module = module.unwrap();
+    let Stmt::Declaration(x) = &module.stmts[0] else { panic!("expected a declaration") };
+    let ExprKind::Call(call) = &x.value.kind else { panic!("expected a call") };
+    assert!(call.args.is_empty());
+    assert_eq!(x.variable.ty, TypeExpr::Primitive(Primitive::Int));
+    let Stmt::Declaration(y) = &module.stmts[2] else { panic!("expected a declaration") };
+    assert_eq!(y.variable.ty, TypeExpr::Primitive(Primitive::Int));
+}
+
+#[test]
+fn test_walk_static_method_calls() {
+    // the classes example from the parser tests
+    let module = walk(include_str!("../../fluxc_parser/tests/classes.flx")).unwrap();
+    let Stmt::Declaration(counter) = &module.stmts[0] else { panic!("expected a declaration") };
+    let ExprKind::Call(call) = &counter.value.kind else { panic!("expected a call") };
+    let counter_ty = TypeExpr::Primitive(Primitive::Ref("Counter".into()));
+    assert_eq!(call.callee.kind, ExprKind::Method(counter_ty.clone(), "new".into()));
+    assert_eq!(counter.variable.ty, counter_ty);
+
+    // methods taking `self` need a value to be called on
+    let err = walk(
+        "class Counter {\n    count: int\n\n    add mut self, n: int -> {\n        self.count += n\n    }\n}\nCounter.add 1",
+    )
+    .err()
+    .unwrap();
+    assert!(matches!(err.kind, ErrorKind::E0228(method, ty) if method == "add" && ty == "Counter"));
+}
+
+#[test]
+fn test_walk_statements_after_values() {
+    // values at the end of a line are not called with the next statement
+    let module = walk("struct P { x: int }\nmut p = P { x: 1 }\np.x = 3").unwrap();
+    assert_eq!(module.stmts.len(), 2);
+}
+
+#[test]
+fn test_walk_method_dispatch() {
+    let module = walk(
+        r#"
+interface Shape {
+    scale self, n: int -> int
+}
+struct Square { size: int }
+impl Shape for Square {
+    scale self, n: int -> int {
+        return self.size + n
+    }
+}
+measure shape: Shape -> int {
+    return shape.scale 2
+}
+let square = Square { size: 2 }
+let scaled = square.scale 2
+"#,
+    )
+    .unwrap();
+    let Stmt::Declaration(scaled) = &module.stmts[1] else { panic!("expected a declaration") };
+    let ExprKind::MethodCall(call) = &scaled.value.kind else { panic!("expected a method call") };
+    assert_eq!(call.dispatch, Dispatch::Static("Square".into()));
+    let body = module.functions[0].body.as_ref().unwrap();
+    let Stmt::Return(value) = &body.block.stmts[0] else { panic!("expected a return") };
+    let ExprKind::MethodCall(call) = &value.kind else { panic!("expected a method call") };
+    assert_eq!(call.dispatch, Dispatch::Dynamic("Shape".into()));
+}
+
+#[test]
+fn test_walk_duplicate_function() {
+    let err = walk("f -> {}\nf -> {}").err().unwrap();
+    assert!(matches!(err.kind, ErrorKind::E0210(name) if name == "f"));
+}
+
+#[test]
+fn test_walk_undeclared_type() {
+    let err = walk("struct Line { start: Point }").err().unwrap();
+    assert!(matches!(err.kind, ErrorKind::E0209(name) if name == "Point"));
+}
+
+#[test]
+fn test_walk_block_scopes() {
+    let module = walk("let x = 1\nlet y = {\n    let x = \"x\"\n    x\n}\nlet z = x").unwrap();
+    let Stmt::Declaration(x) = &module.stmts[0] else { panic!("expected a declaration") };
+    let Stmt::Declaration(z) = &module.stmts[2] else { panic!("expected a declaration") };
+    // the shadowing variable is dropped at the end of the block
+    assert_eq!(z.value.kind, ExprKind::Local(x.variable.id));
+    assert_eq!(z.value.ty, TypeExpr::Primitive(Primitive::Int));
+
+    let err = walk("let y = {\n    let x = 1\n    x\n}\nlet z = x").err().unwrap();
+    assert!(matches!(err.kind, ErrorKind::E0205(name) if name == "x"));
+}
+
+#[test]
+fn test_walk_unused_variables() {
+    let warnings = warnings(
+        r#"
+f a: int, b: int -> int {
+    let unused = 1
+    let _ignored = 2
+    return a
+}
+let top = 1
+"#,
+    );
+    assert_eq!(warnings.len(), 2);
+    assert!(matches!(&warnings[0], WarningKind::W0001(name) if name == "unused"));
+    assert!(matches!(&warnings[1], WarningKind::W0001(name) if name == "b"));
+}
+
+#[test]
+fn test_walk_unused_imports() {
+    let warnings = warnings("import { sqrt, pow as power } from \"math\"");
+    assert_eq!(warnings.len(), 2);
+    assert!(matches!(&warnings[0], WarningKind::W0002(name) if name == "sqrt"));
+    assert!(matches!(&warnings[1], WarningKind::W0002(name) if name == "power"));
+}
+
+#[test]
+fn test_walk_mutable_bindings() {
+    walk("mut x = 1\nx = 2\nx += 1\nx++\nlet y = &mut x").unwrap();
+    walk("f x: mut int -> {\n    x += 1\n}").unwrap();
+}
+
+#[test]
+fn test_walk_immutable_bindings() {
+    let err = walk("let x = 1\nx = 2").err().unwrap();
+    assert!(matches!(&err.kind, ErrorKind::E0211(name) if name == "x"));
+    assert_eq!(err.span.as_str(), "x = 2");
+    // the error relates to the declaration of the variable
+    assert_eq!(err.related[0].as_str(), "x");
+    assert_eq!(err.related[0].start(), 4);
+
+    let err = walk("const C = 1\nC += 1").err().unwrap();
+    assert!(matches!(&err.kind, ErrorKind::E0212(name) if name == "C"));
+    let err = walk("let x = 1\nx++").err().unwrap();
+    assert!(matches!(&err.kind, ErrorKind::E0211(name) if name == "x"));
+    let err = walk("let x = 1\nlet y = &mut x").err().unwrap();
+    assert!(matches!(&err.kind, ErrorKind::E0211(name) if name == "x"));
+    let err = walk("f x: int -> {\n    x += 1\n}").err().unwrap();
+    assert!(matches!(&err.kind, ErrorKind::E0211(name) if name == "x"));
+}
+
+#[test]
+fn test_walk_immutable_receiver() {
+    let err = walk(
+        r#"
+class Counter {
+    count: int
+
+    add self, n: int -> {
+        self.count += n
+    }
+}
+"#,
+    )
+    .err()
+    .unwrap();
+    assert!(matches!(&err.kind, ErrorKind::E0211(name) if name == "self"));
+    assert_eq!(err.span.as_str(), "self.count += n");
+}
+
+#[test]
+fn test_walk_const_evaluation() {
+    let module = walk(
+        r#"
+const fact n: int -> int {
+    if n == 0 {
+        return 1
+    }
+    return n * (fact (n - 1))
+}
+const C = 299_792_458
+const DISTANCE = C * 4 + (fact 5)
+const GREETING = "hello, " + "world"
+const SAME = DISTANCE == 1199169952 && GREETING != "hello"
+"#,
+    )
+    .unwrap();
+    let values = module
+        .stmts
+        .iter()
+        .map(|stmt| match stmt {
+            Stmt::Declaration(decl) => decl.variable.ty.clone(),
+            _ => panic!("expected a declaration"),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        values,
+        vec![
+            TypeExpr::Primitive(Primitive::IntLiteral(299_792_458)),
+            TypeExpr::Primitive(Primitive::IntLiteral(1_199_169_952)),
+            TypeExpr::Primitive(Primitive::StringLiteral("hello, world".into())),
+            TypeExpr::Primitive(Primitive::True),
+        ]
+    );
+    // the initializer is folded into its value
+    let Stmt::Declaration(distance) = &module.stmts[1] else { panic!("expected a declaration") };
+    assert_eq!(distance.value.kind, ExprKind::Literal(Literal::Int(1_199_169_952)));
+}
+
+#[test]
+fn test_walk_const_comparisons() {
+    let module = walk(
+        "const LIMIT = 10\nconst BELOW = 2 * 4 < LIMIT\nconst ABOVE = \"b\" > \"c\"\nconst AT_MOST = 1.5 <= 1.5 && LIMIT >= 11",
+    )
+    .unwrap();
+    let values = module
+        .stmts
+        .iter()
+        .skip(1)
+        .map(|stmt| match stmt {
+            Stmt::Declaration(decl) => decl.value.kind.clone(),
+            _ => panic!("expected a declaration"),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        values,
+        vec![
+            ExprKind::Literal(Literal::Bool(true)),
+            ExprKind::Literal(Literal::Bool(false)),
+            ExprKind::Literal(Literal::Bool(false)),
+        ]
+    );
+}
+
+#[test]
+fn test_walk_const_logical_precedence() {
+    let module =
+        walk("const EITHER = true || false && false\nconst BOTH = false && true || true").unwrap();
+    for stmt in &module.stmts {
+        let Stmt::Declaration(decl) = stmt else { panic!("expected a declaration") };
+        assert_eq!(decl.value.kind, ExprKind::Literal(Literal::Bool(true)));
+    }
+}
+
+#[test]
+fn test_walk_const_casts() {
+    let module = walk("const HALF = 3 as float / 2.0\nconst FLAG = true as int + 1").unwrap();
+    let values = module
+        .stmts
+        .iter()
+        .map(|stmt| match stmt {
+            Stmt::Declaration(decl) => decl.variable.ty.clone(),
+            _ => panic!("expected a declaration"),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        values,
+        vec![
+            TypeExpr::Primitive(Primitive::FloatLiteral(1.5)),
+            TypeExpr::Primitive(Primitive::IntLiteral(2)),
+        ]
+    );
+}
+
+#[test]
+fn test_walk_const_not_evaluable() {
+    let err = walk("let x = 1\nconst C = x + 1").err().unwrap();
+    assert!(matches!(&err.kind, ErrorKind::E0213(name) if name == "C"));
+    assert_eq!(err.span.as_str(), "x");
+
+    let err = walk("f n: int -> int {\n    return n\n}\nconst C = f 1").err().unwrap();
+    assert!(matches!(&err.kind, ErrorKind::E0213(name) if name == "C"));
+
+    let err = walk("const C = 1 / (1 - 1)").err().unwrap();
+    assert!(matches!(&err.kind, ErrorKind::E0214(name, _) if name == "C"));
+}
+
+#[test]
+fn test_walk_display_signatures() {
+    let module = walk(
+        "const C = 1 + 2\nlet x: int | \"a\" = 1\nadd a: int, b: int -> int {\n    return a + b\n}",
+    )
+    .unwrap();
+    let variables = module
+        .stmts
+        .iter()
+        .map(|stmt| match stmt {
+            Stmt::Declaration(decl) => decl.variable.to_string(),
+            _ => panic!("expected a declaration"),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(variables, vec!["const C: 3", "let x: int | \"a\""]);
+    assert_eq!(module.functions[0].to_string(), "add a: int, b: int -> int");
+}
+
+#[test]
+fn test_walk_type_aliases() {
+    walk("type List = () | (int, List)\ntype Nums = List | float").unwrap();
+
+    let err = walk("type A = A | int").err().unwrap();
+    assert!(matches!(&err.kind, ErrorKind::E0215(name) if name == "A"));
+    assert_eq!(err.span.as_str(), "A");
+
+    let err = walk("type A = (int, A)").err().unwrap();
+    assert!(matches!(&err.kind, ErrorKind::E0216(name) if name == "A"));
+
+    let err = walk("type A = Missing").err().unwrap();
+    assert!(matches!(&err.kind, ErrorKind::E0209(name) if name == "Missing"));
+
+    let err = walk("struct A { x: int }\ntype A = int").err().unwrap();
+    assert!(matches!(&err.kind, ErrorKind::E0210(name) if name == "A"));
+}
+
+#[test]
+fn test_walk_generics() {
+    let module = walk("id<T> x: T -> T {\n    return x\n}\nlet a = id 1").unwrap();
+    assert_eq!(module.functions[0].to_string(), "id<T> x: T -> T");
+    let Stmt::Declaration(decl) = &module.stmts[0] else { panic!("expected a declaration") };
+    let ExprKind::Call(call) = &decl.value.kind else { panic!("expected a call") };
+    assert_eq!(call.type_args, vec![TypeExpr::Primitive(Primitive::Int)]);
+
+    let err = walk("struct Box<T> { value: T }\ntype B = Box").err().unwrap();
+    assert!(matches!(&err.kind, ErrorKind::E0218(name, 1, 0) if name == "Box"));
+
+    let err = walk("type Pair<A, B> = (A, B)\ntype P = Pair<int>").err().unwrap();
+    assert!(matches!(&err.kind, ErrorKind::E0218(name, 2, 1) if name == "Pair"));
+
+    let err = walk("id<T> x: U -> T {\n    return x\n}").err().unwrap();
+    assert!(matches!(&err.kind, ErrorKind::E0209(name) if name == "U"));
+}
+
+#[test]
+fn test_walk_conditional_types() {
+    walk("type First<T> = T extends (infer A, infer B) ? A : never\ntype Keys = keyof (int, int)")
+        .unwrap();
+
+    let err = walk("type A = infer T").err().unwrap();
+    assert!(matches!(&err.kind, ErrorKind::E0219(name) if name == "T"));
+
+    let err = walk("type First<T> = T extends (infer A,) ? A : B").err().unwrap();
+    assert!(matches!(&err.kind, ErrorKind::E0209(name) if name == "B"));
+}
+
+#[test]
+fn test_walk_closures() {
+    let module = walk(
+        r#"
+make_adder n: int -> (int) -> int {
+    let offset = 1
+    return (x) -> { x + n + offset }
+}
+let add = make_adder 2
+let y = add 3
+"#,
+    )
+    .unwrap();
+    let make_adder = &module.functions[0];
+    let body = make_adder.body.as_ref().unwrap();
+    let Stmt::Return(value) = &body.block.stmts[1] else { panic!("expected a return") };
+    let ExprKind::Closure(closure) = &value.kind else { panic!("expected a closure") };
+    assert_eq!(value.ty.to_string(), "(int) -> int");
+    assert_eq!(closure.function.args[0].ty, TypeExpr::Primitive(Primitive::Int));
+    // the closure captures the parameter and the local of the enclosing function
+    let captures: Vec<_> = closure.captures.iter().map(|capture| capture.name.as_str()).collect();
+    assert_eq!(captures, vec!["n", "offset"]);
+    assert_eq!(closure.function.body.as_ref().unwrap().captures.len(), 2);
+    // calls go through the local holding the closure
+    let Stmt::Declaration(y) = &module.stmts[1] else { panic!("expected a declaration") };
+    let ExprKind::Call(call) = &y.value.kind else { panic!("expected a call") };
+    assert!(matches!(call.callee.kind, ExprKind::Local(_)));
+    assert_eq!(y.variable.ty, TypeExpr::Primitive(Primitive::Int));
+}
+
+#[test]
+fn test_walk_captured_mutation() {
+    // captured variables are copied into the closure, so cannot be mutated
+    let err = walk("f -> {\n    mut x = 1\n    let g = () -> { x += 1 }\n}").err().unwrap();
+    assert!(matches!(&err.kind, ErrorKind::E0221(name) if name == "x"));
+    // but the variables declared by the closure can be
+    walk("let g = (x: mut int) -> { x += 1 }").unwrap();
+}
+
+#[test]
+fn test_walk_optionals() {
+    let module = walk(
+        r#"
+struct User { name: string }
+name user: User? -> string {
+    return user?.name ?? "anonymous"
+}
+const D = () ?? 5
+"#,
+    )
+    .unwrap();
+    let Stmt::Declaration(decl) = &module.stmts[0] else { panic!("expected a declaration") };
+    assert_eq!(decl.variable.ty, TypeExpr::Primitive(Primitive::IntLiteral(5)));
+    let body = &module.functions[0].body.as_ref().unwrap().block;
+    let Stmt::Return(value) = &body.stmts[0] else { panic!("expected a return") };
+    let ExprKind::BinaryExpr(coalesce) = &value.kind else {
+        panic!("expected a binary expression")
+    };
+    assert_eq!(coalesce.lhs.ty.to_string(), "unit | string");
+    assert!(matches!(&coalesce.lhs.kind, ExprKind::FieldAccess(access) if access.optional));
+}
+
+#[test]
+fn test_walk_error_propagation() {
+    let module = walk(
+        r#"
+parse n: int -> int | Error {
+    if n == 0 {
+        return Error { message: "zero" }
+    }
+    return n
+}
+half n: int -> int | Error {
+    let value = parse n?
+    return value / 2
+}
+"#,
+    )
+    .unwrap();
+    // the standard error type is declared in every module
+    assert_eq!(module.structs[0].name, "Error");
+    let body = &module.functions[1].body.as_ref().unwrap().block;
+    let Stmt::Declaration(decl) = &body.stmts[0] else { panic!("expected a declaration") };
+    assert!(
+        matches!(&decl.value.kind, ExprKind::UnaryExpr(unary) if unary.kind == UnaryOp::Propagate)
+    );
+    assert_eq!(decl.variable.ty, TypeExpr::Primitive(Primitive::Int));
+}
+
+#[test]
+fn test_walk_casts() {
+    let module = walk("let value: int | string = 1\nlet number = value as int").unwrap();
+    let Stmt::Declaration(decl) = &module.stmts[1] else { panic!("expected a declaration") };
+    // the target of the cast is the type of the cast expression
+    let ExprKind::Cast(cast) = &decl.value.kind else { panic!("expected a cast") };
+    assert_eq!(decl.value.ty, TypeExpr::Primitive(Primitive::Int));
+    assert_eq!(cast.expr.ty.to_string(), "int | string");
+}
+
+#[test]
+fn test_walk_sized_numbers() {
+    let module =
+        walk("let flags: u8 = 1\nconst BIG = 300\nconst WRAPPED = BIG as u8\nconst HALF = 0.5f32")
+            .unwrap();
+    // literals are lowered as values of the numeric type they are used as
+    let Stmt::Declaration(flags) = &module.stmts[0] else { panic!("expected a declaration") };
+    assert_eq!(flags.value.ty.to_string(), "u8");
+    // constants are converted as they would be at runtime
+    let values = module.stmts[2..]
+        .iter()
+        .map(|stmt| match stmt {
+            Stmt::Declaration(decl) => decl.variable.ty.clone(),
+            _ => panic!("expected a declaration"),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        values,
+        vec![
+            TypeExpr::Primitive(Primitive::IntLiteral(44)),
+            TypeExpr::Primitive(Primitive::FloatLiteral(0.5)),
+        ]
+    ); // unsigned literals above `i64::MAX` keep their type
+    let module = walk("let max = 18446744073709551615u64").unwrap();
+    let Stmt::Declaration(max) = &module.stmts[0] else { panic!("expected a declaration") };
+    assert_eq!(max.variable.ty.to_string(), "u64");
+}
+
+#[test]
+fn test_walk_literal_typed_values() {
+    let module = walk(
+        r#"
+struct P { x: int, y: int }
+type IsInt<T> = T extends int ? true : false
+let k: keyof P = "x"
+let a: IsInt<int> = true
+let b: IsInt<string> = false
+let s = "x"
+"#,
+    )
+    .unwrap();
+    // literals take on the literal types they are assigned to
+    let types: Vec<_> = module
+        .stmts
+        .iter()
+        .map(|stmt| match stmt {
+            Stmt::Declaration(decl) => decl.value.ty.to_string(),
+            _ => panic!("expected a declaration"),
+        })
+        .collect();
+    assert_eq!(types, vec!["\"x\"", "true", "false", "string"]);
+
+    let err = walk("struct P { x: int, y: int }\nlet k: keyof P = \"z\"").err().unwrap();
+    assert!(matches!(&err.kind, ErrorKind::E0204(..)));
+    let err = walk("type IsInt<T> = T extends int ? true : false\nlet a: IsInt<int> = false")
+        .err()
+        .unwrap();
+    assert!(matches!(&err.kind, ErrorKind::E0204(..)));
+}
+
+#[test]
+fn test_walk_int_literal_types() {
+    let module = walk(
+        r#"
+f x: 1 | 2 -> 1 | 2 {
+    return x
+}
+g -> 1 | 2 {
+    return 1
+}
+let y: 1 = 1
+let z: 1 | 2 = 2
+let w = f 1
+"#,
+    )
+    .unwrap();
+    // integer literals take on the literal types they are assigned to
+    let types: Vec<_> = module
+        .stmts
+        .iter()
+        .filter_map(|stmt| match stmt {
+            Stmt::Declaration(decl) => Some(decl.value.ty.to_string()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(types, vec!["1", "2", "1 | 2"]);
+
+    let err = walk("let y: 1 | 2 = 3").err().unwrap();
+    assert!(matches!(&err.kind, ErrorKind::E0204(..)));
+}
@@ -1,3 +1,8 @@
+use std::collections::HashMap;
+use std::ops::Range;
+
+use fluxc_types::infer::Inference;
+use tokio::sync::Mutex;
 use tower_lsp::jsonrpc::Result;
 use tower_lsp::lsp_types::*;
 use tower_lsp::{Client, LanguageServer, LspService, Server};
@@ -5,18 +10,104 @@ use tower_lsp::{Client, LanguageServer, LspService, Server};
 #[derive(Debug)]
 struct Backend {
     client: Client,
+    /// The text of the open documents.
+    documents: Mutex<HashMap<Url, String>>,
+}
+
+/// Convert a position in the given text into a byte offset. Columns of
+/// positions count UTF-16 code units, as the language server protocol
+/// requires.
+fn offset_of(text: &str, position: Position) -> Option<usize> {
+    let start = match position.line {
+        0 => 0,
+        line => text.match_indices('\n').nth(line as usize - 1)?.0 + 1,
+    };
+    let line = &text[start..];
+    let column = line
+        .char_indices()
+        .scan(0, |units, (offset, c)| {
+            let column = *units;
+            *units += c.len_utf16();
+            Some((offset, column))
+        })
+        .find(|(_, column)| *column >= position.character as usize)
+        .map_or(line.len(), |(offset, _)| offset);
+    Some(start + column)
+}
+
+/// Convert a byte offset in the given text into a position, whose column
+/// counts UTF-16 code units.
+fn position_of(text: &str, offset: usize) -> Position {
+    let before = &text[..offset];
+    let line = before.matches('\n').count();
+    let start = before.rfind('\n').map_or(0, |i| i + 1);
+    Position::new(line as u32, before[start..].encode_utf16().count() as u32)
+}
+
+/// Infer the type of the innermost expression at the given offset.
+fn type_at(text: &str, offset: usize) -> Option<(Range<usize>, String)> {
+    let ast = fluxc_parser::parse(fluxc_lexer::lex(text).ok()?).ok()?;
+    let mut inference = Inference::new();
+    inference.infer_ast(&ast).ok()?;
+    inference.type_at(offset).map(|(span, ty)| (span, ty.to_string()))
 }
 
 #[tower_lsp::async_trait]
 impl LanguageServer for Backend {
     async fn initialize(&self, _: InitializeParams) -> Result<InitializeResult> {
-        Ok(InitializeResult::default())
+        Ok(InitializeResult {
+            capabilities: ServerCapabilities {
+                text_document_sync: Some(TextDocumentSyncKind::FULL.into()),
+                hover_provider: Some(HoverProviderCapability::Simple(true)),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
     }
 
     async fn initialized(&self, _: InitializedParams) {
         self.client.log_message(MessageType::INFO, "server initialized!").await;
     }
 
+    async fn did_open(&self, params: DidOpenTextDocumentParams) {
+        let document = params.text_document;
+        self.documents.lock().await.insert(document.uri, document.text);
+    }
+
+    async fn did_change(&self, mut params: DidChangeTextDocumentParams) {
+        // documents are synchronised in full, so the last change holds the
+        // entire text
+        if let Some(change) = params.content_changes.pop() {
+            self.documents.lock().await.insert(params.text_document.uri, change.text);
+        }
+    }
+
+    async fn did_close(&self, params: DidCloseTextDocumentParams) {
+        self.documents.lock().await.remove(&params.text_document.uri);
+    }
+
+    async fn hover(&self, params: HoverParams) -> Result<Option<Hover>> {
+        let position = params.text_document_position_params;
+        let documents = self.documents.lock().await;
+        let text = match documents.get(&position.text_document.uri) {
+            Some(text) => text,
+            None => return Ok(None),
+        };
+        let hover = offset_of(text, position.position)
+            .and_then(|offset| type_at(text, offset))
+            .map(|(span, ty)| Hover {
+                contents: HoverContents::Markup(MarkupContent {
+                    kind: MarkupKind::Markdown,
+                    value: format!("```flux\n{}\n```", ty),
+                }),
+                range: Some(tower_lsp::lsp_types::Range::new(
+                    position_of(text, span.start),
+                    position_of(text, span.end),
+                )),
+            });
+        Ok(hover)
+    }
+
     async fn shutdown(&self) -> Result<()> {
         Ok(())
     }
@@ -27,6 +118,23 @@ async fn main() {
     let stdin = tokio::io::stdin();
     let stdout = tokio::io::stdout();
 
-    let (service, socket) = LspService::new(|client| Backend { client });
+    let (service, socket) =
+        LspService::new(|client| Backend { client, documents: Mutex::new(HashMap::new()) });
     Server::new(stdin, stdout, socket).serve(service).await;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn positions_count_utf16_code_units() {
+        // `é` is one UTF-16 code unit and two bytes, and `😀` is two UTF-16
+        // code units and four bytes
+        let text = "let a = 1\nlet é😀 = a";
+        let offset = text.rfind('a').unwrap();
+        let position = Position::new(1, 10);
+        assert_eq!(position_of(text, offset), position);
+        assert_eq!(offset_of(text, position), Some(offset));
+    }
+}